@@ -50,6 +50,12 @@ pub fn mk_attr_id(&self) -> AttrId {
         assert!(id != u32::MAX);
         AttrId::from_u32(id)
     }
+
+    /// Makes sure that the ids made from now on differ from the ones made by `other` so far, e.g.
+    /// for a session that reuses the attributes of a crate parsed in another one.
+    pub fn skip_ids_of(&self, other: &AttrIdGenerator) {
+        self.0.fetch_max(other.0.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
 }
 
 impl Attribute {
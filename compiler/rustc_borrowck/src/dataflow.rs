@@ -249,8 +249,9 @@ fn new(body: &'mir Body<'tcx>, regioncx: &'mir RegionInferenceContext<'tcx>) ->
 
 impl<'tcx> PoloniusOutOfScopePrecomputer<'_, 'tcx> {
     /// Loans are in scope while they are live: whether they are contained within any live region.
-    /// In the location-insensitive analysis, a loan will be contained in a region if the issuing
-    /// region can reach it in the subset graph. So this is a reachability problem.
+    /// In the location-sensitive analysis, a loan is contained in a region at a point if the
+    /// issuing region can reach it at this point in the localized constraint graph. This is
+    /// precomputed in `polonius::loan_liveness`, so this is only a walk over the CFG.
     fn precompute_loans_out_of_scope(
         &mut self,
         loan_idx: BorrowIndex,
@@ -263,7 +264,7 @@ fn precompute_loans_out_of_scope(
         // We first handle the cases where the loan doesn't go out of scope, depending on the issuing
         // region's successors.
         for successor in graph::depth_first_search(&self.regioncx.region_graph(), issuing_region) {
-            // 1. Via applied member constraints
+            // Via applied member constraints
             //
             // The issuing region can flow into the choice regions, and they are either:
             // - placeholders or free regions themselves,
@@ -282,13 +283,9 @@ fn precompute_loans_out_of_scope(
                 }
             }
 
-            // 2. Via regions that are live at all points: placeholders and free regions.
-            //
-            // If the issuing region outlives such a region, its loan escapes the function and
-            // cannot go out of scope. We can early return.
-            if self.regioncx.is_region_live_at_all_points(successor) {
-                return;
-            }
+            // Note that flowing into regions that are live at all points, like placeholders and
+            // free regions, does not make the loan escape the function: this only happens at the
+            // points where it flows into them, and the localized constraint graph accounts for it.
         }
 
         let first_block = loan_issued_at.block;
@@ -370,12 +367,6 @@ fn loan_kill_location(
 
             // - the loan goes out of scope at `location` if it's not contained within any regions
             // live at this point.
-            //
-            // FIXME: if the issuing region `i` can reach a live region `r` at point `p`, and `r` is
-            // live at point `q`, then it's guaranteed that `i` would reach `r` at point `q`.
-            // Reachability is location-insensitive, and we could take advantage of that, by jumping
-            // to a further point than just the next statement: we can jump to the furthest point
-            // within the block where `r` is live.
             if self.regioncx.is_loan_live_at(loan_idx, location) {
                 continue;
             }
@@ -396,33 +387,26 @@ pub fn new(
         regioncx: &'mir RegionInferenceContext<'tcx>,
         borrow_set: &'mir BorrowSet<'tcx>,
     ) -> Self {
-        let mut borrows_out_of_scope_at_location =
-            calculate_borrows_out_of_scope_at_location(body, regioncx, borrow_set);
-
         // The in-tree polonius analysis computes loans going out of scope using the set-of-loans
-        // model, and makes sure they're identical to the existing computation of the set-of-points
-        // model.
-        if tcx.sess.opts.unstable_opts.polonius.is_next_enabled() {
-            let mut polonius_prec = PoloniusOutOfScopePrecomputer::new(body, regioncx);
-            for (loan_idx, loan_data) in borrow_set.iter_enumerated() {
-                let issuing_region = loan_data.region;
-                let loan_issued_at = loan_data.reserve_location;
-
-                polonius_prec.precompute_loans_out_of_scope(
-                    loan_idx,
-                    issuing_region,
-                    loan_issued_at,
-                );
-            }
-
-            assert_eq!(
-                borrows_out_of_scope_at_location, polonius_prec.loans_out_of_scope_at_location,
-                "polonius loan scopes differ from NLL borrow scopes, for body {:?}",
-                body.span,
-            );
-
-            borrows_out_of_scope_at_location = polonius_prec.loans_out_of_scope_at_location;
-        }
+        // model and the location-sensitive loan liveness. It accepts more programs than the
+        // set-of-points model of NLLs, e.g. NLL problem case #3.
+        let borrows_out_of_scope_at_location =
+            if tcx.sess.opts.unstable_opts.polonius.is_next_enabled() {
+                let mut polonius_prec = PoloniusOutOfScopePrecomputer::new(body, regioncx);
+                for (loan_idx, loan_data) in borrow_set.iter_enumerated() {
+                    let issuing_region = loan_data.region;
+                    let loan_issued_at = loan_data.reserve_location;
+
+                    polonius_prec.precompute_loans_out_of_scope(
+                        loan_idx,
+                        issuing_region,
+                        loan_issued_at,
+                    );
+                }
+                polonius_prec.loans_out_of_scope_at_location
+            } else {
+                calculate_borrows_out_of_scope_at_location(body, regioncx, borrow_set)
+            };
 
         Borrows { tcx, body, borrow_set, borrows_out_of_scope_at_location }
     }
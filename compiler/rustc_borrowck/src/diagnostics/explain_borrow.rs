@@ -434,7 +434,22 @@ pub(crate) fn explain_why_borrow_contains_point(
         let borrow_region_vid = borrow.region;
         debug!(?borrow_region_vid);
 
-        let mut region_sub = self.regioncx.find_sub_region_live_at(borrow_region_vid, location);
+        // When using `-Zpolonius=next`, the loan is only live where it flows into live regions, so
+        // we look for the region that contains it at this point, and for uses where it is live.
+        let polonius_loan = tcx
+            .sess
+            .opts
+            .unstable_opts
+            .polonius
+            .is_next_enabled()
+            .then(|| self.borrow_set.get_index_of(&borrow.reserve_location))
+            .flatten();
+
+        let mut region_sub = polonius_loan
+            .and_then(|_| {
+                regioncx.regions_containing_loan_at(body, borrow, location).into_iter().next()
+            })
+            .unwrap_or_else(|| self.regioncx.find_sub_region_live_at(borrow_region_vid, location));
         debug!(?region_sub);
 
         let mut use_location = location;
@@ -456,7 +471,7 @@ pub(crate) fn explain_why_borrow_contains_point(
             }
         }
 
-        match find_use::find(body, regioncx, tcx, region_sub, use_location) {
+        match find_use::find(body, regioncx, tcx, region_sub, use_location, polonius_loan) {
             Some(Cause::LiveVar(local, location)) => {
                 let span = body.source_info(location).span;
                 let spans = self
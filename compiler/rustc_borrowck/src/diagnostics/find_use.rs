@@ -2,6 +2,7 @@
 use std::rc::Rc;
 
 use crate::{
+    dataflow::BorrowIndex,
     def_use::{self, DefUse},
     region_infer::{Cause, RegionInferenceContext},
 };
@@ -10,14 +11,18 @@
 use rustc_middle::mir::{self, Body, Local, Location};
 use rustc_middle::ty::{RegionVid, TyCtxt};
 
+/// Finds a use of `region_vid` that is reachable from `start_point`, walking the points contained
+/// in the region. When using `-Zpolonius=next`, a `loan` is given and the points where it is live
+/// are walked instead: the region can contain points on paths where the loan is not live.
 pub(crate) fn find<'tcx>(
     body: &Body<'tcx>,
     regioncx: &Rc<RegionInferenceContext<'tcx>>,
     tcx: TyCtxt<'tcx>,
     region_vid: RegionVid,
     start_point: Location,
+    loan: Option<BorrowIndex>,
 ) -> Option<Cause> {
-    let mut uf = UseFinder { body, regioncx, tcx, region_vid, start_point };
+    let mut uf = UseFinder { body, regioncx, tcx, region_vid, start_point, loan };
 
     uf.find()
 }
@@ -28,6 +33,7 @@ struct UseFinder<'cx, 'tcx> {
     tcx: TyCtxt<'tcx>,
     region_vid: RegionVid,
     start_point: Location,
+    loan: Option<BorrowIndex>,
 }
 
 impl<'cx, 'tcx> UseFinder<'cx, 'tcx> {
@@ -37,7 +43,11 @@ fn find(&mut self) -> Option<Cause> {
 
         queue.push_back(self.start_point);
         while let Some(p) = queue.pop_front() {
-            if !self.regioncx.region_contains(self.region_vid, p) {
+            let contains_point = match self.loan {
+                Some(loan) => p == self.start_point || self.regioncx.is_loan_live_at(loan, p),
+                None => self.regioncx.region_contains(self.region_vid, p),
+            };
+            if !contains_point {
                 continue;
             }
 
@@ -94,6 +94,9 @@ pub fn is_empty(&self) -> bool {
     pub fn into_iter(self) -> impl Iterator<Item = (RegionErrorKind<'tcx>, ErrorGuaranteed)> {
         self.0.into_iter()
     }
+    pub fn iter(&self) -> impl Iterator<Item = &(RegionErrorKind<'tcx>, ErrorGuaranteed)> {
+        self.0.iter()
+    }
     pub fn has_errors(&self) -> Option<ErrorGuaranteed> {
         self.0.get(0).map(|x| x.1)
     }
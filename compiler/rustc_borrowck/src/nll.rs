@@ -122,8 +122,8 @@ pub(crate) fn compute_regions<'cx, 'tcx>(
     let var_origins = infcx.get_region_var_origins();
     let MirTypeckRegionConstraints {
         placeholder_indices,
-        placeholder_index_to_region: _,
-        liveness_constraints,
+        placeholder_index_to_region,
+        mut liveness_constraints,
         mut outlives_constraints,
         mut member_constraints,
         universe_causes,
@@ -153,6 +153,23 @@ pub(crate) fn compute_regions<'cx, 'tcx>(
         infcx.set_tainted_by_errors(guar);
     }
 
+    // When using `-Zpolonius=next`, compute the loans that are live at each point. Unlike with
+    // NLLs, where loans are in scope at all the points of the regions they flow into, this depends
+    // on where they flow into these regions.
+    if infcx.tcx.sess.opts.unstable_opts.polonius.is_next_enabled() {
+        liveness_constraints.loans = Some(polonius::compute_live_loans(
+            infcx.tcx,
+            body,
+            elements,
+            borrow_set,
+            &outlives_constraints,
+            &universal_regions,
+            placeholder_index_to_region.into_iter(),
+            &liveness_constraints,
+            infcx.num_region_vars(),
+        ));
+    }
+
     let mut regioncx = RegionInferenceContext::new(
         infcx,
         var_origins,
@@ -215,6 +232,7 @@ pub(super) fn dump_mir_results<'tcx>(
     body: &Body<'tcx>,
     regioncx: &RegionInferenceContext<'tcx>,
     closure_region_requirements: &Option<ClosureRegionRequirements<'tcx>>,
+    nll_errors: &RegionErrors<'tcx>,
 ) {
     if !dump_enabled(infcx.tcx, "nll", body.source.def_id()) {
         return;
@@ -258,6 +276,15 @@ pub(super) fn dump_mir_results<'tcx>(
         let mut file = create_dump_file(infcx.tcx, "regioncx.scc.dot", false, "nll", &0, body)?;
         regioncx.dump_graphviz_scc_constraints(&mut file)?;
     };
+
+    // If requested, also dump the constraint graph explaining the region errors.
+    if let Some(format) = infcx.tcx.sess.opts.unstable_opts.dump_mir_region_graph {
+        let _: io::Result<()> = try {
+            let extension = format!("regioncx.explain.{}", format.extension());
+            let mut file = create_dump_file(infcx.tcx, &extension, false, "nll", &0, body)?;
+            regioncx.dump_region_graph(infcx.tcx, body, nll_errors, format, &mut file)?;
+        };
+    }
 }
 
 #[allow(rustc::diagnostic_outside_of_impl)]
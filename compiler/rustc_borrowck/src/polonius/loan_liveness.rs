@@ -0,0 +1,159 @@
+//! The location-sensitive computation of live loans used by `-Zpolonius=next`.
+//!
+//! NLLs compute loan scopes from region values, which are sets of points: when a loan flows into a
+//! region that is live for a long time, e.g. because it is returned from the function on a single
+//! path, it is in scope at all the points of that region, including on unrelated paths. This is
+//! NLL problem case #3.
+//!
+//! Here, a loan instead flows through a localized constraint graph, whose nodes are a region at a
+//! given point:
+//! - an outlives constraint `sup: sub` that arises at point `p` is an edge `(sup, p) -> (sub, p)`,
+//!   and one that holds at all points is such an edge at every point.
+//! - a region `r` that is live at a successor `q` of point `p` in the CFG is an edge `(r, p) ->
+//!   (r, q)`: loans only flow along the CFG through the regions that are live there.
+//!
+//! A loan is live at a point `p` when a node `(r, p)` is reachable from its issuing region at the
+//! point it is issued, and `r` is live at `p`.
+
+use std::collections::VecDeque;
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_index::bit_set::{BitSet, SparseBitMatrix};
+use rustc_index::IndexVec;
+use rustc_middle::mir::Body;
+use rustc_middle::ty::RegionVid;
+use rustc_mir_dataflow::points::{DenseLocationMap, PointIndex};
+
+use crate::borrow_set::{BorrowData, BorrowSet};
+use crate::constraints::OutlivesConstraintSet;
+use crate::dataflow::BorrowIndex;
+use crate::region_infer::values::LivenessValues;
+use crate::type_check::Locations;
+
+/// The outlives constraints of a body, localized at the points where they hold.
+pub(crate) struct LocalizedOutlivesGraph {
+    /// For each region: the regions it outlives at all points.
+    outlives_everywhere: IndexVec<RegionVid, Vec<RegionVid>>,
+
+    /// For each point: the `sup: sub` constraints that arise there.
+    outlives_at: FxHashMap<PointIndex, Vec<(RegionVid, RegionVid)>>,
+
+    /// The regions that are live at all points: free regions and placeholders.
+    live_everywhere: BitSet<RegionVid>,
+}
+
+impl LocalizedOutlivesGraph {
+    pub(crate) fn new(
+        outlives_constraints: &OutlivesConstraintSet<'_>,
+        live_everywhere: BitSet<RegionVid>,
+        elements: &DenseLocationMap,
+    ) -> Self {
+        let num_regions = live_everywhere.domain_size();
+        let mut outlives_everywhere = IndexVec::from_elem_n(Vec::new(), num_regions);
+        let mut outlives_at: FxHashMap<_, Vec<_>> = FxHashMap::default();
+        for constraint in outlives_constraints.outlives() {
+            match constraint.locations {
+                Locations::All(_) => outlives_everywhere[constraint.sup].push(constraint.sub),
+                Locations::Single(location) => {
+                    let point = elements.point_from_location(location);
+                    outlives_at.entry(point).or_default().push((constraint.sup, constraint.sub));
+                }
+            }
+        }
+        LocalizedOutlivesGraph { outlives_everywhere, outlives_at, live_everywhere }
+    }
+
+    fn is_live_at(&self, liveness: &LivenessValues, region: RegionVid, point: PointIndex) -> bool {
+        self.live_everywhere.contains(region) || liveness.is_live_at_point(region, point)
+    }
+
+    /// Traverses the localized constraint graph from the issuing region of `loan`, at the point
+    /// where it is issued, and calls `visit` on each node where the region is live.
+    fn visit_live_nodes(
+        &self,
+        body: &Body<'_>,
+        elements: &DenseLocationMap,
+        liveness: &LivenessValues,
+        loan: &BorrowData<'_>,
+        mut visit: impl FnMut(RegionVid, PointIndex),
+    ) {
+        let start = (loan.region, elements.point_from_location(loan.reserve_location));
+        let mut visited = FxHashSet::default();
+        let mut queue = VecDeque::from([start]);
+        visited.insert(start);
+        while let Some((region, point)) = queue.pop_front() {
+            let mut enqueue = |node| {
+                if visited.insert(node) {
+                    queue.push_back(node);
+                }
+            };
+
+            for &sub in &self.outlives_everywhere[region] {
+                enqueue((sub, point));
+            }
+            if let Some(constraints) = self.outlives_at.get(&point) {
+                for &(sup, sub) in constraints {
+                    if sup == region {
+                        enqueue((sub, point));
+                    }
+                }
+            }
+
+            let location = elements.to_location(point);
+            let block_data = &body[location.block];
+            if location.statement_index < block_data.statements.len() {
+                let successor = elements.point_from_location(location.successor_within_block());
+                if self.is_live_at(liveness, region, successor) {
+                    enqueue((region, successor));
+                }
+            } else {
+                for block in block_data.terminator().successors() {
+                    let successor = elements.entry_point(block);
+                    if self.is_live_at(liveness, region, successor) {
+                        enqueue((region, successor));
+                    }
+                }
+            }
+
+            if self.is_live_at(liveness, region, point) {
+                visit(region, point);
+            }
+        }
+    }
+
+    /// Returns the regions that contain `loan` at `point`, i.e. that are live there and reachable
+    /// from the loan in the localized constraint graph.
+    pub(crate) fn regions_containing_loan_at(
+        &self,
+        body: &Body<'_>,
+        elements: &DenseLocationMap,
+        liveness: &LivenessValues,
+        loan: &BorrowData<'_>,
+        point: PointIndex,
+    ) -> Vec<RegionVid> {
+        let mut regions = vec![];
+        self.visit_live_nodes(body, elements, liveness, loan, |region, p| {
+            if p == point {
+                regions.push(region);
+            }
+        });
+        regions
+    }
+}
+
+/// Computes the loans that are live at each point of the CFG.
+pub(crate) fn compute_live_loans(
+    graph: &LocalizedOutlivesGraph,
+    body: &Body<'_>,
+    elements: &DenseLocationMap,
+    liveness: &LivenessValues,
+    borrow_set: &BorrowSet<'_>,
+) -> SparseBitMatrix<PointIndex, BorrowIndex> {
+    let mut live_loans = SparseBitMatrix::new(borrow_set.len());
+    for (loan_idx, loan) in borrow_set.iter_enumerated() {
+        graph.visit_live_nodes(body, elements, liveness, loan, |_, point| {
+            live_loans.insert(point, loan_idx);
+        });
+    }
+    live_loans
+}
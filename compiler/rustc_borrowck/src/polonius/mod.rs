@@ -1,20 +1,57 @@
-//! Functions dedicated to fact generation for the `-Zpolonius=legacy` datalog implementation.
-//!
-//! Will be removed in the future, once the in-tree `-Zpolonius=next` implementation reaches feature
-//! parity.
+//! Polonius-related code:
+//! - the location-sensitive loan liveness of the in-tree `-Zpolonius=next` implementation.
+//! - fact generation for the `-Zpolonius=legacy` datalog implementation. This will be removed in
+//! the future, once the in-tree implementation reaches feature parity.
 
+use rustc_index::bit_set::BitSet;
 use rustc_middle::mir::{Body, LocalKind, Location, START_BLOCK};
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{self, TyCtxt};
 use rustc_mir_dataflow::move_paths::{InitKind, InitLocation, MoveData};
+use rustc_mir_dataflow::points::DenseLocationMap;
 
 use crate::borrow_set::BorrowSet;
+use crate::constraints::OutlivesConstraintSet;
 use crate::facts::{AllFacts, PoloniusRegionVid};
 use crate::location::LocationTable;
+use crate::region_infer::values::{LiveLoans, LivenessValues};
 use crate::type_check::free_region_relations::UniversalRegionRelations;
 use crate::universal_regions::UniversalRegions;
 
 mod loan_invalidations;
 mod loan_kills;
+pub(crate) mod loan_liveness;
+
+/// When using `-Zpolonius=next`, computes the loans that are live at each point of the CFG, once
+/// the liveness of regions is known.
+pub(crate) fn compute_live_loans<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    elements: &DenseLocationMap,
+    borrow_set: &BorrowSet<'tcx>,
+    outlives_constraints: &OutlivesConstraintSet<'tcx>,
+    universal_regions: &UniversalRegions<'tcx>,
+    placeholder_regions: impl Iterator<Item = ty::Region<'tcx>>,
+    liveness: &LivenessValues,
+    num_region_vars: usize,
+) -> LiveLoans {
+    let _prof_timer = tcx.prof.generic_activity("polonius_loan_liveness");
+
+    // Free regions and placeholders are live at all points, but this is only recorded in the
+    // liveness values when region inference starts.
+    let mut live_everywhere = BitSet::new_empty(num_region_vars);
+    for region in universal_regions.universal_regions() {
+        live_everywhere.insert(region);
+    }
+    for placeholder in placeholder_regions {
+        live_everywhere.insert(placeholder.as_var());
+    }
+
+    let graph =
+        loan_liveness::LocalizedOutlivesGraph::new(outlives_constraints, live_everywhere, elements);
+    let live_loans =
+        loan_liveness::compute_live_loans(&graph, body, elements, liveness, borrow_set);
+    LiveLoans { graph, live_loans }
+}
 
 /// When requested, emit most of the facts needed by polonius:
 /// - moves and assignments
@@ -0,0 +1,349 @@
+//! With `-Zdump-mir-region-graph`, in addition to the `-Zdump-mir=nll` files, we render the region
+//! constraint graph of a body to help understand its borrowck errors, as a graphviz file or as a
+//! standalone HTML page. Compared to the raw graphviz dump, it relates regions and constraints to
+//! the source, shows where regions are live, and highlights the paths of constraints that cause
+//! the region errors: the ones that the diagnostics blame.
+
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_graphviz as dot;
+use rustc_infer::infer::{NllRegionVariableOrigin, RegionVariableOrigin};
+use rustc_middle::mir::{Body, Local, VarDebugInfoContents};
+use rustc_middle::ty::{RegionVid, TyCtxt};
+use rustc_session::config::RegionGraphFormat;
+use rustc_span::Span;
+
+use super::graphviz::render_region_vid;
+use super::{OutlivesConstraint, RegionInferenceContext};
+use crate::diagnostics::{RegionErrorKind, RegionErrors};
+use crate::type_check::Locations;
+
+/// A region of the body, described in terms of the source.
+struct RegionNode {
+    vid: RegionVid,
+    name: String,
+    /// Where the region comes from: the local whose type it appears in, or the span of its origin.
+    origin: Option<String>,
+    /// The points where the region is live.
+    liveness: String,
+}
+
+/// An outlives constraint, described in terms of the source.
+struct ConstraintEdge {
+    sup: RegionVid,
+    sub: RegionVid,
+    category: String,
+    location: String,
+    span: String,
+    /// Whether the constraint is on the path of a region error.
+    blamed: bool,
+}
+
+/// The path of constraints that explains a region error, as indices into the edges.
+struct ErrorPath {
+    description: String,
+    edges: Vec<usize>,
+}
+
+struct RegionGraph {
+    nodes: Vec<RegionNode>,
+    edges: Vec<ConstraintEdge>,
+    errors: Vec<ErrorPath>,
+}
+
+impl<'tcx> RegionInferenceContext<'tcx> {
+    /// Writes out the region constraint graph of `body`, with the constraint paths causing the
+    /// `errors`, in the given `format`.
+    pub(crate) fn dump_region_graph(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        body: &Body<'tcx>,
+        errors: &RegionErrors<'tcx>,
+        format: RegionGraphFormat,
+        mut w: &mut dyn Write,
+    ) -> io::Result<()> {
+        let graph = self.region_graph_for_dump(tcx, body, errors);
+        match format {
+            RegionGraphFormat::Graphviz => dot::render(&graph, &mut w),
+            RegionGraphFormat::Html => graph.write_html(w),
+        }
+    }
+
+    fn region_graph_for_dump(
+        &self,
+        tcx: TyCtxt<'tcx>,
+        body: &Body<'tcx>,
+        errors: &RegionErrors<'tcx>,
+    ) -> RegionGraph {
+        let source_map = tcx.sess.source_map();
+        let render_span = |span: Span| source_map.span_to_embeddable_string(span);
+
+        // Relate each region to the first local whose type mentions it.
+        let mut locals_per_region: FxIndexMap<RegionVid, Local> = FxIndexMap::default();
+        for (local, decl) in body.local_decls.iter_enumerated() {
+            tcx.for_each_free_region(&decl.ty, |r| {
+                locals_per_region.entry(r.as_var()).or_insert(local);
+            });
+        }
+        let local_names: FxIndexMap<Local, _> = body
+            .var_debug_info
+            .iter()
+            .filter_map(|info| match info.value {
+                VarDebugInfoContents::Place(place) => Some((place.as_local()?, info.name)),
+                VarDebugInfoContents::Const(_) => None,
+            })
+            .collect();
+
+        let nodes = self
+            .regions()
+            .map(|vid| {
+                let origin = if let Some(&local) = locals_per_region.get(&vid) {
+                    let decl = &body.local_decls[local];
+                    let name = match local_names.get(&local) {
+                        Some(name) => format!("`{name}`"),
+                        None => format!("{local:?}"),
+                    };
+                    Some(format!("in the type of {name} at {}", render_span(decl.source_info.span)))
+                } else {
+                    match self.var_infos[vid].origin {
+                        RegionVariableOrigin::Nll(_) => None,
+                        origin => Some(format!("from {}", render_span(origin.span()))),
+                    }
+                };
+                let liveness = match self.definitions[vid].origin {
+                    NllRegionVariableOrigin::FreeRegion
+                    | NllRegionVariableOrigin::Placeholder(_) => "all points".to_string(),
+                    NllRegionVariableOrigin::Existential { .. } => {
+                        self.liveness_constraints.pretty_print_live_points(vid)
+                    }
+                };
+                RegionNode { vid, name: render_region_vid(vid, self), origin, liveness }
+            })
+            .collect();
+
+        let edge = |constraint: &OutlivesConstraint<'tcx>| ConstraintEdge {
+            sup: constraint.sup,
+            sub: constraint.sub,
+            category: format!("{:?}", constraint.category),
+            location: match constraint.locations {
+                Locations::All(_) => "all points".to_string(),
+                Locations::Single(location) => format!("{location:?}"),
+            },
+            span: render_span(constraint.span),
+            blamed: false,
+        };
+        let mut constraints: Vec<OutlivesConstraint<'tcx>> =
+            self.constraints.outlives().iter().copied().collect();
+        let mut edges: Vec<ConstraintEdge> = constraints.iter().map(|c| edge(c)).collect();
+
+        // The paths of constraints that the region errors are reported for. They can contain
+        // constraints that are not part of the graph, e.g. the ones that come from `'static`.
+        let mut error_paths = vec![];
+        for (error, _) in errors.iter() {
+            let (description, path) = match error {
+                RegionErrorKind::RegionError { longer_fr, shorter_fr, .. } => (
+                    format!(
+                        "{} must outlive {}",
+                        render_region_vid(*longer_fr, self),
+                        render_region_vid(*shorter_fr, self)
+                    ),
+                    self.find_constraint_paths_between_regions(*longer_fr, |r| r == *shorter_fr),
+                ),
+                RegionErrorKind::BoundUniversalRegionError { longer_fr, error_element, .. } => {
+                    let error_region = self.region_from_element(*longer_fr, error_element);
+                    (
+                        format!(
+                            "placeholder {} must outlive {}",
+                            render_region_vid(*longer_fr, self),
+                            render_region_vid(error_region, self)
+                        ),
+                        self.find_constraint_paths_between_regions(*longer_fr, |r| {
+                            r == error_region
+                        }),
+                    )
+                }
+                RegionErrorKind::TypeTestError { .. }
+                | RegionErrorKind::UnexpectedHiddenRegion { .. } => continue,
+            };
+            let path = path.map(|(path, _)| path).unwrap_or_default();
+            let path_edges = path
+                .iter()
+                .map(|constraint| {
+                    constraints.iter().position(|c| c == constraint).unwrap_or_else(|| {
+                        constraints.push(*constraint);
+                        edges.push(edge(constraint));
+                        edges.len() - 1
+                    })
+                })
+                .collect();
+            error_paths.push(ErrorPath { description, edges: path_edges });
+        }
+        for path in &error_paths {
+            for &edge in &path.edges {
+                edges[edge].blamed = true;
+            }
+        }
+
+        RegionGraph { nodes, edges, errors: error_paths }
+    }
+}
+
+impl RegionGraph {
+    fn write_html(&self, w: &mut dyn Write) -> io::Result<()> {
+        writeln!(w, "<!DOCTYPE html>")?;
+        writeln!(w, "<html><head><meta charset=\"utf-8\"><title>Region constraints</title>")?;
+        writeln!(w, "<style>{HTML_STYLE}</style></head><body>")?;
+
+        writeln!(w, "<h2>Region errors</h2>")?;
+        if self.errors.is_empty() {
+            writeln!(w, "<p>None.</p>")?;
+        }
+        for error in &self.errors {
+            writeln!(w, "<h3>{}</h3><ol>", escape_html(&error.description))?;
+            for &edge in &error.edges {
+                let edge = &self.edges[edge];
+                writeln!(
+                    w,
+                    "<li>{}: {} <small>({}, at {}, {})</small></li>",
+                    region_link(edge.sup),
+                    region_link(edge.sub),
+                    escape_html(&edge.category),
+                    escape_html(&edge.location),
+                    escape_html(&edge.span),
+                )?;
+            }
+            writeln!(w, "</ol>")?;
+        }
+
+        writeln!(w, "<h2>Regions</h2>")?;
+        writeln!(w, "<p>Select a region to highlight the constraints it is part of.</p>")?;
+        writeln!(w, "<table><tr><th>Region</th><th>Origin</th><th>Live at</th></tr>")?;
+        for node in &self.nodes {
+            writeln!(
+                w,
+                "<tr id=\"r{id}\" class=\"region\" data-region=\"r{id}\"><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&node.name),
+                escape_html(node.origin.as_deref().unwrap_or("")),
+                escape_html(&node.liveness),
+                id = node.vid.as_usize(),
+            )?;
+        }
+        writeln!(w, "</table>")?;
+
+        writeln!(w, "<h2>Outlives constraints</h2>")?;
+        writeln!(
+            w,
+            "<table><tr><th>Constraint</th><th>Category</th><th>Location</th><th>Span</th></tr>"
+        )?;
+        for edge in &self.edges {
+            writeln!(
+                w,
+                "<tr class=\"constraint{}\" data-sup=\"r{}\" data-sub=\"r{}\"><td>{}: {}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                if edge.blamed { " blamed" } else { "" },
+                edge.sup.as_usize(),
+                edge.sub.as_usize(),
+                region_link(edge.sup),
+                region_link(edge.sub),
+                escape_html(&edge.category),
+                escape_html(&edge.location),
+                escape_html(&edge.span),
+            )?;
+        }
+        writeln!(w, "</table>")?;
+
+        writeln!(w, "<script>{HTML_SCRIPT}</script></body></html>")
+    }
+}
+
+fn region_link(vid: RegionVid) -> String {
+    format!("<a href=\"#r{0}\">{vid:?}</a>", vid.as_usize())
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "\
+body { font-family: sans-serif; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 2px 6px; text-align: left; font-family: monospace; }
+tr.region { cursor: pointer; }
+tr.blamed td { color: #b00; font-weight: bold; }
+tr.selected td { background: #ffd; }
+tr.incoming td { background: #def; }
+tr.outgoing td { background: #dfd; }";
+
+const HTML_SCRIPT: &str = "\
+for (const row of document.querySelectorAll('tr.region')) {
+    row.addEventListener('click', () => {
+        const region = row.dataset.region;
+        for (const r of document.querySelectorAll('tr.region')) {
+            r.classList.toggle('selected', r === row);
+        }
+        for (const c of document.querySelectorAll('tr.constraint')) {
+            c.classList.toggle('outgoing', c.dataset.sup === region);
+            c.classList.toggle('incoming', c.dataset.sub === region);
+        }
+    });
+}";
+
+impl<'this> dot::Labeller<'this> for RegionGraph {
+    type Node = usize;
+    type Edge = usize;
+
+    fn graph_id(&'this self) -> dot::Id<'this> {
+        dot::Id::new("RegionConstraints").unwrap()
+    }
+    fn node_id(&'this self, n: &usize) -> dot::Id<'this> {
+        dot::Id::new(format!("r{}", self.nodes[*n].vid.index())).unwrap()
+    }
+    fn node_shape(&'this self, _node: &usize) -> Option<dot::LabelText<'this>> {
+        Some(dot::LabelText::LabelStr(Cow::Borrowed("box")))
+    }
+    fn node_label(&'this self, n: &usize) -> dot::LabelText<'this> {
+        let node = &self.nodes[*n];
+        let mut label = node.name.clone();
+        if let Some(origin) = &node.origin {
+            label.push_str(&format!("\n{origin}"));
+        }
+        label.push_str(&format!("\nlive at: {}", node.liveness));
+        dot::LabelText::LabelStr(label.into())
+    }
+    fn edge_label(&'this self, e: &usize) -> dot::LabelText<'this> {
+        let edge = &self.edges[*e];
+        let label = format!("{} at {}\n{}", edge.category, edge.location, edge.span);
+        dot::LabelText::LabelStr(label.into())
+    }
+    fn edge_style(&'this self, e: &usize) -> dot::Style {
+        if self.edges[*e].blamed {
+            dot::Style::Bold
+        } else {
+            dot::Style::None
+        }
+    }
+}
+
+impl<'this> dot::GraphWalk<'this> for RegionGraph {
+    type Node = usize;
+    type Edge = usize;
+
+    fn nodes(&'this self) -> dot::Nodes<'this, usize> {
+        (0..self.nodes.len()).collect()
+    }
+    fn edges(&'this self) -> dot::Edges<'this, usize> {
+        (0..self.edges.len()).collect()
+    }
+
+    // Render `a: b` as `a -> b`, indicating the flow
+    // of data during inference.
+
+    fn source(&'this self, edge: &usize) -> usize {
+        self.edges[*edge].sup.index()
+    }
+
+    fn target(&'this self, edge: &usize) -> usize {
+        self.edges[*edge].sub.index()
+    }
+}
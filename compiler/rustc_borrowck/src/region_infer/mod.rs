@@ -23,6 +23,7 @@
 use rustc_mir_dataflow::points::DenseLocationMap;
 use rustc_span::Span;
 
+use crate::borrow_set::BorrowData;
 use crate::constraints::graph::{self, NormalConstraintGraph, RegionGraph};
 use crate::dataflow::BorrowIndex;
 use crate::{
@@ -40,6 +41,7 @@
 };
 
 mod dump_mir;
+mod explanation_dump;
 mod graphviz;
 mod opaque_types;
 mod reverse_sccs;
@@ -2253,20 +2255,6 @@ pub(crate) fn region_graph(&self) -> RegionGraph<'_, 'tcx, graph::Normal> {
         self.constraint_graph.region_graph(&self.constraints, self.universal_regions.fr_static)
     }
 
-    /// Returns whether the given region is considered live at all points: whether it is a
-    /// placeholder or a free region.
-    pub(crate) fn is_region_live_at_all_points(&self, region: RegionVid) -> bool {
-        // FIXME: there must be a cleaner way to find this information. At least, when
-        // higher-ranked subtyping is abstracted away from the borrowck main path, we'll only
-        // need to check whether this is a universal region.
-        let origin = self.region_definition(region).origin;
-        let live_at_all_points = matches!(
-            origin,
-            NllRegionVariableOrigin::Placeholder(_) | NllRegionVariableOrigin::FreeRegion
-        );
-        live_at_all_points
-    }
-
     /// Returns whether the `loan_idx` is live at the given `location`: whether its issuing
     /// region is contained within the type of a variable that is live at this point.
     /// Note: for now, the sets of live loans is only available when using `-Zpolonius=next`.
@@ -2275,6 +2263,17 @@ pub(crate) fn is_loan_live_at(&self, loan_idx: BorrowIndex, location: Location)
         self.liveness_constraints.is_loan_live_at(loan_idx, point)
     }
 
+    /// When using `-Zpolonius=next`, returns the live regions that contain the `loan` at the given
+    /// `location`. Used to explain why a loan is live at the point of a borrowck error.
+    pub(crate) fn regions_containing_loan_at(
+        &self,
+        body: &Body<'tcx>,
+        loan: &BorrowData<'tcx>,
+        location: Location,
+    ) -> Vec<RegionVid> {
+        self.liveness_constraints.regions_containing_loan_at(body, loan, location)
+    }
+
     /// Returns the representative `RegionVid` for a given SCC.
     /// See `RegionTracker` for how a region variable ID is chosen.
     ///
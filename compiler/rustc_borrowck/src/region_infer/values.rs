@@ -4,12 +4,14 @@
 use rustc_index::interval::IntervalSet;
 use rustc_index::interval::SparseIntervalMatrix;
 use rustc_index::Idx;
-use rustc_middle::mir::{BasicBlock, Location};
+use rustc_middle::mir::{BasicBlock, Body, Location};
 use rustc_middle::ty::{self, RegionVid};
 use rustc_mir_dataflow::points::{DenseLocationMap, PointIndex};
 use std::fmt::Debug;
 use std::rc::Rc;
 
+use crate::borrow_set::BorrowData;
+use crate::polonius::loan_liveness::LocalizedOutlivesGraph;
 use crate::BorrowIndex;
 
 rustc_index::newtype_index! {
@@ -55,26 +57,16 @@ pub(crate) struct LivenessValues {
     pub(crate) loans: Option<LiveLoans>,
 }
 
-/// Data used to compute the loans that are live at a given point in the CFG, when using
-/// `-Zpolonius=next`.
+/// The loans that are live at a given point in the CFG, when using `-Zpolonius=next`. They are
+/// computed once liveness is known, see `polonius::loan_liveness`.
 pub(crate) struct LiveLoans {
-    /// The set of loans that flow into a given region. When individual regions are marked as live
-    /// in the CFG, these inflowing loans are recorded as live.
-    pub(crate) inflowing_loans: SparseBitMatrix<RegionVid, BorrowIndex>,
+    /// The localized constraint graph the loans flow through, kept to explain borrowck errors.
+    pub(crate) graph: LocalizedOutlivesGraph,
 
     /// The set of loans that are live at a given point in the CFG.
     pub(crate) live_loans: SparseBitMatrix<PointIndex, BorrowIndex>,
 }
 
-impl LiveLoans {
-    pub(crate) fn new(num_loans: usize) -> Self {
-        LiveLoans {
-            live_loans: SparseBitMatrix::new(num_loans),
-            inflowing_loans: SparseBitMatrix::new(num_loans),
-        }
-    }
-}
-
 impl LivenessValues {
     /// Create an empty map of regions to locations where they're live.
     pub(crate) fn with_specific_points(elements: Rc<DenseLocationMap>) -> Self {
@@ -123,13 +115,6 @@ pub(crate) fn add_location(&mut self, region: RegionVid, location: Location) {
                 self.live_regions.as_mut().unwrap().insert(region);
             }
         }
-
-        // When available, record the loans flowing into this region as live at the given point.
-        if let Some(loans) = self.loans.as_mut() {
-            if let Some(inflowing) = loans.inflowing_loans.row(region) {
-                loans.live_loans.union_row(point, inflowing);
-            }
-        }
     }
 
     /// Records `region` as being live at all the given `points`.
@@ -142,17 +127,6 @@ pub(crate) fn add_points(&mut self, region: RegionVid, points: &IntervalSet<Poin
                 self.live_regions.as_mut().unwrap().insert(region);
             }
         }
-
-        // When available, record the loans flowing into this region as live at the given points.
-        if let Some(loans) = self.loans.as_mut() {
-            if let Some(inflowing) = loans.inflowing_loans.row(region) {
-                if !inflowing.is_empty() {
-                    for point in points.iter() {
-                        loans.live_loans.union_row(point, inflowing);
-                    }
-                }
-            }
-        }
     }
 
     /// Records `region` as being live at all the control-flow points.
@@ -166,7 +140,11 @@ pub(crate) fn add_all_points(&mut self, region: RegionVid) {
 
     /// Returns whether `region` is marked live at the given `location`.
     pub(crate) fn is_live_at(&self, region: RegionVid, location: Location) -> bool {
-        let point = self.elements.point_from_location(location);
+        self.is_live_at_point(region, self.elements.point_from_location(location))
+    }
+
+    /// Returns whether `region` is marked live at the given `point`.
+    pub(crate) fn is_live_at_point(&self, region: RegionVid, point: PointIndex) -> bool {
         if let Some(points) = &self.points {
             points.row(region).is_some_and(|r| r.contains(point))
         } else {
@@ -203,6 +181,22 @@ pub(crate) fn point_from_location(&self, location: Location) -> PointIndex {
         self.elements.point_from_location(location)
     }
 
+    /// When using `-Zpolonius=next`, returns the live regions that contain the `loan` at the given
+    /// `location`.
+    pub(crate) fn regions_containing_loan_at(
+        &self,
+        body: &Body<'_>,
+        loan: &BorrowData<'_>,
+        location: Location,
+    ) -> Vec<RegionVid> {
+        let point = self.elements.point_from_location(location);
+        self.loans
+            .as_ref()
+            .expect("Accessing live loans requires `-Zpolonius=next`")
+            .graph
+            .regions_containing_loan_at(body, &self.elements, self, loan, point)
+    }
+
     /// When using `-Zpolonius=next`, returns whether the `loan_idx` is live at the given `point`.
     pub(crate) fn is_loan_live_at(&self, loan_idx: BorrowIndex, point: PointIndex) -> bool {
         self.loans
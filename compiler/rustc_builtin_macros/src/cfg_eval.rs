@@ -10,7 +10,7 @@
 use rustc_ast::{Attribute, HasAttrs, HasTokens};
 use rustc_errors::PResult;
 use rustc_expand::base::{Annotatable, ExtCtxt};
-use rustc_expand::config::StripUnconfigured;
+use rustc_expand::config::{CfgReport, StripUnconfigured};
 use rustc_expand::configure;
 use rustc_feature::Features;
 use rustc_parse::parser::{ForceCollect, Parser};
@@ -28,7 +28,13 @@ pub(crate) fn expand(
 ) -> Vec<Annotatable> {
     check_builtin_macro_attribute(ecx, meta_item, sym::cfg_eval);
     warn_on_duplicate_attribute(ecx, &annotatable, sym::cfg_eval);
-    vec![cfg_eval(ecx.sess, ecx.ecfg.features, annotatable, ecx.current_expansion.lint_node_id)]
+    vec![cfg_eval(
+        ecx.sess,
+        ecx.ecfg.features,
+        annotatable,
+        ecx.current_expansion.lint_node_id,
+        ecx.cfg_report.as_ref(),
+    )]
 }
 
 pub(crate) fn cfg_eval(
@@ -36,9 +42,12 @@ pub(crate) fn cfg_eval(
     features: &Features,
     annotatable: Annotatable,
     lint_node_id: NodeId,
+    cfg_report: Option<&CfgReport>,
 ) -> Annotatable {
     let features = Some(features);
-    CfgEval { cfg: &mut StripUnconfigured { sess, features, config_tokens: true, lint_node_id } }
+    let cfg =
+        &mut StripUnconfigured { sess, features, config_tokens: true, lint_node_id, cfg_report };
+    CfgEval { cfg }
         .configure_annotatable(annotatable)
         // Since the item itself has already been configured by the `InvocationCollector`,
         // we know that fold result vector will contain exactly one element.
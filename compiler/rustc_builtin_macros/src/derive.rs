@@ -83,6 +83,7 @@ fn expand(
                             features,
                             item.clone(),
                             ecx.current_expansion.lint_node_id,
+                            ecx.cfg_report.as_ref(),
                         );
                         for other in others {
                             other.item = first.item.clone();
@@ -998,6 +998,28 @@ fn expand_format_args_impl<'cx>(
     })
 }
 
+/// Checks the format string and arguments given to a macro declared with `#[format_string]`,
+/// emitting the errors that `format_args!` would. Returns the `format_args!` expression, which
+/// the expansion of the macro includes so that the names that the format string captures, like
+/// `x` in `"{x}"`, are resolved.
+pub fn check_format_string(ecx: &mut ExtCtxt<'_>, sp: Span, tts: TokenStream) -> Option<P<Expr>> {
+    let sp = ecx.with_def_site_ctxt(sp);
+    match parse_args(ecx, sp, tts) {
+        // `Retry` means that the format string is a macro call that isn't expanded yet, in which
+        // case there is nothing to check.
+        Ok(input) => match make_format_args(ecx, input, false) {
+            ExpandResult::Ready(Ok(format_args)) => {
+                Some(ecx.expr(sp, ExprKind::FormatArgs(P(format_args))))
+            }
+            ExpandResult::Ready(Err(_)) | ExpandResult::Retry(()) => None,
+        },
+        Err(err) => {
+            err.emit();
+            None
+        }
+    }
+}
+
 pub(crate) fn expand_format_args<'cx>(
     ecx: &'cx mut ExtCtxt<'_>,
     sp: Span,
@@ -56,6 +56,8 @@
 pub mod test_harness;
 pub mod util;
 
+pub use format::check_format_string;
+
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
 
 pub fn register_builtin_macros(resolver: &mut dyn ResolverExpand) {
@@ -131,5 +133,5 @@ pub fn register_builtin_macros(resolver: &mut dyn ResolverExpand) {
     }
 
     let client = proc_macro::bridge::client::Client::expand1(proc_macro::quote);
-    register(sym::quote, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client })));
+    register(sym::quote, SyntaxExtensionKind::Bang(Box::new(BangProcMacro { client, pure: None })));
 }
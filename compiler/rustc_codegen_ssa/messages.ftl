@@ -1,5 +1,9 @@
 codegen_ssa_L4Bender_exporting_symbols_unimplemented = exporting symbols not implemented yet for L4Bender
 
+codegen_ssa_abi_baseline_read = failed to read ABI baseline `{$path}`: {$error}
+
+codegen_ssa_abi_breaking_change = ABI-breaking change compared to `{$baseline}`: {$change}
+
 codegen_ssa_add_native_library = failed to add native library {$library_path}: {$error}
 
 codegen_ssa_apple_sdk_error_sdk_path = failed to get {$sdk_name} SDK path: {$error}
@@ -0,0 +1,318 @@
+//! Checking the binary interface of a crate against an earlier build.
+//!
+//! `--print=exported-abi` renders the symbols exported by the crate, the ABI of the exported
+//! functions, and the layout of the exported `#[repr(C)]` types as JSON. `-Zabi-baseline`
+//! compares the crate being compiled against such a file produced by an earlier build and
+//! reports every change that breaks binary compatibility, such as a removed symbol, a changed
+//! field offset or an argument that is now passed differently.
+
+use std::fs;
+use std::path::Path;
+
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::middle::exported_symbols::{ExportedSymbol, SymbolExportKind};
+use rustc_middle::ty::layout::{LayoutCx, LayoutOf, TyAndLayout};
+use rustc_middle::ty::{self, AdtDef, Instance, Ty, TyCtxt};
+use rustc_session::config::PrintKind;
+use rustc_target::abi::call::{ArgAbi, FnAbi, PassMode};
+use serde_json::{json, Map, Value};
+
+use crate::back::symbol_export::{symbol_name_for_instance_in_crate, threshold};
+use crate::errors;
+
+/// Handles `--print=exported-abi` and `-Zabi-baseline`. Must run after codegen, so that the
+/// set of exported symbols is known.
+pub fn print_and_check_exported_abi(tcx: TyCtxt<'_>) {
+    let sess = tcx.sess;
+    let prints: Vec<_> =
+        sess.opts.prints.iter().filter(|p| p.kind == PrintKind::ExportedAbi).collect();
+    if prints.is_empty() && sess.opts.unstable_opts.abi_baseline.is_none() {
+        return;
+    }
+
+    let current = exported_abi(tcx);
+    if !prints.is_empty() {
+        let mut rendered = serde_json::to_string_pretty(&current).unwrap();
+        rendered.push('\n');
+        for req in prints {
+            req.out.overwrite(&rendered, sess);
+        }
+    }
+
+    if let Some(path) = &sess.opts.unstable_opts.abi_baseline {
+        check_against_baseline(tcx, &current, path);
+    }
+}
+
+/// Collects the exported symbols and `#[repr(C)]` type layouts of the local crate.
+fn exported_abi(tcx: TyCtxt<'_>) -> Value {
+    let cx = LayoutCx { tcx, param_env: ty::ParamEnv::reveal_all() };
+    let export_threshold = threshold(tcx);
+
+    let mut symbols = Map::new();
+    for &(symbol, info) in tcx.exported_symbols(LOCAL_CRATE) {
+        if !info.level.is_below_threshold(export_threshold) {
+            continue;
+        }
+
+        let name = symbol_name_for_instance_in_crate(tcx, symbol, LOCAL_CRATE);
+        let mut entry = Map::new();
+        let kind = match info.kind {
+            SymbolExportKind::Text => "text",
+            SymbolExportKind::Data => "data",
+            SymbolExportKind::Tls => "tls",
+        };
+        entry.insert("kind".to_string(), json!(kind));
+
+        if let ExportedSymbol::NonGeneric(def_id) = symbol {
+            match tcx.def_kind(def_id) {
+                DefKind::Fn | DefKind::AssocFn => {
+                    let instance = Instance::mono(tcx, def_id);
+                    if let Ok(fn_abi) = tcx.fn_abi_of_instance(
+                        ty::ParamEnv::reveal_all().and((instance, ty::List::empty())),
+                    ) {
+                        entry.insert("abi".to_string(), fn_abi_json(fn_abi));
+                    }
+                }
+                DefKind::Static { .. } => {
+                    let ty = tcx.type_of(def_id).instantiate_identity();
+                    if let Ok(layout) = cx.layout_of(ty) {
+                        entry.insert("size".to_string(), json!(layout.size.bytes()));
+                        entry.insert("align".to_string(), json!(layout.align.abi.bytes()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        symbols.insert(name, Value::Object(entry));
+    }
+
+    let effective_visibilities = tcx.effective_visibilities(());
+    let mut types = Map::new();
+    for def_id in tcx.hir_crate_items(()).definitions() {
+        if !matches!(tcx.def_kind(def_id), DefKind::Struct | DefKind::Union | DefKind::Enum) {
+            continue;
+        }
+        let adt_def = tcx.adt_def(def_id);
+        if !adt_def.repr().c()
+            || tcx.generics_of(def_id).requires_monomorphization(tcx)
+            || !effective_visibilities.is_exported(def_id)
+        {
+            continue;
+        }
+
+        let ty = tcx.type_of(def_id).instantiate_identity();
+        if let Ok(layout) = cx.layout_of(ty) {
+            types.insert(tcx.def_path_str(def_id), type_layout_json(&cx, adt_def, layout));
+        }
+    }
+
+    json!({ "symbols": symbols, "types": types })
+}
+
+fn fn_abi_json<'tcx>(fn_abi: &FnAbi<'tcx, Ty<'tcx>>) -> Value {
+    json!({
+        "conv": format!("{:?}", fn_abi.conv),
+        "c_variadic": fn_abi.c_variadic,
+        "ret": arg_abi_json(&fn_abi.ret),
+        "args": fn_abi.args.iter().map(arg_abi_json).collect::<Vec<_>>(),
+    })
+}
+
+fn arg_abi_json<'tcx>(arg: &ArgAbi<'tcx, Ty<'tcx>>) -> Value {
+    let mode = match arg.mode {
+        PassMode::Ignore => "ignore",
+        PassMode::Direct(_) => "direct",
+        PassMode::Pair(..) => "pair",
+        PassMode::Cast { .. } => "cast",
+        PassMode::Indirect { on_stack: false, .. } => "indirect",
+        PassMode::Indirect { on_stack: true, .. } => "indirect-on-stack",
+    };
+    json!({
+        "type": arg.layout.ty.to_string(),
+        "mode": mode,
+        "size": arg.layout.size.bytes(),
+        "align": arg.layout.align.abi.bytes(),
+    })
+}
+
+fn type_layout_json<'tcx>(
+    cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
+    adt_def: AdtDef<'tcx>,
+    layout: TyAndLayout<'tcx>,
+) -> Value {
+    let variants: Vec<_> = adt_def
+        .variants()
+        .iter_enumerated()
+        .map(|(variant_idx, variant_def)| {
+            let variant_layout = layout.for_variant(cx, variant_idx);
+            let fields: Vec<_> = variant_def
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, field_def)| {
+                    let field_layout = variant_layout.field(cx, i);
+                    json!({
+                        "name": field_def.name.as_str(),
+                        "offset": variant_layout.fields.offset(i).bytes(),
+                        "size": field_layout.size.bytes(),
+                        "align": field_layout.align.abi.bytes(),
+                    })
+                })
+                .collect();
+            let discriminant = adt_def
+                .is_enum()
+                .then(|| adt_def.discriminant_for_variant(cx.tcx, variant_idx).val.to_string());
+            json!({
+                "name": variant_def.name.as_str(),
+                "discriminant": discriminant,
+                "fields": fields,
+            })
+        })
+        .collect();
+
+    json!({
+        "size": layout.size.bytes(),
+        "align": layout.align.abi.bytes(),
+        "variants": variants,
+    })
+}
+
+fn check_against_baseline(tcx: TyCtxt<'_>, current: &Value, path: &Path) {
+    let baseline = fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|contents| serde_json::from_str::<Value>(&contents).map_err(|e| e.to_string()));
+    let baseline = match baseline {
+        Ok(baseline) => baseline,
+        Err(error) => {
+            tcx.dcx().emit_err(errors::AbiBaselineRead { path, error });
+            return;
+        }
+    };
+
+    let mut changes = vec![];
+    diff_symbols(&baseline["symbols"], &current["symbols"], &mut changes);
+    diff_types(&baseline["types"], &current["types"], &mut changes);
+
+    for change in changes {
+        tcx.dcx().emit_err(errors::AbiBreakingChange { baseline: path, change });
+    }
+}
+
+fn diff_symbols(old: &Value, new: &Value, changes: &mut Vec<String>) {
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else { return };
+    for (name, old_symbol) in old {
+        let Some(new_symbol) = new.get(name) else {
+            changes.push(format!("exported symbol `{name}` was removed"));
+            continue;
+        };
+        for key in ["kind", "size", "align"] {
+            diff_value(
+                &format!("symbol `{name}`"),
+                key,
+                &old_symbol[key],
+                &new_symbol[key],
+                changes,
+            );
+        }
+
+        let (old_abi, new_abi) = (&old_symbol["abi"], &new_symbol["abi"]);
+        if old_abi.is_null() || new_abi.is_null() {
+            continue;
+        }
+        let function = format!("function `{name}`");
+        for key in ["conv", "c_variadic"] {
+            diff_value(&function, key, &old_abi[key], &new_abi[key], changes);
+        }
+        diff_arg(&format!("return value of {function}"), &old_abi["ret"], &new_abi["ret"], changes);
+
+        let (Some(old_args), Some(new_args)) =
+            (old_abi["args"].as_array(), new_abi["args"].as_array())
+        else {
+            continue;
+        };
+        if old_args.len() != new_args.len() {
+            changes.push(format!(
+                "{function} changed its number of arguments from {} to {}",
+                old_args.len(),
+                new_args.len()
+            ));
+            continue;
+        }
+        for (i, (old_arg, new_arg)) in old_args.iter().zip(new_args).enumerate() {
+            diff_arg(&format!("argument {i} of {function}"), old_arg, new_arg, changes);
+        }
+    }
+}
+
+fn diff_arg(what: &str, old: &Value, new: &Value, changes: &mut Vec<String>) {
+    for key in ["mode", "size", "align"] {
+        diff_value(what, key, &old[key], &new[key], changes);
+    }
+}
+
+fn diff_types(old: &Value, new: &Value, changes: &mut Vec<String>) {
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else { return };
+    for (name, old_type) in old {
+        let Some(new_type) = new.get(name) else {
+            changes.push(format!("exported `#[repr(C)]` type `{name}` was removed"));
+            continue;
+        };
+        let what = format!("type `{name}`");
+        for key in ["size", "align"] {
+            diff_value(&what, key, &old_type[key], &new_type[key], changes);
+        }
+
+        let (Some(old_variants), Some(new_variants)) =
+            (old_type["variants"].as_array(), new_type["variants"].as_array())
+        else {
+            continue;
+        };
+        if old_variants.len() != new_variants.len() {
+            changes.push(format!(
+                "{what} changed its number of variants from {} to {}",
+                old_variants.len(),
+                new_variants.len()
+            ));
+            continue;
+        }
+        for (old_variant, new_variant) in old_variants.iter().zip(new_variants) {
+            let variant =
+                format!("variant `{}` of {what}", old_variant["name"].as_str().unwrap_or("?"));
+            diff_value(&variant, "name", &old_variant["name"], &new_variant["name"], changes);
+            diff_value(
+                &variant,
+                "discriminant",
+                &old_variant["discriminant"],
+                &new_variant["discriminant"],
+                changes,
+            );
+
+            let (Some(old_fields), Some(new_fields)) =
+                (old_variant["fields"].as_array(), new_variant["fields"].as_array())
+            else {
+                continue;
+            };
+            for old_field in old_fields {
+                let field_name = old_field["name"].as_str().unwrap_or("?");
+                let field = format!("field `{field_name}` of {variant}");
+                let Some(new_field) = new_fields.iter().find(|f| f["name"] == old_field["name"])
+                else {
+                    changes.push(format!("{field} was removed"));
+                    continue;
+                };
+                for key in ["offset", "size", "align"] {
+                    diff_value(&field, key, &old_field[key], &new_field[key], changes);
+                }
+            }
+        }
+    }
+}
+
+fn diff_value(what: &str, key: &str, old: &Value, new: &Value, changes: &mut Vec<String>) {
+    if old != new {
+        changes.push(format!("{key} of {what} changed from {old} to {new}"));
+    }
+}
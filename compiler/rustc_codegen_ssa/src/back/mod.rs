@@ -1,3 +1,4 @@
+pub mod abi_check;
 pub mod archive;
 pub mod command;
 pub mod link;
@@ -1033,3 +1033,17 @@ pub struct CompilerBuiltinsCannotCall {
     pub caller: String,
     pub callee: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_abi_baseline_read)]
+pub struct AbiBaselineRead<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_abi_breaking_change)]
+pub struct AbiBreakingChange<'a> {
+    pub baseline: &'a Path,
+    pub change: String,
+}
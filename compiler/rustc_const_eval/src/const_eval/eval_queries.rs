@@ -17,6 +17,7 @@
 use rustc_span::{Span, DUMMY_SP};
 use rustc_target::abi::{self, Abi};
 
+use super::profile::{self, ConstEvalProfiler};
 use super::{CanAccessMutGlobal, CompileTimeInterpCx, CompileTimeMachine};
 use crate::const_eval::CheckAlignment;
 use crate::errors::ConstEvalError;
@@ -385,9 +386,21 @@ fn eval_in_interpreter<'tcx, R: InterpretationResult<'tcx>>(
         // so we have to reject reading mutable global memory.
         CompileTimeMachine::new(CanAccessMutGlobal::from(is_static), CheckAlignment::Error),
     );
+    if profile::is_enabled(tcx.sess) {
+        ecx.machine.profiler = Some(ConstEvalProfiler::new());
+    }
+
     let res = ecx.load_mir(cid.instance.def, cid.promoted);
-    res.and_then(|body| eval_body_using_ecx(&mut ecx, cid, body))
-        .map_err(|error| report_eval_error(&ecx, cid, error))
+    let res = res
+        .and_then(|body| eval_body_using_ecx(&mut ecx, cid, body))
+        .map_err(|error| report_eval_error(&ecx, cid, error));
+
+    if let Some(mut profiler) = ecx.machine.profiler.take() {
+        // Evaluation errors leave the frames that were executing on the stack.
+        profiler.charge(ecx.machine.stack.iter().map(|frame| frame.instance));
+        profiler.finish(tcx, cid);
+    }
+    res
 }
 
 #[inline(always)]
@@ -29,9 +29,11 @@
     self, compile_time_machine, err_ub, throw_exhaust, throw_inval, throw_ub_custom, throw_unsup,
     throw_unsup_format, AllocId, AllocRange, ConstAllocation, CtfeProvenance, FnArg, FnVal, Frame,
     GlobalAlloc, ImmTy, InterpCx, InterpResult, MPlaceTy, OpTy, Pointer, PointerArithmetic, Scalar,
+    StackPopJump,
 };
 
 use super::error::*;
+use super::profile::ConstEvalProfiler;
 
 /// When hitting this many interpreted terminators we emit a deny by default lint
 /// that notfies the user that their constant takes a long time to evaluate. If that's
@@ -67,6 +69,10 @@ pub struct CompileTimeMachine<'tcx> {
     /// storing the result in the given `AllocId`.
     /// Used to prevent reads from a static's base allocation, as that may allow for self-initialization loops.
     pub(crate) static_root_ids: Option<(AllocId, LocalDefId)>,
+
+    /// Records where the evaluation spends its time, if `-Zprint-const-eval-profile` or
+    /// `-Zconst-eval-profile-folded` is set.
+    pub(super) profiler: Option<ConstEvalProfiler<'tcx>>,
 }
 
 #[derive(Copy, Clone)]
@@ -101,6 +107,7 @@ pub(crate) fn new(
             can_access_mut_global,
             check_alignment,
             static_root_ids: None,
+            profiler: None,
         }
     }
 }
@@ -592,6 +599,10 @@ fn binary_ptr_op(
     }
 
     fn increment_const_eval_counter(ecx: &mut InterpCx<'tcx, Self>) -> InterpResult<'tcx> {
+        if let Some(profiler) = &mut ecx.machine.profiler {
+            profiler.step();
+        }
+
         // The step limit has already been hit in a previous call to `increment_const_eval_counter`.
 
         if let Some(new_steps) = ecx.machine.num_evaluated_steps.checked_add(1) {
@@ -660,9 +671,30 @@ fn init_frame(
         // Enforce stack size limit. Add 1 because this is run before the new frame is pushed.
         if !ecx.recursion_limit.value_within_limit(ecx.stack().len() + 1) {
             throw_exhaust!(StackFrameLimitReached)
-        } else {
-            Ok(frame)
         }
+
+        // The caller stops executing until the new frame is popped.
+        let machine = &mut ecx.machine;
+        if let Some(profiler) = &mut machine.profiler {
+            profiler.charge(machine.stack.iter().map(|frame| frame.instance));
+        }
+        Ok(frame)
+    }
+
+    fn after_stack_pop(
+        ecx: &mut InterpCx<'tcx, Self>,
+        frame: Frame<'tcx>,
+        unwinding: bool,
+    ) -> InterpResult<'tcx, StackPopJump> {
+        // Compile-time evaluation does not support unwinding.
+        assert!(!unwinding);
+
+        let machine = &mut ecx.machine;
+        if let Some(profiler) = &mut machine.profiler {
+            profiler
+                .charge(machine.stack.iter().map(|frame| frame.instance).chain([frame.instance]));
+        }
+        Ok(StackPopJump::Normal)
     }
 
     #[inline(always)]
@@ -0,0 +1,68 @@
+//! Support for `-Zprint-const-eval-profile` and `-Zconst-eval-profile-folded`, which report
+//! where the time and steps of compile-time evaluation are spent.
+
+use std::time::Instant;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_middle::mir::interpret::GlobalId;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{Instance, TyCtxt};
+use rustc_session::code_stats::ConstEvalCost;
+use rustc_session::Session;
+
+pub(crate) fn is_enabled(sess: &Session) -> bool {
+    sess.opts.unstable_opts.print_const_eval_profile
+        || sess.opts.unstable_opts.const_eval_profile_folded.is_some()
+}
+
+/// Records the cost of the call stacks evaluated by one `InterpCx`.
+///
+/// The cost is charged to the innermost frame each time a frame is pushed or popped, so the
+/// profile only contains the self cost of each call stack. Steps are counted like for the
+/// `long_running_const_eval` lint.
+pub(crate) struct ConstEvalProfiler<'tcx> {
+    /// Steps evaluated since the innermost frame started or resumed executing.
+    steps: u64,
+    /// When the innermost frame started or resumed executing.
+    since: Instant,
+    /// Self cost of each call stack, from the outermost frame to the innermost one.
+    costs: FxHashMap<Vec<Instance<'tcx>>, ConstEvalCost>,
+}
+
+impl<'tcx> ConstEvalProfiler<'tcx> {
+    pub(crate) fn new() -> Self {
+        ConstEvalProfiler { steps: 0, since: Instant::now(), costs: Default::default() }
+    }
+
+    pub(crate) fn step(&mut self) {
+        self.steps += 1;
+    }
+
+    /// Charges the cost since the last call to `stack`, given from the outermost frame.
+    pub(crate) fn charge(&mut self, stack: impl Iterator<Item = Instance<'tcx>>) {
+        let now = Instant::now();
+        let cost = ConstEvalCost { steps: self.steps, time: now - self.since };
+        self.steps = 0;
+        self.since = now;
+
+        let stack: Vec<_> = stack.collect();
+        if !stack.is_empty() {
+            *self.costs.entry(stack).or_default() += cost;
+        }
+    }
+
+    /// Hands the profile of the evaluation of `cid` to the session. The outermost frame is
+    /// named after `cid`, so that promoteds can be told apart from the item they are in.
+    pub(crate) fn finish(self, tcx: TyCtxt<'tcx>, cid: GlobalId<'tcx>) {
+        let root = cid.display(tcx);
+        #[allow(rustc::potential_query_instability)]
+        let costs = self.costs.into_iter().map(|(stack, cost)| {
+            let mut names = vec![root.clone()];
+            names.extend(
+                stack[1..].iter().map(|instance| with_no_trimmed_paths!(instance.to_string())),
+            );
+            (names, cost)
+        });
+        tcx.sess.code_stats.record_const_eval_costs(costs);
+    }
+}
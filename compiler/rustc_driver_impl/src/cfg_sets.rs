@@ -0,0 +1,167 @@
+//! `-Z cfg-sets`, which type-checks a crate once per configuration listed in a file, in a single
+//! invocation of the compiler, e.g. to check the combinations of the features of a crate.
+//!
+//! The configuration of a session is fixed when the session is created, and the results of its
+//! queries, starting with the expansion of the crate, depend on it. So each configuration is
+//! checked in its own session, one after the other. The sessions run within the same session
+//! globals though, so the crate is only parsed once: the sessions after the first one expand and
+//! analyze a copy of it. Each diagnostic emitted after parsing is tagged with the configuration it
+//! occurs in.
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_interface::interface;
+use rustc_session::EarlyDiagCtxt;
+
+use crate::{Callbacks, Compilation};
+
+/// Reads the configurations listed in `path`, one per line, as the `--cfg` specs to add to the
+/// ones of the command line, separated by whitespace. Whitespace within the quotes of a string, as
+/// in `feature="a b"`, doesn't separate specs. Lines starting with `#` are comments.
+pub(crate) fn read_cfg_sets(early_dcx: &EarlyDiagCtxt, path: &Path) -> Vec<Vec<String>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => early_dcx.early_fatal(format!(
+            "failed to read the configurations in `{}`: {err}",
+            path.display()
+        )),
+    };
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            split_cfg_specs(line).unwrap_or_else(|| {
+                early_dcx.early_fatal(format!(
+                    "unterminated string in the configuration on line {} of `{}`",
+                    i + 1,
+                    path.display()
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Splits `line` at the whitespace that is outside of string literals, or returns `None` if a
+/// string isn't terminated. Escapes are kept as they are, to be unescaped when the specs are
+/// parsed like the ones of `--cfg`.
+fn split_cfg_specs(line: &str) -> Option<Vec<String>> {
+    let mut specs = Vec::new();
+    let mut spec = String::new();
+    let mut chars = line.chars();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() && !in_string => {
+                if !spec.is_empty() {
+                    specs.push(std::mem::take(&mut spec));
+                }
+                continue;
+            }
+            '"' => in_string = !in_string,
+            '\\' if in_string => {
+                spec.push(c);
+                spec.push(chars.next()?);
+                continue;
+            }
+            _ => {}
+        }
+        spec.push(c);
+    }
+    if in_string {
+        return None;
+    }
+    if !spec.is_empty() {
+        specs.push(spec);
+    }
+    Some(specs)
+}
+
+/// Type-checks the crate of `config` once per configuration of `cfg_sets`, stopping after the
+/// analysis of each one. The configurations that are the same once added to the one of the
+/// command line are only checked once.
+///
+/// The callbacks are called for each configuration, like they are for a single compilation.
+pub(crate) fn check_cfg_sets(
+    mut config: interface::Config,
+    cfg_sets: Vec<Vec<String>>,
+    callbacks: &mut (dyn Callbacks + Send),
+) -> interface::Result<()> {
+    let mut checked = FxHashSet::default();
+    let mut configs = Vec::new();
+    let mut notes = Vec::new();
+    for cfg_set in cfg_sets {
+        let mut crate_cfg: Vec<String> =
+            config.crate_cfg.iter().cloned().chain(cfg_set.iter().cloned()).collect();
+        crate_cfg.sort();
+        crate_cfg.dedup();
+        if !checked.insert(crate_cfg.clone()) {
+            continue;
+        }
+
+        let mut opts = config.opts.clone();
+        // The sessions share the hygiene data of the session globals, which the incremental
+        // caches of a session can't account for.
+        opts.incremental = None;
+        let mut set_config = interface::Config {
+            opts,
+            crate_cfg,
+            crate_check_cfg: config.crate_check_cfg.clone(),
+            input: config.input.clone(),
+            output_file: config.output_file.clone(),
+            output_dir: config.output_dir.clone(),
+            ice_file: config.ice_file.clone(),
+            // All the sessions use the file loader of the first one.
+            file_loader: if configs.is_empty() { config.file_loader.take() } else { None },
+            locale_resources: config.locale_resources,
+            lint_caps: config.lint_caps.clone(),
+            psess_created: None,
+            hash_untracked_state: None,
+            register_lints: None,
+            override_queries: None,
+            make_codegen_backend: None,
+            registry: config.registry.clone(),
+            using_internal_features: Arc::clone(&config.using_internal_features),
+            expanded_args: config.expanded_args.clone(),
+        };
+        callbacks.config(&mut set_config);
+        configs.push(set_config);
+
+        notes.push(if cfg_set.is_empty() {
+            "in the configuration without additional cfgs".to_owned()
+        } else {
+            format!("in the configuration `{}`", cfg_set.join(" "))
+        });
+    }
+
+    let mut notes = notes.into_iter();
+    interface::run_compilers(configs, |compiler| {
+        let sess = &compiler.sess;
+        let note = notes.next().unwrap();
+        let early_exit = || {
+            if let Some(guar) = sess.dcx().has_errors() { Err(guar) } else { Ok(()) }
+        };
+
+        compiler.enter(|queries| {
+            // The crate is the same in every configuration, and so are its parse errors, so they
+            // are reported without any note, and only by the first session.
+            queries.parse()?;
+            sess.dcx().note_all_diagnostics(note);
+            if callbacks.after_crate_root_parsing(compiler, queries) == Compilation::Stop {
+                return early_exit();
+            }
+
+            queries.global_ctxt()?.enter(|tcx| tcx.resolver_for_lowering());
+            if callbacks.after_expansion(compiler, queries) == Compilation::Stop {
+                return early_exit();
+            }
+
+            queries.global_ctxt()?.enter(|tcx| tcx.analysis(()))?;
+            callbacks.after_analysis(compiler, queries);
+            early_exit()
+        })
+    })
+}
@@ -36,7 +36,7 @@
 use rustc_metadata::locator;
 use rustc_parse::{new_parser_from_file, new_parser_from_source_str, unwrap_or_emit_fatal};
 use rustc_session::config::{nightly_options, CG_OPTIONS, Z_OPTIONS};
-use rustc_session::config::{ErrorOutputType, Input, OutFileName, OutputType};
+use rustc_session::config::{ErrorOutputType, Input, OutFileName, OutputType, PrintKind};
 use rustc_session::getopts::{self, Matches};
 use rustc_session::lint::{Lint, LintId};
 use rustc_session::output::collect_crate_types;
@@ -83,6 +83,7 @@
 use {do_not_use_print as print, do_not_use_print as println};
 
 pub mod args;
+mod cfg_sets;
 pub mod pretty;
 #[macro_use]
 mod print;
@@ -98,8 +99,9 @@ pub(super) fn install() {}
 }
 
 use crate::session_diagnostics::{
-    RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch, RLinkRustcVersionMismatch,
-    RLinkWrongFileType, RlinkCorruptFile, RlinkNotAFile, RlinkUnableToRead,
+    ConstEvalProfileWrite, RLinkEmptyVersionNumber, RLinkEncodingVersionMismatch,
+    RLinkRustcVersionMismatch, RLinkWrongFileType, RlinkCorruptFile, RlinkNotAFile,
+    RlinkUnableToRead,
 };
 
 rustc_fluent_macro::fluent_messages! { "../messages.ftl" }
@@ -344,6 +346,12 @@ fn run_compiler(
         },
     };
 
+    if has_input && let Some(path) = config.opts.unstable_opts.cfg_sets.clone() {
+        let cfg_sets = cfg_sets::read_cfg_sets(&default_early_dcx, &path);
+        drop(default_early_dcx);
+        return cfg_sets::check_cfg_sets(config, cfg_sets, callbacks);
+    }
+
     drop(default_early_dcx);
 
     callbacks.config(&mut config);
@@ -456,6 +464,25 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            // Constants may also be evaluated during monomorphization.
+            if sess.opts.unstable_opts.print_const_eval_profile {
+                sess.code_stats.print_const_eval_profile();
+            }
+
+            if let Some(path) = &sess.opts.unstable_opts.const_eval_profile_folded {
+                if let Err(error) = fs::write(path, sess.code_stats.const_eval_profile_folded()) {
+                    sess.dcx().emit_err(ConstEvalProfileWrite { path, error: error.to_string() });
+                }
+            }
+
+            for req in sess.opts.prints.iter().filter(|p| p.kind == PrintKind::TypeLayouts) {
+                req.out.overwrite(&sess.code_stats.type_layouts_json(), sess);
+            }
+
+            queries
+                .global_ctxt()?
+                .enter(|tcx| rustc_codegen_ssa::back::abi_check::print_and_check_exported_abi(tcx));
+
             if sess.opts.unstable_opts.print_vtable_sizes {
                 let crate_name = queries.global_ctxt()?.enter(|tcx| tcx.crate_name(LOCAL_CRATE));
 
@@ -709,9 +736,15 @@ fn print_crate_info(
     #[allow(unused_imports)]
     use {do_not_use_safe_print as safe_print, do_not_use_safe_print as safe_println};
 
-    // NativeStaticLibs and LinkArgs are special - printed during linking
+    // NativeStaticLibs and LinkArgs are special - printed during linking,
+    // TypeLayouts and ExportedAbi are printed after codegen
     // (empty iterator returns true)
-    if sess.opts.prints.iter().all(|p| p.kind == NativeStaticLibs || p.kind == LinkArgs) {
+    if sess
+        .opts
+        .prints
+        .iter()
+        .all(|p| matches!(p.kind, NativeStaticLibs | LinkArgs | TypeLayouts | ExportedAbi))
+    {
         return Compilation::Continue;
     }
 
@@ -863,6 +896,8 @@ fn print_crate_info(
             // Any output here interferes with Cargo's parsing of other printed output
             NativeStaticLibs => {}
             LinkArgs => {}
+            TypeLayouts => {}
+            ExportedAbi => {}
             SplitDebuginfo => {
                 use rustc_target::spec::SplitDebuginfo::{Off, Packed, Unpacked};
 
@@ -38,6 +38,13 @@ pub(crate) struct RlinkCorruptFile<'a> {
     pub file: &'a std::path::Path,
 }
 
+#[derive(Diagnostic)]
+#[diag(driver_impl_const_eval_profile_write)]
+pub(crate) struct ConstEvalProfileWrite<'a> {
+    pub path: &'a std::path::Path,
+    pub error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(driver_impl_ice)]
 pub(crate) struct Ice;
@@ -508,6 +508,9 @@ struct DiagCtxtInner {
     /// The file where the ICE information is stored. This allows delayed_span_bug backtraces to be
     /// stored along side the main panic backtrace.
     ice_file: Option<PathBuf>,
+
+    /// A note added to each error and warning emitted.
+    note_all_diagnostics: Option<String>,
 }
 
 /// A key denoting where from a diagnostic was stashed.
@@ -1119,6 +1122,12 @@ pub fn set_must_produce_diag(&self) {
         );
         self.inner.borrow_mut().must_produce_diag = Some(Backtrace::capture());
     }
+
+    /// Adds `note` to each error and warning emitted from now on, e.g. to tell which of the
+    /// sessions of a compiler invocation that runs several of them a diagnostic comes from.
+    pub fn note_all_diagnostics(&self, note: String) {
+        self.inner.borrow_mut().note_all_diagnostics = Some(note);
+    }
 }
 
 // This `impl` block contains only the public diagnostic creation/emission API.
@@ -1414,6 +1423,7 @@ fn new(emitter: Box<DynEmitter>) -> Self {
             unstable_expect_diagnostics: Vec::new(),
             fulfilled_expectations: Default::default(),
             ice_file: None,
+            note_all_diagnostics: None,
         }
     }
 
@@ -1580,6 +1590,11 @@ fn emit_diagnostic(&mut self, mut diagnostic: DiagInner) -> Option<ErrorGuarante
                     let msg = "duplicate diagnostic emitted due to `-Z deduplicate-diagnostics=no`";
                     diagnostic.sub(Note, msg, MultiSpan::new());
                 }
+                if let Some(note) = &self.note_all_diagnostics
+                    && (is_error || matches!(diagnostic.level, ForceWarning(_) | Warning))
+                {
+                    diagnostic.sub(Note, note.clone(), MultiSpan::new());
+                }
 
                 if is_error {
                     self.deduplicated_err_count += 1;
@@ -24,6 +24,10 @@ expand_cannot_be_name_of_macro =
 expand_collapse_debuginfo_illegal =
     illegal value for attribute #[collapse_debuginfo(no|external|yes)]
 
+expand_couldnt_dump_cfg_report = could not write the cfg report: {$error}
+
+expand_couldnt_dump_macro_expansions = could not write the macro expansions: {$error}
+
 expand_count_repetition_misplaced =
     `count` can not be placed inside the inner-most repetition
 
@@ -58,6 +62,12 @@ expand_feature_removed =
     .label = feature has been removed
     .reason = {$reason}
 
+expand_format_string_malformed =
+    `#[format_string]` expects the name of the metavariable bound to the format string and, optionally, the name of the one bound to its arguments
+
+expand_format_string_unbound_metavar =
+    no rule of this macro binds a metavariable `${$name}`
+
 expand_glob_delegation_outside_impls =
     glob delegation is only supported in impls
 
@@ -133,6 +143,9 @@ expand_only_one_word =
 expand_proc_macro_back_compat = using an old version of `{$crate_name}`
     .note = older versions of the `{$crate_name}` crate no longer compile; please update to `{$crate_name}` v{$fixed_version}, or switch to one of the `{$crate_name}` alternatives
 
+expand_proc_macro_cache_write_failed =
+    failed to write the cache of proc macro expansions to `{$path}`: {$err}
+
 expand_proc_macro_derive_panicked =
     proc-macro derive panicked
     .help = message: {$message}
@@ -1,7 +1,10 @@
 use crate::base::ast::NestedMetaItem;
+use crate::cfg_report::CfgReport;
 use crate::errors;
 use crate::expand::{self, AstFragment, Invocation};
+use crate::mbe::expansion_trace::ExpansionStep;
 use crate::module::DirOwnership;
+use crate::proc_macro_cache::ProcMacroCache;
 
 use rustc_ast::attr::MarkedAttrs;
 use rustc_ast::ptr::P;
@@ -343,6 +346,11 @@ fn expand<'cx>(
 pub type MacroExpanderFn =
     for<'cx> fn(&'cx mut ExtCtxt<'_>, Span, TokenStream) -> MacroExpanderResult<'cx>;
 
+/// Checks the input of `format_args!` made of the format string and arguments given to a macro
+/// declared with `#[format_string]`, emitting the errors that `format_args!` would. Returns the
+/// `format_args!` expression if the input is valid.
+pub type FormatStringChecker = fn(&mut ExtCtxt<'_>, Span, TokenStream) -> Option<P<ast::Expr>>;
+
 impl<F> TTMacroExpander for F
 where
     F: for<'cx> Fn(&'cx mut ExtCtxt<'_>, Span, TokenStream) -> MacroExpanderResult<'cx>,
@@ -1148,6 +1156,12 @@ pub struct ExtCtxt<'a> {
     /// in the AST, but insert it here so that we know
     /// not to expand it again.
     pub(super) expanded_inert_attrs: MarkedAttrs,
+    /// The expansions of `#[proc_macro_pure]` macros, cached in the incremental directory.
+    pub(crate) proc_macro_cache: ProcMacroCache,
+    /// The expansions of declarative macros, recorded for `-Z dump-macro-expansions`.
+    pub(crate) macro_expansion_steps: Vec<ExpansionStep>,
+    /// The `cfg` and `cfg_attr` attributes evaluated, recorded for `-Z dump-cfg-report`.
+    pub cfg_report: Option<CfgReport>,
 }
 
 impl<'a> ExtCtxt<'a> {
@@ -1177,6 +1191,9 @@ pub fn new(
             expansions: FxIndexMap::default(),
             expanded_inert_attrs: MarkedAttrs::new(),
             buffered_early_lint: vec![],
+            proc_macro_cache: ProcMacroCache::default(),
+            macro_expansion_steps: Vec::new(),
+            cfg_report: CfgReport::new(sess),
         }
     }
 
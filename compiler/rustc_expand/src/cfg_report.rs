@@ -0,0 +1,137 @@
+//! Implementation of `-Z dump-cfg-report`, which records each `#[cfg]` and `#[cfg_attr]` evaluated
+//! while expanding a crate, and writes them to a JSON file. The code left out of the current
+//! configuration is the code under the `#[cfg]`s that are not active. `src/etc/cfg-coverage.py`
+//! merges the reports of several builds to find the code that none of them compiles.
+
+use std::cell::RefCell;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use rustc_ast::{MetaItem, NestedMetaItem};
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_session::config::SwitchWithOptPath;
+use rustc_session::Session;
+use rustc_span::{BytePos, Span};
+
+use crate::base::ExtCtxt;
+use crate::errors::CouldntDumpCfgReport;
+
+#[derive(Clone, Copy)]
+pub(crate) enum CfgAttrKind {
+    Cfg,
+    CfgAttr,
+}
+
+struct CfgDecision {
+    kind: CfgAttrKind,
+    span: Span,
+    predicate: String,
+    active: bool,
+}
+
+/// The `#[cfg]` and `#[cfg_attr]` attributes evaluated so far, by location in the source. An
+/// attribute can be evaluated more than once, e.g. in the input of a derive macro or in each
+/// expansion of a `macro_rules!` macro, but is only reported once.
+pub struct CfgReport {
+    decisions: RefCell<FxIndexMap<(BytePos, BytePos), CfgDecision>>,
+}
+
+#[derive(serde::Serialize)]
+struct CfgJson {
+    /// `cfg` or `cfg_attr`.
+    attr: &'static str,
+    predicate: String,
+    span: String,
+    /// Whether the predicate holds, i.e. whether the item is kept for a `cfg`, or whether the
+    /// attributes are applied for a `cfg_attr`.
+    active: bool,
+}
+
+#[derive(serde::Serialize)]
+struct CrateJson<'a> {
+    crate_name: &'a str,
+    /// The names and `name="value"` pairs set in the current configuration.
+    configuration: Vec<String>,
+    cfgs: Vec<CfgJson>,
+}
+
+impl CfgReport {
+    /// Returns an empty report, if `-Z dump-cfg-report` is enabled.
+    pub(crate) fn new(sess: &Session) -> Option<CfgReport> {
+        sess.opts
+            .unstable_opts
+            .dump_cfg_report
+            .enabled()
+            .then(|| CfgReport { decisions: Default::default() })
+    }
+
+    pub(crate) fn record(&self, kind: CfgAttrKind, span: Span, predicate: &MetaItem, active: bool) {
+        let predicate =
+            pprust::meta_list_item_to_string(&NestedMetaItem::MetaItem(predicate.clone()));
+        // The syntax context of the span differs between the expansions of a macro.
+        let location = (span.lo(), span.hi());
+        self.decisions.borrow_mut().insert(location, CfgDecision { kind, span, predicate, active });
+    }
+}
+
+/// Writes the report of `cx` to `<crate>.cfg-report.json`, in the directory given to
+/// `-Z dump-cfg-report`.
+pub(crate) fn dump_cfg_report(cx: &ExtCtxt<'_>) {
+    let (Some(report), SwitchWithOptPath::Enabled(directory)) =
+        (&cx.cfg_report, &cx.sess.opts.unstable_opts.dump_cfg_report)
+    else {
+        return;
+    };
+    if let Err(err) = write_cfg_report(cx, report, directory.as_deref()) {
+        cx.dcx().emit_err(CouldntDumpCfgReport { error: err.to_string() });
+    }
+}
+
+fn write_cfg_report(
+    cx: &ExtCtxt<'_>,
+    report: &CfgReport,
+    directory: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let directory = if let Some(directory) = directory {
+        fs::create_dir_all(directory)?;
+        directory
+    } else {
+        Path::new(".")
+    };
+
+    let mut configuration: Vec<String> = cx
+        .sess
+        .psess
+        .config
+        .iter()
+        .map(|&(name, value)| match value {
+            Some(value) => format!("{name}={value:?}"),
+            None => name.to_string(),
+        })
+        .collect();
+    configuration.sort();
+
+    let source_map = cx.source_map();
+    let cfgs = report
+        .decisions
+        .borrow()
+        .values()
+        .map(|decision| CfgJson {
+            attr: match decision.kind {
+                CfgAttrKind::Cfg => "cfg",
+                CfgAttrKind::CfgAttr => "cfg_attr",
+            },
+            predicate: decision.predicate.clone(),
+            span: source_map.span_to_embeddable_string(decision.span),
+            active: decision.active,
+        })
+        .collect();
+
+    let crate_name = &cx.ecfg.crate_name;
+    let file = File::create(directory.join(format!("{crate_name}.cfg-report.json")))?;
+    let krate = CrateJson { crate_name, configuration, cfgs };
+    serde_json::to_writer_pretty(BufWriter::new(file), &krate)?;
+    Ok(())
+}
@@ -1,5 +1,8 @@
 //! Conditional compilation stripping.
 
+pub use crate::cfg_report::CfgReport;
+
+use crate::cfg_report::CfgAttrKind;
 use crate::errors::{
     FeatureNotAllowed, FeatureRemoved, FeatureRemovedReason, InvalidCfg, MalformedFeatureAttribute,
     MalformedFeatureAttributeHelp, RemoveExprNotSupported,
@@ -32,6 +35,8 @@ pub struct StripUnconfigured<'a> {
     /// which needs eager expansion of `cfg` and `cfg_attr`
     pub config_tokens: bool,
     pub lint_node_id: NodeId,
+    /// Where to record the `cfg` and `cfg_attr` attributes evaluated, for `-Z dump-cfg-report`.
+    pub cfg_report: Option<&'a CfgReport>,
 }
 
 pub fn features(sess: &Session, krate_attrs: &[Attribute], crate_name: Symbol) -> Features {
@@ -129,6 +134,9 @@ pub fn pre_configure_attrs(sess: &Session, attrs: &[Attribute]) -> ast::AttrVec
         features: None,
         config_tokens: false,
         lint_node_id: ast::CRATE_NODE_ID,
+        // The attributes of the crate are configured again when it's expanded, which is where
+        // they are recorded for `-Z dump-cfg-report`.
+        cfg_report: None,
     };
     attrs
         .iter()
@@ -266,7 +274,12 @@ pub(crate) fn expand_cfg_attr(&self, attr: &Attribute, recursive: bool) -> Vec<A
             );
         }
 
-        if !attr::cfg_matches(&cfg_predicate, &self.sess, self.lint_node_id, self.features) {
+        let active =
+            attr::cfg_matches(&cfg_predicate, &self.sess, self.lint_node_id, self.features);
+        if let Some(cfg_report) = self.cfg_report {
+            cfg_report.record(CfgAttrKind::CfgAttr, attr.span, &cfg_predicate, active);
+        }
+        if !active {
             return vec![];
         }
 
@@ -374,7 +387,12 @@ pub(crate) fn cfg_true(&self, attr: &Attribute) -> (bool, Option<MetaItem>) {
         };
         (
             parse_cfg(&meta_item, self.sess).map_or(true, |meta_item| {
-                attr::cfg_matches(meta_item, &self.sess, self.lint_node_id, self.features)
+                let active =
+                    attr::cfg_matches(meta_item, &self.sess, self.lint_node_id, self.features);
+                if let Some(cfg_report) = self.cfg_report {
+                    cfg_report.record(CfgAttrKind::Cfg, attr.span, meta_item, active);
+                }
+                active
             }),
             Some(meta_item),
         )
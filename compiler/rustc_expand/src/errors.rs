@@ -5,6 +5,7 @@
 use rustc_span::symbol::{Ident, MacroRulesNormalizedIdent};
 use rustc_span::{Span, Symbol};
 use std::borrow::Cow;
+use std::path::Path;
 
 #[derive(Diagnostic)]
 #[diag(expand_expr_repeat_no_syntax_vars)]
@@ -20,6 +21,18 @@ pub(crate) struct MustRepeatOnce {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(expand_couldnt_dump_cfg_report)]
+pub(crate) struct CouldntDumpCfgReport {
+    pub(crate) error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand_couldnt_dump_macro_expansions)]
+pub(crate) struct CouldntDumpMacroExpansions {
+    pub(crate) error: String,
+}
+
 #[derive(Diagnostic)]
 #[diag(expand_count_repetition_misplaced)]
 pub(crate) struct CountRepetitionMisplaced {
@@ -406,6 +419,13 @@ pub struct ProcMacroDeriveTokens {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(expand_proc_macro_cache_write_failed)]
+pub(crate) struct ProcMacroCacheWriteFailed<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(expand_duplicate_matcher_binding)]
 pub struct DuplicateMatcherBinding {
@@ -442,6 +462,21 @@ pub(crate) struct EmptyDelegationMac {
     pub kind: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(expand_format_string_malformed)]
+pub(crate) struct FormatStringMalformed {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand_format_string_unbound_metavar)]
+pub(crate) struct FormatStringUnboundMetavar {
+    #[primary_span]
+    pub span: Span,
+    pub name: Ident,
+}
+
 #[derive(Diagnostic)]
 #[diag(expand_glob_delegation_outside_impls)]
 pub(crate) struct GlobDelegationOutsideImpls {
@@ -1,4 +1,5 @@
 use crate::base::*;
+use crate::cfg_report;
 use crate::config::StripUnconfigured;
 use crate::errors::{
     EmptyDelegationMac, GlobDelegationOutsideImpls, GlobDelegationTraitlessQpath, IncompleteParse,
@@ -6,6 +7,7 @@
     WrongFragmentKind,
 };
 use crate::mbe::diagnostics::annotate_err_with_kind;
+use crate::mbe::expansion_trace;
 use crate::module::{mod_dir_path, parse_external_mod, DirOwnership, ParsedExternalMod};
 use crate::placeholders::{placeholder, PlaceholderExpander};
 
@@ -415,6 +417,9 @@ pub fn expand_crate(&mut self, krate: ast::Crate) -> ast::Crate {
         let krate = self.fully_expand_fragment(AstFragment::Crate(krate)).make_crate();
         assert_eq!(krate.id, ast::CRATE_NODE_ID);
         self.cx.trace_macros_diag();
+        self.cx.proc_macro_cache.save(self.cx.sess);
+        expansion_trace::dump_macro_expansions(self.cx);
+        cfg_report::dump_cfg_report(self.cx);
         krate
     }
 
@@ -1771,6 +1776,7 @@ fn cfg(&self) -> StripUnconfigured<'_> {
             features: Some(self.cx.ecfg.features),
             config_tokens: false,
             lint_node_id: self.cx.current_expansion.lint_node_id,
+            cfg_report: self.cx.cfg_report.as_ref(),
         }
     }
 
@@ -2217,6 +2223,9 @@ pub struct ExpansionConfig<'feat> {
     pub span_debug: bool,
     /// If true, show backtraces for proc-macro panics
     pub proc_macro_backtrace: bool,
+    /// Checks the format strings of the macros declared with `#[format_string]`. It is provided
+    /// by `rustc_builtin_macros`, which implements `format_args!`.
+    pub check_format_string: Option<FormatStringChecker>,
 }
 
 impl ExpansionConfig<'_> {
@@ -2229,6 +2238,7 @@ pub fn default(crate_name: String, features: &Features) -> ExpansionConfig<'_> {
             should_test: false,
             span_debug: false,
             proc_macro_backtrace: false,
+            check_format_string: None,
         }
     }
 }
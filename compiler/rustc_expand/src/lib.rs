@@ -18,11 +18,14 @@
 extern crate proc_macro as pm;
 
 mod build;
+mod cfg_report;
 mod errors;
 // FIXME(Nilstrieb) Translate macro_rules diagnostics
 #[allow(rustc::untranslatable_diagnostic)]
 mod mbe;
 mod placeholders;
+mod proc_macro_cache;
+mod proc_macro_sandbox;
 mod proc_macro_server;
 
 pub use mbe::macro_rules::compile_declarative_macro;
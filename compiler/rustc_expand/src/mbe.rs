@@ -4,8 +4,10 @@
 //! official terminology: "declarative macros".
 
 pub(crate) mod diagnostics;
+pub(crate) mod expansion_trace;
 pub(crate) mod macro_rules;
 
+mod format_string;
 mod macro_check;
 mod macro_parser;
 mod metavar_expr;
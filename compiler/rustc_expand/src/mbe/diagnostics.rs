@@ -7,13 +7,14 @@
 use rustc_ast::token::{self, Token, TokenKind};
 use rustc_ast::tokenstream::TokenStream;
 use rustc_ast_pretty::pprust;
-use rustc_errors::{Applicability, Diag, DiagMessage};
+use rustc_errors::{pluralize, Applicability, Diag, DiagMessage};
 use rustc_macros::Subdiagnostic;
 use rustc_parse::parser::{Parser, Recovery};
 use rustc_span::source_map::SourceMap;
 use rustc_span::symbol::Ident;
 use rustc_span::{ErrorGuaranteed, Span};
 use std::borrow::Cow;
+use std::mem;
 use tracing::debug;
 
 use super::macro_rules::{parser_from_cx, NoopTracker};
@@ -25,6 +26,7 @@ pub(super) fn failed_to_match_macro<'cx>(
     name: Ident,
     arg: TokenStream,
     lhses: &[Vec<MatcherLoc>],
+    lhs_spans: &[Span],
 ) -> Box<dyn MacResult + 'cx> {
     let psess = &cx.sess.psess;
 
@@ -48,6 +50,7 @@ pub(super) fn failed_to_match_macro<'cx>(
         return result;
     }
 
+    let arm_failures = tracker.arm_failures;
     let Some(BestFailure { token, msg: label, remaining_matcher, .. }) = tracker.best_failure
     else {
         return DummyResult::any(sp, cx.dcx().span_delayed_bug(sp, "failed to match a macro"));
@@ -81,6 +84,33 @@ pub(super) fn failed_to_match_macro<'cx>(
         }
     }
 
+    if cx.sess.opts.unstable_opts.macro_match_details {
+        for (i, (failure, &lhs_span)) in arm_failures.iter().zip(lhs_spans).enumerate() {
+            let found = match failure.token.kind {
+                token::Eof => "the end of the macro call".to_string(),
+                _ => format!("`{}`", pprust::token_to_string(&failure.token)),
+            };
+            // The parser is created pointing at the first token.
+            let matched = failure.position_in_tokenstream.saturating_sub(1);
+            let expected = match &failure.expected[..] {
+                [] => "nothing".to_string(),
+                [expected] => expected.to_string(),
+                [rest @ .., last] => {
+                    let rest: Vec<_> = rest.iter().map(|expected| expected.to_string()).collect();
+                    format!("{} or {last}", rest.join(", "))
+                }
+            };
+            err.span_note(
+                lhs_span,
+                format!(
+                    "rule #{} matched {matched} token{}, then expected {expected}, but found {found}",
+                    i + 1,
+                    pluralize!(matched),
+                ),
+            );
+        }
+    }
+
     // Check whether there's a missing comma in this macro call, like `println!("{}" a);`
     if let Some((arg, comma_span)) = arg.add_comma() {
         for lhs in lhses {
@@ -114,6 +144,13 @@ struct CollectTrackerAndEmitter<'a, 'cx, 'matcher> {
     remaining_matcher: Option<&'matcher MatcherLoc>,
     /// Which arm's failure should we report? (the one furthest along)
     best_failure: Option<BestFailure>,
+    /// The failures of all the arms, in order, for `-Z macro-match-details`.
+    arm_failures: Vec<ArmFailure>,
+    /// The matcher positions that the current arm tried on the last token it reached, which are
+    /// what it expected if it fails to match.
+    arm_expected: Vec<&'matcher MatcherLoc>,
+    /// The metavariables that could each parse the input where a local ambiguity was found.
+    ambiguous_metavars: Vec<&'matcher MatcherLoc>,
     root_span: Span,
     result: Option<Box<dyn MacResult + 'cx>>,
 }
@@ -125,6 +162,13 @@ struct BestFailure {
     remaining_matcher: MatcherLoc,
 }
 
+/// Why an arm failed to match, for `-Z macro-match-details`.
+struct ArmFailure {
+    token: Token,
+    position_in_tokenstream: usize,
+    expected: Vec<MatcherLoc>,
+}
+
 impl BestFailure {
     fn is_better_position(&self, position: usize) -> bool {
         position > self.position_in_tokenstream
@@ -144,9 +188,26 @@ fn before_match_loc(&mut self, parser: &TtParser, matcher: &'matcher MatcherLoc)
         {
             self.remaining_matcher = Some(matcher);
         }
+
+        // The other positions only lead to the ones that match a token.
+        let matches_token = matches!(
+            matcher,
+            MatcherLoc::Token { .. }
+                | MatcherLoc::SequenceSep { .. }
+                | MatcherLoc::MetaVarDecl { .. }
+                | MatcherLoc::Eof
+        );
+        if matches_token && !self.arm_expected.contains(&matcher) {
+            self.arm_expected.push(matcher);
+        }
+    }
+
+    fn before_token(&mut self) {
+        self.arm_expected.clear();
     }
 
     fn after_arm(&mut self, result: &NamedParseResult<Self::Failure>) {
+        let arm_expected = mem::take(&mut self.arm_expected);
         match result {
             Success(_) => {
                 // Nonterminal parser recovery might turn failed matches into successful ones,
@@ -159,6 +220,12 @@ fn after_arm(&mut self, result: &NamedParseResult<Self::Failure>) {
             Failure((token, approx_position, msg)) => {
                 debug!(?token, ?msg, "a new failure of an arm");
 
+                self.arm_failures.push(ArmFailure {
+                    token: token.clone(),
+                    position_in_tokenstream: *approx_position,
+                    expected: arm_expected.into_iter().cloned().collect(),
+                });
+
                 if self
                     .best_failure
                     .as_ref()
@@ -177,13 +244,28 @@ fn after_arm(&mut self, result: &NamedParseResult<Self::Failure>) {
             }
             Error(err_sp, msg) => {
                 let span = err_sp.substitute_dummy(self.root_span);
-                let guar = self.cx.dcx().span_err(span, msg.clone());
+                let mut err = self.cx.dcx().struct_span_err(span, msg.clone());
+                if self.cx.sess.opts.unstable_opts.macro_match_details {
+                    for metavar in &self.ambiguous_metavars {
+                        if let Some(metavar_span) = metavar.span() {
+                            err.span_note(
+                                metavar_span,
+                                format!("the input here could be parsed by {metavar}"),
+                            );
+                        }
+                    }
+                }
+                let guar = err.emit();
                 self.result = Some(DummyResult::any(span, guar));
             }
             ErrorReported(guar) => self.result = Some(DummyResult::any(self.root_span, *guar)),
         }
     }
 
+    fn local_ambiguity(&mut self, metavars: Vec<&'matcher MatcherLoc>) {
+        self.ambiguous_metavars = metavars;
+    }
+
     fn description() -> &'static str {
         "detailed"
     }
@@ -195,7 +277,16 @@ fn recovery() -> Recovery {
 
 impl<'a, 'cx> CollectTrackerAndEmitter<'a, 'cx, '_> {
     fn new(cx: &'a mut ExtCtxt<'cx>, root_span: Span) -> Self {
-        Self { cx, remaining_matcher: None, best_failure: None, root_span, result: None }
+        Self {
+            cx,
+            remaining_matcher: None,
+            best_failure: None,
+            arm_failures: Vec::new(),
+            arm_expected: Vec::new(),
+            ambiguous_metavars: Vec::new(),
+            root_span,
+            result: None,
+        }
     }
 }
 
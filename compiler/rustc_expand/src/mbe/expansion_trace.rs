@@ -0,0 +1,201 @@
+//! Implementation of `-Z dump-macro-expansions`, which writes each step of the expansion of the
+//! declarative macros of a crate to a JSON file, for tools that want to step through them.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use rustc_ast::token::{self, Token};
+use rustc_ast::tokenstream::TokenStream;
+use rustc_ast_pretty::pprust;
+use rustc_parse::parser::ParseNtResult;
+use rustc_session::config::SwitchWithOptPath;
+use rustc_span::hygiene::LocalExpnId;
+use rustc_span::source_map::SourceMap;
+use rustc_span::symbol::Ident;
+use rustc_span::Span;
+
+use crate::base::ExtCtxt;
+use crate::errors::CouldntDumpMacroExpansions;
+use crate::mbe::diagnostics::parse_failure_msg;
+use crate::mbe::macro_parser::{Error, ErrorReported, Failure, Success};
+use crate::mbe::macro_parser::{NamedMatch, NamedMatches, NamedParseResult};
+use crate::mbe::macro_rules::Tracker;
+
+/// An expansion of a declarative macro.
+#[derive(serde::Serialize)]
+pub(crate) struct ExpansionStep {
+    /// The id of the expansion. The expansions of the macro calls in its output have it as their
+    /// `parent`.
+    id: u32,
+    /// The expansion that the macro call comes from, if it isn't written in the source.
+    parent: Option<u32>,
+    macro_name: String,
+    call_site: String,
+    input: String,
+    /// The arms that were tried, in order.
+    arms: Vec<ArmJson>,
+    matched_arm: Option<usize>,
+    /// The fragments bound to the metavariables of the matched arm. The fragments of a metavariable
+    /// under repetitions are nested in one array per repetition.
+    bindings: BTreeMap<String, BindingJson>,
+    /// The tokens that the macro call expands to, if the expansion succeeded.
+    output: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+struct ArmJson {
+    index: usize,
+    /// `matched`, `failed` or `error`.
+    result: &'static str,
+    /// Why the arm didn't match.
+    message: Option<String>,
+    /// The span of the token of the input that the arm didn't expect.
+    span: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(untagged)]
+enum BindingJson {
+    Fragment(String),
+    Repetition(Vec<BindingJson>),
+}
+
+#[derive(serde::Serialize)]
+struct CrateJson<'a> {
+    crate_name: &'a str,
+    expansions: &'a [ExpansionStep],
+}
+
+impl ExpansionStep {
+    pub(crate) fn new(cx: &ExtCtxt<'_>, name: Ident, call_site: Span, input: &TokenStream) -> Self {
+        let id = cx.current_expansion.id;
+        let parent = id.expn_data().parent.as_local().filter(|&parent| parent != LocalExpnId::ROOT);
+        ExpansionStep {
+            id: id.as_u32(),
+            parent: parent.map(LocalExpnId::as_u32),
+            macro_name: name.to_string(),
+            call_site: cx.source_map().span_to_embeddable_string(call_site),
+            input: pprust::tts_to_string(input),
+            arms: Vec::new(),
+            matched_arm: None,
+            bindings: BTreeMap::new(),
+            output: None,
+        }
+    }
+
+    pub(crate) fn set_matched_arm(&mut self, index: usize, named_matches: &NamedMatches) {
+        self.matched_arm = Some(index);
+        self.bindings = named_matches
+            .iter()
+            .map(|(ident, named_match)| (ident.to_string(), binding_to_json(named_match)))
+            .collect();
+    }
+
+    pub(crate) fn set_output(&mut self, output: &TokenStream) {
+        self.output = Some(pprust::tts_to_string(output));
+    }
+}
+
+fn binding_to_json(named_match: &NamedMatch) -> BindingJson {
+    match named_match {
+        NamedMatch::MatchedSeq(seq) => {
+            BindingJson::Repetition(seq.iter().map(binding_to_json).collect())
+        }
+        NamedMatch::MatchedSingle(nt) => BindingJson::Fragment(match nt {
+            ParseNtResult::Tt(tt) => pprust::tt_to_string(tt),
+            ParseNtResult::Ident(ident, is_raw) => {
+                pprust::token_to_string(&Token::new(token::Ident(ident.name, *is_raw), ident.span))
+                    .into_owned()
+            }
+            ParseNtResult::Lifetime(ident) => ident.to_string(),
+            ParseNtResult::Nt(nt) => pprust::nonterminal_to_string(nt),
+        }),
+    }
+}
+
+/// A tracker that records the result of matching each arm in `ExpansionStep::arms`.
+pub(crate) struct ArmTracker<'a> {
+    step: &'a mut ExpansionStep,
+    source_map: &'a SourceMap,
+    call_site: Span,
+}
+
+impl<'a> ArmTracker<'a> {
+    pub(crate) fn new(
+        step: &'a mut ExpansionStep,
+        source_map: &'a SourceMap,
+        call_site: Span,
+    ) -> Self {
+        ArmTracker { step, source_map, call_site }
+    }
+
+    fn span_to_string(&self, span: Span) -> String {
+        self.source_map.span_to_embeddable_string(span.substitute_dummy(self.call_site))
+    }
+}
+
+impl<'matcher> Tracker<'matcher> for ArmTracker<'_> {
+    type Failure = (Token, usize, &'static str);
+
+    fn build_failure(tok: Token, position: usize, msg: &'static str) -> Self::Failure {
+        (tok, position, msg)
+    }
+
+    fn after_arm(&mut self, result: &NamedParseResult<Self::Failure>) {
+        let index = self.step.arms.len();
+        let arm = match result {
+            Success(_) => ArmJson { index, result: "matched", message: None, span: None },
+            Failure((token, _, _)) => ArmJson {
+                index,
+                result: "failed",
+                message: Some(parse_failure_msg(token, None).into_owned()),
+                span: Some(self.span_to_string(token.span)),
+            },
+            Error(span, msg) => ArmJson {
+                index,
+                result: "error",
+                message: Some(msg.clone()),
+                span: Some(self.span_to_string(*span)),
+            },
+            ErrorReported(_) => ArmJson { index, result: "error", message: None, span: None },
+        };
+        self.step.arms.push(arm);
+    }
+
+    fn description() -> &'static str {
+        "expansion-trace"
+    }
+}
+
+/// Writes the expansion steps recorded in `cx` to `<crate>.macro-expansions.json`, in the
+/// directory given to `-Z dump-macro-expansions`.
+pub(crate) fn dump_macro_expansions(cx: &ExtCtxt<'_>) {
+    let SwitchWithOptPath::Enabled(ref directory) =
+        cx.sess.opts.unstable_opts.dump_macro_expansions
+    else {
+        return;
+    };
+    if let Err(err) = write_macro_expansions(cx, directory.as_deref()) {
+        cx.dcx().emit_err(CouldntDumpMacroExpansions { error: err.to_string() });
+    }
+}
+
+fn write_macro_expansions(
+    cx: &ExtCtxt<'_>,
+    directory: Option<&Path>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let directory = if let Some(directory) = directory {
+        fs::create_dir_all(directory)?;
+        directory
+    } else {
+        Path::new(".")
+    };
+
+    let crate_name = &cx.ecfg.crate_name;
+    let file = File::create(directory.join(format!("{crate_name}.macro-expansions.json")))?;
+    let krate = CrateJson { crate_name, expansions: &cx.macro_expansion_steps };
+    serde_json::to_writer_pretty(BufWriter::new(file), &krate)?;
+    Ok(())
+}
@@ -0,0 +1,203 @@
+//! Support for `#[format_string(fmt, args)]` on declarative macros, which declares that the
+//! metavariable `$fmt` of the macro is bound to a format string, and `$args` to its arguments, so
+//! that each call of the macro is checked like a call of `format_args!` would be.
+
+use rustc_ast as ast;
+use rustc_ast::ptr::P;
+use rustc_ast::token;
+use rustc_ast::tokenstream::{TokenStream, TokenTree};
+use rustc_ast::{AttrVec, LocalKind, DUMMY_NODE_ID};
+use rustc_parse::parser::ParseNtResult;
+use rustc_session::Session;
+use rustc_span::symbol::{sym, Ident, MacroRulesNormalizedIdent};
+use rustc_span::Span;
+use thin_vec::thin_vec;
+
+use crate::base::ExtCtxt;
+use crate::errors::{FormatStringMalformed, FormatStringUnboundMetavar};
+use crate::expand::AstFragment;
+use crate::mbe;
+use crate::mbe::macro_parser::{MatchedSeq, MatchedSingle, NamedMatch, NamedMatches};
+
+/// The metavariables named by the `#[format_string]` attribute of a macro.
+pub(super) struct FormatStringMetavars {
+    /// The metavariable bound to the format string.
+    fmt: MacroRulesNormalizedIdent,
+    /// The metavariable bound to the arguments, one argument per repetition.
+    args: Option<MacroRulesNormalizedIdent>,
+}
+
+impl FormatStringMetavars {
+    /// Reads the `#[format_string]` attribute of a macro, if any, and checks that the
+    /// metavariables it names are bound by some rule of the macro.
+    pub(super) fn from_attrs(
+        sess: &Session,
+        attrs: &[ast::Attribute],
+        lhses: &[mbe::TokenTree],
+    ) -> Option<FormatStringMetavars> {
+        let attr = ast::attr::find_by_name(attrs, sym::format_string)?;
+        // A malformed attribute is reported when checking it against its template.
+        let list = attr.meta_item_list()?;
+        let idents: Option<Vec<Ident>> =
+            list.iter().map(|item| item.ident().filter(|_| item.is_word())).collect();
+        let (fmt, args) = match idents.as_deref() {
+            Some(&[fmt]) => (fmt, None),
+            Some(&[fmt, args]) => (fmt, Some(args)),
+            _ => {
+                sess.dcx().emit_err(FormatStringMalformed { span: attr.span });
+                return None;
+            }
+        };
+
+        let mut is_valid = true;
+        for ident in [Some(fmt), args].into_iter().flatten() {
+            let name = MacroRulesNormalizedIdent::new(ident);
+            if !lhses.iter().any(|lhs| binds_metavar(lhs, name)) {
+                sess.dcx().emit_err(FormatStringUnboundMetavar { span: ident.span, name: ident });
+                is_valid = false;
+            }
+        }
+        is_valid.then(|| FormatStringMetavars {
+            fmt: MacroRulesNormalizedIdent::new(fmt),
+            args: args.map(MacroRulesNormalizedIdent::new),
+        })
+    }
+
+    /// Returns the input of `format_args!` made of the format string and the arguments that the
+    /// matched rule bound, or `None` if the rule doesn't bind the format string.
+    pub(super) fn format_args_input(
+        &self,
+        named_matches: &NamedMatches,
+        call_site: Span,
+    ) -> Option<TokenStream> {
+        let fmt @ MatchedSingle(_) = named_matches.get(&self.fmt)? else {
+            // The format string is under a repetition, there is no single call to check.
+            return None;
+        };
+        let mut tts = Vec::new();
+        push_format_string(fmt, &mut tts);
+        match self.args.and_then(|args| named_matches.get(&args)) {
+            Some(MatchedSeq(args)) => {
+                for arg in args {
+                    tts.push(TokenTree::token_alone(token::Comma, call_site));
+                    push_fragment(arg, &mut tts);
+                }
+            }
+            Some(arg @ MatchedSingle(_)) => {
+                tts.push(TokenTree::token_alone(token::Comma, call_site));
+                push_fragment(arg, &mut tts);
+            }
+            None => {}
+        }
+        Some(TokenStream::new(tts))
+    }
+}
+
+/// Makes the statement `if false { let _ = format_args!(..); }` out of the `format_args!`
+/// expression that checked the format string of a call, to be added to the expansion of the call
+/// so that the names captured by the format string are resolved, without evaluating the
+/// arguments a second time.
+pub(super) fn format_string_check(
+    cx: &ExtCtxt<'_>,
+    sp: Span,
+    format_args: P<ast::Expr>,
+) -> ast::Stmt {
+    let sp = cx.with_def_site_ctxt(sp);
+    let local = P(ast::Local {
+        pat: cx.pat_wild(sp),
+        ty: None,
+        id: DUMMY_NODE_ID,
+        kind: LocalKind::Init(format_args),
+        span: sp,
+        colon_sp: None,
+        attrs: AttrVec::new(),
+        tokens: None,
+    });
+    let block = cx.block(sp, thin_vec![cx.stmt_local(local, sp)]);
+    cx.stmt_expr(cx.expr(sp, ast::ExprKind::If(cx.expr_bool(sp, false), block, None)))
+}
+
+/// Adds the statement made by `format_string_check` to the expansion of a call. An expression is
+/// wrapped in a block that runs the statement first, and the statement is prepended to statements.
+/// The other kinds of fragments are left as they are: the format string is still checked, but
+/// the names that it captures aren't resolved.
+pub(super) fn add_format_string_check(fragment: AstFragment, check: ast::Stmt) -> AstFragment {
+    let with_check = |expr: P<ast::Expr>| {
+        let span = expr.span;
+        let tail = ast::Stmt { id: DUMMY_NODE_ID, span, kind: ast::StmtKind::Expr(expr) };
+        let block = P(ast::Block {
+            stmts: thin_vec![check, tail],
+            id: DUMMY_NODE_ID,
+            rules: ast::BlockCheckMode::Default,
+            span,
+            tokens: None,
+            could_be_bare_literal: false,
+        });
+        P(ast::Expr {
+            id: DUMMY_NODE_ID,
+            kind: ast::ExprKind::Block(block, None),
+            span,
+            attrs: AttrVec::new(),
+            tokens: None,
+        })
+    };
+    match fragment {
+        AstFragment::Expr(expr) => AstFragment::Expr(with_check(expr)),
+        AstFragment::MethodReceiverExpr(expr) => AstFragment::MethodReceiverExpr(with_check(expr)),
+        AstFragment::OptExpr(Some(expr)) => AstFragment::OptExpr(Some(with_check(expr))),
+        AstFragment::Stmts(mut stmts) => {
+            stmts.insert(0, check);
+            AstFragment::Stmts(stmts)
+        }
+        fragment => fragment,
+    }
+}
+
+fn binds_metavar(tt: &mbe::TokenTree, name: MacroRulesNormalizedIdent) -> bool {
+    match tt {
+        mbe::TokenTree::MetaVarDecl(_, bind, _) => MacroRulesNormalizedIdent::new(*bind) == name,
+        mbe::TokenTree::Delimited(.., delimited) => {
+            delimited.tts.iter().any(|tt| binds_metavar(tt, name))
+        }
+        mbe::TokenTree::Sequence(_, seq) => seq.tts.iter().any(|tt| binds_metavar(tt, name)),
+        mbe::TokenTree::Token(_)
+        | mbe::TokenTree::MetaVar(..)
+        | mbe::TokenTree::MetaVarExpr(..) => false,
+    }
+}
+
+/// Pushes the format string, as a literal token if it is a string literal, so that it's checked
+/// like a literal written in the call to `format_args!`, which can capture variables.
+fn push_format_string(fmt: &NamedMatch, tts: &mut Vec<TokenTree>) {
+    if let MatchedSingle(ParseNtResult::Nt(nt)) = fmt
+        && let token::NtExpr(expr) | token::NtLiteral(expr) = &**nt
+        && let ast::ExprKind::Lit(lit) = expr.kind
+        && let token::Str | token::StrRaw(_) = lit.kind
+    {
+        tts.push(TokenTree::token_alone(token::Literal(lit), expr.span));
+    } else {
+        push_fragment(fmt, tts);
+    }
+}
+
+/// Pushes the tokens of a fragment like `transcribe` does, concatenating the fragments of nested
+/// repetitions.
+fn push_fragment(named_match: &NamedMatch, tts: &mut Vec<TokenTree>) {
+    match named_match {
+        MatchedSingle(ParseNtResult::Tt(tt)) => tts.push(tt.clone()),
+        MatchedSingle(ParseNtResult::Ident(ident, is_raw)) => {
+            tts.push(TokenTree::token_alone(token::NtIdent(*ident, *is_raw), ident.span))
+        }
+        MatchedSingle(ParseNtResult::Lifetime(ident)) => {
+            tts.push(TokenTree::token_alone(token::NtLifetime(*ident), ident.span))
+        }
+        MatchedSingle(ParseNtResult::Nt(nt)) => {
+            tts.push(TokenTree::token_alone(token::Interpolated(nt.clone()), nt.use_span()))
+        }
+        MatchedSeq(seq) => {
+            for named_match in seq {
+                push_fragment(named_match, tts);
+            }
+        }
+    }
+}
@@ -459,6 +459,7 @@ fn parse_tt_inner<'matcher, T: Tracker<'matcher>>(
         // modified if `token == Eof`.
         let mut eof_mps = EofMatcherPositions::None;
 
+        track.before_token();
         while let Some(mut mp) = self.cur_mps.pop() {
             let matcher_loc = &matcher[mp.idx];
             track.before_match_loc(self, matcher_loc);
@@ -698,6 +699,7 @@ pub(super) fn parse_tt<'matcher, T: Tracker<'matcher>>(
 
                 (_, _) => {
                     // Too many possibilities!
+                    track.local_ambiguity(self.bb_mps.iter().map(|mp| &matcher[mp.idx]).collect());
                     return self.ambiguity_error(matcher, parser.token.span);
                 }
             }
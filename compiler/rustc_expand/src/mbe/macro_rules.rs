@@ -3,6 +3,10 @@
 use crate::expand::{ensure_complete_parse, parse_ast_fragment, AstFragment, AstFragmentKind};
 use crate::mbe;
 use crate::mbe::diagnostics::{annotate_doc_comment, parse_failure_msg};
+use crate::mbe::expansion_trace::{ArmTracker, ExpansionStep};
+use crate::mbe::format_string::{
+    add_format_string_check, format_string_check, FormatStringMetavars,
+};
 use crate::mbe::macro_check;
 use crate::mbe::macro_parser::{Error, ErrorReported, Failure, Success, TtParser};
 use crate::mbe::macro_parser::{MatcherLoc, NamedMatch::*};
@@ -52,6 +56,9 @@ pub(crate) struct ParserAnyMacro<'a> {
     arm_span: Span,
     /// Whether or not this macro is defined in the current crate
     is_local: bool,
+    /// The check of the format string of a macro declared with `#[format_string]`, added to the
+    /// expansion so that the names that the format string captures are resolved.
+    format_string_check: Option<ast::Stmt>,
 }
 
 impl<'a> ParserAnyMacro<'a> {
@@ -64,6 +71,7 @@ pub(crate) fn make(mut self: Box<ParserAnyMacro<'a>>, kind: AstFragmentKind) ->
             arm_span,
             is_trailing_mac,
             is_local,
+            format_string_check,
         } = *self;
         let snapshot = &mut parser.create_snapshot_for_diagnostic();
         let fragment = match parse_ast_fragment(parser, kind) {
@@ -94,7 +102,10 @@ pub(crate) fn make(mut self: Box<ParserAnyMacro<'a>>, kind: AstFragmentKind) ->
         // Make sure we don't have any tokens left to parse so we don't silently drop anything.
         let path = ast::Path::from_ident(macro_ident.with_span_pos(site_span));
         ensure_complete_parse(parser, &path, kind.name(), site_span);
-        fragment
+        match format_string_check {
+            Some(check) => add_format_string_check(fragment, check),
+            None => fragment,
+        }
     }
 }
 
@@ -104,7 +115,10 @@ struct MacroRulesMacroExpander {
     span: Span,
     transparency: Transparency,
     lhses: Vec<Vec<MatcherLoc>>,
+    /// The spans of the matchers of the rules, for diagnostics.
+    lhs_spans: Vec<Span>,
     rhses: Vec<mbe::TokenTree>,
+    format_string: Option<FormatStringMetavars>,
 }
 
 impl TTMacroExpander for MacroRulesMacroExpander {
@@ -123,7 +137,9 @@ fn expand<'cx>(
             self.transparency,
             input,
             &self.lhses,
+            &self.lhs_spans,
             &self.rhses,
+            self.format_string.as_ref(),
         ))
     }
 }
@@ -155,6 +171,9 @@ pub(super) trait Tracker<'matcher> {
     /// The usize is the approximate position of the token in the input token stream.
     fn build_failure(tok: Token, position: usize, msg: &'static str) -> Self::Failure;
 
+    /// This is called before trying to match the matcher positions on the next token.
+    fn before_token(&mut self) {}
+
     /// This is called before trying to match next MatcherLoc on the current token.
     fn before_match_loc(&mut self, _parser: &TtParser, _matcher: &'matcher MatcherLoc) {}
 
@@ -162,6 +181,10 @@ fn before_match_loc(&mut self, _parser: &TtParser, _matcher: &'matcher MatcherLo
     /// this is called, `before_match_loc` was called at least once (with a `MatcherLoc::Eof`).
     fn after_arm(&mut self, _result: &NamedParseResult<Self::Failure>) {}
 
+    /// This is called when an arm can't be matched because of a local ambiguity, with the
+    /// metavariables that could each parse the next fragment of the input.
+    fn local_ambiguity(&mut self, _metavars: Vec<&'matcher MatcherLoc>) {}
+
     /// For tracing.
     fn description() -> &'static str;
 
@@ -191,7 +214,7 @@ fn description() -> &'static str {
 
 /// Expands the rules based macro defined by `lhses` and `rhses` for a given
 /// input `arg`.
-#[instrument(skip(cx, transparency, arg, lhses, rhses))]
+#[instrument(skip(cx, transparency, arg, lhses, lhs_spans, rhses, format_string))]
 fn expand_macro<'cx>(
     cx: &'cx mut ExtCtxt<'_>,
     sp: Span,
@@ -201,7 +224,9 @@ fn expand_macro<'cx>(
     transparency: Transparency,
     arg: TokenStream,
     lhses: &[Vec<MatcherLoc>],
+    lhs_spans: &[Span],
     rhses: &[mbe::TokenTree],
+    format_string: Option<&FormatStringMetavars>,
 ) -> Box<dyn MacResult + 'cx> {
     let psess = &cx.sess.psess;
     // Macros defined in the current crate have a real node id,
@@ -213,11 +238,40 @@ fn expand_macro<'cx>(
         trace_macros_note(&mut cx.expansions, sp, msg);
     }
 
-    // Track nothing for the best performance.
-    let try_success_result = try_match_macro(psess, name, &arg, lhses, &mut NoopTracker);
+    let mut trace_step = cx
+        .sess
+        .opts
+        .unstable_opts
+        .dump_macro_expansions
+        .enabled()
+        .then(|| ExpansionStep::new(cx, name, sp, &arg));
+
+    let try_success_result = match &mut trace_step {
+        // Record the result of matching each arm, for `-Z dump-macro-expansions`.
+        Some(step) => {
+            let mut tracker = ArmTracker::new(step, cx.source_map(), sp);
+            try_match_macro(psess, name, &arg, lhses, &mut tracker)
+        }
+        // Track nothing for the best performance.
+        None => try_match_macro(psess, name, &arg, lhses, &mut NoopTracker),
+    };
 
     match try_success_result {
         Ok((i, named_matches)) => {
+            if let Some(step) = &mut trace_step {
+                step.set_matched_arm(i, &named_matches);
+            }
+
+            let format_string_check = if let Some(format_string) = format_string
+                && let Some(check_format_string) = cx.ecfg.check_format_string
+                && let Some(input) = format_string.format_args_input(&named_matches, sp)
+            {
+                check_format_string(cx, sp, input)
+                    .map(|format_args| format_string_check(cx, sp, format_args))
+            } else {
+                None
+            };
+
             let (rhs, rhs_span): (&mbe::Delimited, DelimSpan) = match &rhses[i] {
                 mbe::TokenTree::Delimited(span, _, delimited) => (&delimited, *span),
                 _ => cx.dcx().span_bug(sp, "malformed macro rhs"),
@@ -230,6 +284,7 @@ fn expand_macro<'cx>(
                 Ok(tts) => tts,
                 Err(err) => {
                     let guar = err.emit();
+                    cx.macro_expansion_steps.extend(trace_step);
                     return DummyResult::any(arm_span, guar);
                 }
             };
@@ -239,6 +294,11 @@ fn expand_macro<'cx>(
                 trace_macros_note(&mut cx.expansions, sp, msg);
             }
 
+            if let Some(mut step) = trace_step {
+                step.set_output(&tts);
+                cx.macro_expansion_steps.push(step);
+            }
+
             let p = Parser::new(psess, tts, None);
 
             if is_local {
@@ -259,15 +319,18 @@ fn expand_macro<'cx>(
                 is_trailing_mac: cx.current_expansion.is_trailing_mac,
                 arm_span,
                 is_local,
+                format_string_check,
             })
         }
         Err(CanRetry::No(guar)) => {
             debug!("Will not retry matching as an error was emitted already");
+            cx.macro_expansion_steps.extend(trace_step);
             DummyResult::any(sp, guar)
         }
         Err(CanRetry::Yes) => {
+            cx.macro_expansion_steps.extend(trace_step);
             // Retry and emit a better error.
-            diagnostics::failed_to_match_macro(cx, sp, def_span, name, arg, lhses)
+            diagnostics::failed_to_match_macro(cx, sp, def_span, name, arg, lhses, lhs_spans)
         }
     }
 }
@@ -582,6 +645,9 @@ pub fn compile_declarative_macro(
         Vec::new()
     };
 
+    let lhs_spans = lhses.iter().map(|lhs| lhs.span()).collect();
+    let format_string = FormatStringMetavars::from_attrs(sess, &def.attrs, &lhses);
+
     // Convert the lhses into `MatcherLoc` form, which is better for doing the
     // actual matching.
     let lhses = lhses
@@ -603,7 +669,9 @@ pub fn compile_declarative_macro(
         node_id: def.id,
         transparency,
         lhses,
+        lhs_spans,
         rhses,
+        format_string,
     });
     (mk_syn_ext(expander), rule_spans)
 }
@@ -1,15 +1,18 @@
 use crate::base::{self, *};
 use crate::errors;
+use crate::proc_macro_cache::expand_cached;
+use crate::proc_macro_sandbox::ProcessPipe;
 use crate::proc_macro_server;
 
 use rustc_ast as ast;
 use rustc_ast::ptr::P;
 use rustc_ast::tokenstream::TokenStream;
+use rustc_data_structures::svh::Svh;
 use rustc_errors::ErrorGuaranteed;
 use rustc_parse::parser::{ForceCollect, Parser};
 use rustc_session::config::ProcMacroExecutionStrategy;
 use rustc_span::profiling::SpannedEventArgRecorder;
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 
 struct MessagePipe<T> {
     tx: std::sync::mpsc::SyncSender<T>,
@@ -33,14 +36,26 @@ fn recv(&mut self) -> Option<T> {
 }
 
 fn exec_strategy(ecx: &ExtCtxt<'_>) -> impl pm::bridge::server::ExecutionStrategy {
-    pm::bridge::server::MaybeCrossThread::<MessagePipe<_>>::new(
-        ecx.sess.opts.unstable_opts.proc_macro_execution_strategy
-            == ProcMacroExecutionStrategy::CrossThread,
+    pm::bridge::server::MaybeCrossProcess::<_, ProcessPipe>::new(
+        ecx.sess.opts.unstable_opts.proc_macro_sandbox,
+        pm::bridge::server::MaybeCrossThread::<MessagePipe<_>>::new(
+            ecx.sess.opts.unstable_opts.proc_macro_execution_strategy
+                == ProcMacroExecutionStrategy::CrossThread,
+        ),
     )
 }
 
+/// A proc macro declared with `#[proc_macro_pure]`, whose expansions can be cached.
+#[derive(Clone, Copy, Debug)]
+pub struct PureProcMacro {
+    /// The hash of the crate defining the macro, which changes with the code of the macro.
+    pub crate_hash: Svh,
+    pub name: Symbol,
+}
+
 pub struct BangProcMacro {
     pub client: pm::bridge::client::Client<pm::TokenStream, pm::TokenStream>,
+    pub pure: Option<PureProcMacro>,
 }
 
 impl base::BangProcMacro for BangProcMacro {
@@ -55,15 +70,17 @@ fn expand<'cx>(
                 recorder.record_arg_with_span(ecx.sess.source_map(), ecx.expansion_descr(), span);
             });
 
-        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let strategy = exec_strategy(ecx);
-        let server = proc_macro_server::Rustc::new(ecx);
-        self.client.run(&strategy, server, input, proc_macro_backtrace).map_err(|e| {
-            ecx.dcx().emit_err(errors::ProcMacroPanicked {
-                span,
-                message: e
-                    .as_str()
-                    .map(|message| errors::ProcMacroPanickedHelp { message: message.into() }),
+        expand_cached(ecx, self.pure.as_ref(), &[&input], |ecx, tracked_inputs| {
+            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+            let strategy = exec_strategy(ecx);
+            let server = proc_macro_server::Rustc::new(ecx, tracked_inputs);
+            self.client.run(&strategy, server, input.clone(), proc_macro_backtrace).map_err(|e| {
+                ecx.dcx().emit_err(errors::ProcMacroPanicked {
+                    span,
+                    message: e
+                        .as_str()
+                        .map(|message| errors::ProcMacroPanickedHelp { message: message.into() }),
+                })
             })
         })
     }
@@ -71,6 +88,7 @@ fn expand<'cx>(
 
 pub struct AttrProcMacro {
     pub client: pm::bridge::client::Client<(pm::TokenStream, pm::TokenStream), pm::TokenStream>,
+    pub pure: Option<PureProcMacro>,
 }
 
 impl base::AttrProcMacro for AttrProcMacro {
@@ -86,24 +104,28 @@ fn expand<'cx>(
                 recorder.record_arg_with_span(ecx.sess.source_map(), ecx.expansion_descr(), span);
             });
 
-        let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-        let strategy = exec_strategy(ecx);
-        let server = proc_macro_server::Rustc::new(ecx);
-        self.client.run(&strategy, server, annotation, annotated, proc_macro_backtrace).map_err(
-            |e| {
-                ecx.dcx().emit_err(errors::CustomAttributePanicked {
-                    span,
-                    message: e.as_str().map(|message| errors::CustomAttributePanickedHelp {
-                        message: message.into(),
-                    }),
+        let inputs = [&annotation, &annotated];
+        expand_cached(ecx, self.pure.as_ref(), &inputs, |ecx, tracked_inputs| {
+            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+            let strategy = exec_strategy(ecx);
+            let server = proc_macro_server::Rustc::new(ecx, tracked_inputs);
+            self.client
+                .run(&strategy, server, annotation.clone(), annotated.clone(), proc_macro_backtrace)
+                .map_err(|e| {
+                    ecx.dcx().emit_err(errors::CustomAttributePanicked {
+                        span,
+                        message: e.as_str().map(|message| errors::CustomAttributePanickedHelp {
+                            message: message.into(),
+                        }),
+                    })
                 })
-            },
-        )
+        })
     }
 }
 
 pub struct DeriveProcMacro {
     pub client: pm::bridge::client::Client<pm::TokenStream, pm::TokenStream>,
+    pub pure: Option<PureProcMacro>,
 }
 
 impl MultiItemModifier for DeriveProcMacro {
@@ -134,22 +156,25 @@ fn expand(
                         span,
                     );
                 });
-            let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
-            let strategy = exec_strategy(ecx);
-            let server = proc_macro_server::Rustc::new(ecx);
-            match self.client.run(&strategy, server, input, proc_macro_backtrace) {
+            let expanded =
+                expand_cached(ecx, self.pure.as_ref(), &[&input], |ecx, tracked_inputs| {
+                    let proc_macro_backtrace = ecx.ecfg.proc_macro_backtrace;
+                    let strategy = exec_strategy(ecx);
+                    let server = proc_macro_server::Rustc::new(ecx, tracked_inputs);
+                    self.client.run(&strategy, server, input.clone(), proc_macro_backtrace).map_err(
+                        |e| {
+                            ecx.dcx().emit_err(errors::ProcMacroDerivePanicked {
+                                span,
+                                message: e.as_str().map(|message| {
+                                    errors::ProcMacroDerivePanickedHelp { message: message.into() }
+                                }),
+                            })
+                        },
+                    )
+                });
+            match expanded {
                 Ok(stream) => stream,
-                Err(e) => {
-                    ecx.dcx().emit_err({
-                        errors::ProcMacroDerivePanicked {
-                            span,
-                            message: e.as_str().map(|message| {
-                                errors::ProcMacroDerivePanickedHelp { message: message.into() }
-                            }),
-                        }
-                    });
-                    return ExpandResult::Ready(vec![]);
-                }
+                Err(_) => return ExpandResult::Ready(vec![]),
             }
         };
 
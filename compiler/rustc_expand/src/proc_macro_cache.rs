@@ -0,0 +1,417 @@
+//! The cache of the expansions of `#[proc_macro_pure]` macros, kept in the incremental directory.
+//!
+//! A pure proc macro promises that its output only depends on its input tokens, and on the
+//! environment variables and files that it reads through `proc_macro::tracked_env` and
+//! `proc_macro::tracked_path`. So with incremental compilation, we key its expansions by the hash of
+//! the macro crate, the name of the macro and its input tokens, and record the tracked inputs it
+//! read. When a later session expands the macro with the same input, and the tracked inputs haven't
+//! changed, we reuse the output instead of running the macro.
+//!
+//! Spans can't be saved across sessions, so the spans of the output are saved relative to the
+//! expansion: as its call site, def site or mixed site span, or as the span of a token of the
+//! input. An output with other spans isn't cached. Neither is the expansion of a macro that emits
+//! diagnostics or expands other macros, as we can't replay that.
+
+use std::hash::Hash;
+use std::mem;
+use std::path::Path;
+use std::{fs, io};
+
+use rustc_ast::token::{Delimiter, Token, TokenKind};
+use rustc_ast::tokenstream::{DelimSpacing, DelimSpan, Spacing, TokenStream, TokenTree};
+use rustc_ast_pretty::pprust;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_macros::{Decodable, Encodable};
+use rustc_serialize::opaque::{FileEncoder, IntEncodedWithFixedSize, MemDecoder};
+use rustc_serialize::{Decodable, Decoder, Encodable, Encoder};
+use rustc_session::Session;
+use rustc_span::{Span, Symbol};
+
+use crate::base::ExtCtxt;
+use crate::errors;
+use crate::proc_macro::PureProcMacro;
+
+const FILE_NAME: &str = "proc-macro-expansions.bin";
+const FILE_MAGIC: &[u8] = b"RSPM";
+
+/// An environment variable or a file that a macro read besides its input, and what it read.
+#[derive(Clone, Encodable, Decodable)]
+pub(crate) enum TrackedInput {
+    /// A variable read with `tracked_env::var`.
+    EnvVar(String, Option<String>),
+    /// A path given to `tracked_path::path`, with the hash of the contents of the file if the
+    /// compiler could read it.
+    Path(String, Option<Fingerprint>),
+    /// A file read with `tracked_path::read`, with the hash of its contents.
+    File(String, Option<Fingerprint>),
+    /// A directory read with `tracked_path::read_dir`, with its entries.
+    Dir(String, Option<Vec<String>>),
+}
+
+impl TrackedInput {
+    /// Checks that the input still has the value that the macro read, and adds it to the dep-info
+    /// like running the macro would have.
+    fn is_unchanged(&self, ecx: &ExtCtxt<'_>) -> bool {
+        let psess = ecx.psess();
+        match self {
+            TrackedInput::EnvVar(var, value) => {
+                let current = match ecx.sess.opts.logical_env.get(var) {
+                    Some(value) => Some(value.clone()),
+                    None => std::env::var(var).ok(),
+                };
+                psess
+                    .env_depinfo
+                    .borrow_mut()
+                    .insert((Symbol::intern(var), current.as_deref().map(Symbol::intern)));
+                current == *value
+            }
+            TrackedInput::Path(path, hash) => {
+                psess.file_depinfo.borrow_mut().insert(Symbol::intern(path));
+                fs::read(path).ok().map(|bytes| hash_bytes(&bytes)) == *hash
+            }
+            TrackedInput::File(path, hash) => {
+                let current = psess.source_map().load_binary_file(Path::new(path));
+                if current.is_err() {
+                    psess.file_depinfo.borrow_mut().insert(Symbol::intern(path));
+                }
+                current.ok().map(|(bytes, _)| hash_bytes(&bytes)) == *hash
+            }
+            TrackedInput::Dir(path, entries) => {
+                psess.file_depinfo.borrow_mut().insert(Symbol::intern(path));
+                read_dir_names(Path::new(path)).ok() == *entries
+            }
+        }
+    }
+}
+
+/// What a macro read and did besides computing its output, recorded while it runs.
+#[derive(Default)]
+pub(crate) struct TrackedInputs {
+    pub inputs: Vec<TrackedInput>,
+    /// Whether the macro did something that we can't replay, like emitting a diagnostic.
+    pub has_side_effects: bool,
+}
+
+pub(crate) fn hash_bytes(bytes: &[u8]) -> Fingerprint {
+    let mut hasher = StableHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the sorted names of the entries of a directory.
+pub(crate) fn read_dir_names(path: &Path) -> io::Result<Vec<String>> {
+    let mut names = fs::read_dir(path)?
+        .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+        .collect::<io::Result<Vec<_>>>()?;
+    names.sort_unstable();
+    Ok(names)
+}
+
+/// A span of an output, relative to its expansion.
+#[derive(Clone, Copy, Encodable, Decodable)]
+enum CachedSpan {
+    CallSite,
+    DefSite,
+    MixedSite,
+    /// The span of a token of the input, by its index in `ExpansionSpans::input`.
+    Input(u32),
+}
+
+#[derive(Clone, Encodable, Decodable)]
+enum CachedTokenTree {
+    Token(TokenKind, CachedSpan, Spacing),
+    Delimited(CachedSpan, CachedSpan, DelimSpacing, Delimiter, Vec<CachedTokenTree>),
+}
+
+#[derive(Clone, Encodable, Decodable)]
+struct CachedExpansion {
+    tracked_inputs: Vec<TrackedInput>,
+    output: Vec<CachedTokenTree>,
+}
+
+/// The spans that the spans of the output of an expansion are saved relative to.
+struct ExpansionSpans {
+    call_site: Span,
+    def_site: Span,
+    mixed_site: Span,
+    /// The spans of the tokens and delimiters of the input, in order.
+    input: Vec<Span>,
+    input_indices: FxHashMap<Span, u32>,
+}
+
+impl ExpansionSpans {
+    fn new(ecx: &ExtCtxt<'_>, inputs: &[&TokenStream]) -> ExpansionSpans {
+        // Like the spans given to the proc macro server.
+        let expn_data = ecx.current_expansion.id.expn_data();
+        let mut spans = ExpansionSpans {
+            call_site: ecx.with_call_site_ctxt(expn_data.call_site),
+            def_site: ecx.with_def_site_ctxt(expn_data.def_site),
+            mixed_site: ecx.with_mixed_site_ctxt(expn_data.call_site),
+            input: Vec::new(),
+            input_indices: FxHashMap::default(),
+        };
+        for input in inputs {
+            spans.collect_input_spans(input);
+        }
+        spans
+    }
+
+    fn collect_input_spans(&mut self, stream: &TokenStream) {
+        for tree in stream.trees() {
+            match tree {
+                TokenTree::Token(token, _) => self.push_input_span(token.span),
+                TokenTree::Delimited(span, _, _, stream) => {
+                    self.push_input_span(span.open);
+                    self.collect_input_spans(stream);
+                    self.push_input_span(span.close);
+                }
+            }
+        }
+    }
+
+    fn push_input_span(&mut self, span: Span) {
+        let index = self.input.len() as u32;
+        self.input_indices.entry(span).or_insert(index);
+        self.input.push(span);
+    }
+
+    fn cache_span(&self, span: Span) -> Option<CachedSpan> {
+        if span == self.call_site {
+            Some(CachedSpan::CallSite)
+        } else if span == self.def_site {
+            Some(CachedSpan::DefSite)
+        } else if span == self.mixed_site {
+            Some(CachedSpan::MixedSite)
+        } else {
+            self.input_indices.get(&span).map(|&index| CachedSpan::Input(index))
+        }
+    }
+
+    fn restore_span(&self, span: CachedSpan) -> Span {
+        match span {
+            CachedSpan::CallSite => self.call_site,
+            CachedSpan::DefSite => self.def_site,
+            CachedSpan::MixedSite => self.mixed_site,
+            // The input has the same tokens as the one the output was cached for, so the index is
+            // in bounds.
+            CachedSpan::Input(index) => self.input[index as usize],
+        }
+    }
+
+    fn cache_stream(&self, stream: &TokenStream) -> Option<Vec<CachedTokenTree>> {
+        stream
+            .trees()
+            .map(|tree| match tree {
+                TokenTree::Token(token, _) if matches!(token.kind, TokenKind::Interpolated(_)) => {
+                    None
+                }
+                TokenTree::Token(token, spacing) => Some(CachedTokenTree::Token(
+                    token.kind.clone(),
+                    self.cache_span(token.span)?,
+                    *spacing,
+                )),
+                TokenTree::Delimited(span, spacing, delim, stream) => {
+                    Some(CachedTokenTree::Delimited(
+                        self.cache_span(span.open)?,
+                        self.cache_span(span.close)?,
+                        *spacing,
+                        *delim,
+                        self.cache_stream(stream)?,
+                    ))
+                }
+            })
+            .collect()
+    }
+
+    fn restore_stream(&self, trees: Vec<CachedTokenTree>) -> TokenStream {
+        TokenStream::new(
+            trees
+                .into_iter()
+                .map(|tree| match tree {
+                    CachedTokenTree::Token(kind, span, spacing) => {
+                        TokenTree::Token(Token::new(kind, self.restore_span(span)), spacing)
+                    }
+                    CachedTokenTree::Delimited(open, close, spacing, delim, trees) => {
+                        TokenTree::Delimited(
+                            DelimSpan::from_pair(self.restore_span(open), self.restore_span(close)),
+                            spacing,
+                            delim,
+                            self.restore_stream(trees),
+                        )
+                    }
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Hashes the tokens of `stream`, but not their spans. Returns `None` if the stream has
+/// interpolated tokens, which we don't cache expansions of.
+fn hash_stream(stream: &TokenStream, hasher: &mut StableHasher) -> Option<()> {
+    stream.len().hash(hasher);
+    for tree in stream.trees() {
+        match tree {
+            TokenTree::Token(token, spacing) => {
+                if let TokenKind::Interpolated(_) = token.kind {
+                    return None;
+                }
+                mem::discriminant(&token.kind).hash(hasher);
+                pprust::token_kind_to_string(&token.kind).hash(hasher);
+                (*spacing as u8).hash(hasher);
+            }
+            TokenTree::Delimited(_, spacing, delim, stream) => {
+                delim.hash(hasher);
+                (spacing.open as u8, spacing.close as u8).hash(hasher);
+                hash_stream(stream, hasher)?;
+            }
+        }
+    }
+    Some(())
+}
+
+fn expansion_key(pure: &PureProcMacro, inputs: &[&TokenStream]) -> Option<Fingerprint> {
+    let mut hasher = StableHasher::new();
+    pure.crate_hash.as_u128().hash(&mut hasher);
+    pure.name.as_str().hash(&mut hasher);
+    for input in inputs {
+        hash_stream(input, &mut hasher)?;
+    }
+    Some(hasher.finish())
+}
+
+/// The expansions of pure proc macros, loaded from the previous session when the first one is
+/// expanded.
+#[derive(Default)]
+pub(crate) struct ProcMacroCache {
+    loaded: bool,
+    /// The expansions saved by the previous session that this session hasn't used yet.
+    previous: FxHashMap<Fingerprint, CachedExpansion>,
+    /// The expansions of this session, which replace the ones of the previous session.
+    current: FxIndexMap<Fingerprint, CachedExpansion>,
+}
+
+impl ProcMacroCache {
+    fn get(&mut self, sess: &Session, key: Fingerprint) -> Option<CachedExpansion> {
+        if !self.loaded {
+            self.loaded = true;
+            if let Some(dir) = sess.incr_comp_session_dir_opt() {
+                self.previous = read_expansions(sess, &dir.join(FILE_NAME)).unwrap_or_default();
+            }
+        }
+        match self.current.get(&key) {
+            Some(expansion) => Some(expansion.clone()),
+            None => self.previous.remove(&key),
+        }
+    }
+
+    /// Writes the expansions of this session to the incremental directory, if a pure macro was
+    /// expanded.
+    pub(crate) fn save(&self, sess: &Session) {
+        if !self.loaded {
+            return;
+        }
+        let Some(dir) = sess.incr_comp_session_dir_opt() else {
+            return;
+        };
+        let path = dir.join(FILE_NAME);
+        if let Err(err) = write_expansions(sess, &path, &self.current) {
+            sess.dcx().emit_warn(errors::ProcMacroCacheWriteFailed { path: &path, err });
+        }
+    }
+}
+
+fn read_expansions(sess: &Session, path: &Path) -> Option<FxHashMap<Fingerprint, CachedExpansion>> {
+    let data = fs::read(path).ok()?;
+    let mut decoder = MemDecoder::new(data.strip_prefix(FILE_MAGIC)?, 0).ok()?;
+    // The expansions are followed by their length, so that a file that wasn't fully written is
+    // ignored instead of decoded past its end.
+    let footer_pos = decoder.len().checked_sub(IntEncodedWithFixedSize::ENCODED_SIZE)?;
+    let len = decoder.with_position(footer_pos, |decoder| IntEncodedWithFixedSize::decode(decoder));
+    if len.0 != footer_pos as u64 {
+        return None;
+    }
+    if decoder.read_str() != sess.cfg_version {
+        return None;
+    }
+    let len = decoder.read_usize();
+    Some(
+        (0..len)
+            .map(|_| {
+                let key = Fingerprint::decode(&mut decoder);
+                (key, CachedExpansion::decode(&mut decoder))
+            })
+            .collect(),
+    )
+}
+
+fn write_expansions(
+    sess: &Session,
+    path: &Path,
+    expansions: &FxIndexMap<Fingerprint, CachedExpansion>,
+) -> io::Result<()> {
+    // The file may be a hard link to the one of the previous session, which must not change.
+    if let Err(err) = fs::remove_file(path)
+        && err.kind() != io::ErrorKind::NotFound
+    {
+        return Err(err);
+    }
+    let mut encoder = FileEncoder::new(path)?;
+    encoder.emit_raw_bytes(FILE_MAGIC);
+    encoder.emit_str(sess.cfg_version);
+    encoder.emit_usize(expansions.len());
+    for (key, expansion) in expansions {
+        key.encode(&mut encoder);
+        expansion.encode(&mut encoder);
+    }
+    IntEncodedWithFixedSize((encoder.position() - FILE_MAGIC.len()) as u64).encode(&mut encoder);
+    encoder.finish().map(|_| ()).map_err(|(_, err)| {
+        // Don't leave a partial file for the next session.
+        let _ = fs::remove_file(path);
+        err
+    })
+}
+
+/// Expands a proc macro with `run`, or reuses a cached expansion if the macro is pure, incremental
+/// compilation is enabled, and the previous session expanded the macro with the same input and
+/// tracked inputs.
+pub(crate) fn expand_cached<E>(
+    ecx: &mut ExtCtxt<'_>,
+    pure: Option<&PureProcMacro>,
+    inputs: &[&TokenStream],
+    run: impl FnOnce(&mut ExtCtxt<'_>, Option<&mut TrackedInputs>) -> Result<TokenStream, E>,
+) -> Result<TokenStream, E> {
+    let key = match pure {
+        Some(pure) if ecx.sess.opts.incremental.is_some() => expansion_key(pure, inputs),
+        _ => None,
+    };
+    let Some(key) = key else {
+        return run(ecx, None);
+    };
+
+    let spans = ExpansionSpans::new(ecx, inputs);
+    if let Some(expansion) = ecx.proc_macro_cache.get(ecx.sess, key) {
+        // Check all the inputs, so that they are all in the dep-info.
+        let unchanged = expansion
+            .tracked_inputs
+            .iter()
+            .fold(true, |unchanged, input| input.is_unchanged(ecx) && unchanged);
+        if unchanged {
+            let output = spans.restore_stream(expansion.output.clone());
+            ecx.proc_macro_cache.current.insert(key, expansion);
+            return Ok(output);
+        }
+    }
+
+    let mut tracked_inputs = TrackedInputs::default();
+    let output = run(ecx, Some(&mut tracked_inputs))?;
+    if !tracked_inputs.has_side_effects
+        && let Some(cached_output) = spans.cache_stream(&output)
+    {
+        let expansion =
+            CachedExpansion { tracked_inputs: tracked_inputs.inputs, output: cached_output };
+        ecx.proc_macro_cache.current.insert(key, expansion);
+    }
+    Ok(output)
+}
@@ -0,0 +1,326 @@
+//! The child processes that `-Z proc-macro-sandbox` runs proc macros in.
+//!
+//! The process is forked from rustc, so it already has the proc macro loaded, and only runs the
+//! client side of the bridge: every request to the compiler, including the reads of tracked
+//! files, is sent back to rustc over a socket. Before running the macro, the process closes the
+//! files it inherited from rustc and installs a seccomp filter that only allows the system calls
+//! needed to talk to rustc and to manage memory, so the macro can only observe the files that
+//! rustc reads for it and records in the dep-info.
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub(crate) use linux::ProcessPipe;
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+pub(crate) use unsupported::ProcessPipe;
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod linux {
+    use std::io::{self, Read, Write};
+    use std::net::Shutdown;
+    use std::os::fd::{FromRawFd, IntoRawFd};
+    use std::os::unix::net::UnixStream;
+    use std::{fs, panic};
+
+    /// The longest message that the server accepts from the client. The output of a macro is
+    /// rarely more than a few megabytes, and the length comes from a process that isn't trusted.
+    const MAX_MESSAGE_LEN: u64 = 1 << 30;
+
+    pub(crate) struct ProcessPipe {
+        stream: UnixStream,
+        /// The process running the client, on the server side of the pipe.
+        child: Option<libc::pid_t>,
+        /// Why the last message from the client was rejected, if it was.
+        invalid_message: Option<io::Error>,
+    }
+
+    impl ProcessPipe {
+        fn write_message(&mut self, tag: Option<u8>, bytes: &[u8]) -> io::Result<()> {
+            let len = bytes.len() + usize::from(tag.is_some());
+            self.stream.write_all(&(len as u64).to_le_bytes())?;
+            if let Some(tag) = tag {
+                self.stream.write_all(&[tag])?;
+            }
+            self.stream.write_all(bytes)
+        }
+
+        fn read_message(&mut self) -> io::Result<Vec<u8>> {
+            let mut len = [0; 8];
+            self.stream.read_exact(&mut len)?;
+            let len = u64::from_le_bytes(len);
+            if len > MAX_MESSAGE_LEN {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("a message of {len} bytes is too long"),
+                ));
+            }
+            let mut message = vec![0; len as usize];
+            self.stream.read_exact(&mut message)?;
+            Ok(message)
+        }
+
+        /// Waits for the child process to exit, and returns its status.
+        fn wait(&mut self) -> Option<libc::c_int> {
+            let pid = self.child.take()?;
+            // The client may still be waiting for a reply, which it won't get.
+            let _ = self.stream.shutdown(Shutdown::Both);
+            let mut status = 0;
+            loop {
+                // SAFETY: `pid` is a child of this process that has not been waited for yet.
+                if unsafe { libc::waitpid(pid, &mut status, 0) } != -1 {
+                    return Some(status);
+                }
+                if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+                    return None;
+                }
+            }
+        }
+    }
+
+    impl pm::bridge::server::ProcessPipe for ProcessPipe {
+        fn spawn(client: impl FnOnce(Self)) -> Result<Self, String> {
+            let (server, client_stream) = UnixStream::pair()
+                .map_err(|err| format!("could not create a socket for the proc macro: {err}"))?;
+
+            // SAFETY: the child process only runs the client, which doesn't touch the state of
+            // rustc, and exits with `_exit` without unwinding or returning into rustc. Only the
+            // thread calling `fork` exists in the child, so a lock that another thread of rustc
+            // held at that point stays locked forever there. The allocators that rustc uses reset
+            // their locks when forking, but the locks of `std`, like the one of `stderr`, aren't:
+            // a macro taking such a lock blocks, which is a hang, not undefined behavior.
+            match unsafe { libc::fork() } {
+                -1 => Err(format!(
+                    "could not start a process for the proc macro: {}",
+                    io::Error::last_os_error()
+                )),
+                0 => {
+                    drop(server);
+                    let sandboxed = close_inherited_fds(client_stream)
+                        .and_then(|stream| install_sandbox().map(|()| stream));
+                    let status = match sandboxed {
+                        Ok(stream) => {
+                            let pipe = ProcessPipe { stream, child: None, invalid_message: None };
+                            match panic::catch_unwind(panic::AssertUnwindSafe(|| client(pipe))) {
+                                Ok(()) => 0,
+                                Err(_) => 101,
+                            }
+                        }
+                        Err(err) => {
+                            eprintln!("error: could not sandbox the proc macro process: {err}");
+                            1
+                        }
+                    };
+                    // SAFETY: `_exit` doesn't run the exit handlers of rustc.
+                    unsafe { libc::_exit(status) }
+                }
+                pid => Ok(ProcessPipe { stream: server, child: Some(pid), invalid_message: None }),
+            }
+        }
+
+        fn send(&mut self, tag: u8, bytes: &[u8]) {
+            if let Err(err) = self.write_message(Some(tag), bytes) {
+                panic!("could not send a message to the compiler: {err}");
+            }
+        }
+
+        fn send_reply(&mut self, bytes: &[u8]) {
+            // If the client is gone, the next `recv` reports it.
+            let _ = self.write_message(None, bytes);
+        }
+
+        fn recv(&mut self) -> Option<Vec<u8>> {
+            match self.read_message() {
+                Ok(message) => Some(message),
+                Err(err) => {
+                    if err.kind() == io::ErrorKind::InvalidData {
+                        self.invalid_message = Some(err);
+                    }
+                    None
+                }
+            }
+        }
+
+        fn exit_status(mut self) -> String {
+            let status = self.wait();
+            if let Some(err) = self.invalid_message.take() {
+                return format!("invalid message from the proc macro process: {err}");
+            }
+            match status {
+                Some(status)
+                    if libc::WIFSIGNALED(status) && libc::WTERMSIG(status) == libc::SIGSYS =>
+                {
+                    "the proc macro made a system call that is not allowed in the sandbox".into()
+                }
+                Some(status) if libc::WIFSIGNALED(status) => {
+                    format!(
+                        "the proc macro process was killed by signal {}",
+                        libc::WTERMSIG(status)
+                    )
+                }
+                Some(status) => format!(
+                    "the proc macro process exited with status {}",
+                    libc::WEXITSTATUS(status)
+                ),
+                None => "the proc macro process exited unexpectedly".into(),
+            }
+        }
+    }
+
+    impl Drop for ProcessPipe {
+        fn drop(&mut self) {
+            self.wait();
+        }
+    }
+
+    // The parts of `linux/filter.h` and `linux/seccomp.h` that we need.
+    const BPF_LD_W_ABS: u16 = 0x20;
+    const BPF_JMP_JEQ_K: u16 = 0x15;
+    const BPF_JMP_JGE_K: u16 = 0x35;
+    const BPF_RET_K: u16 = 0x06;
+    const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+    const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+    /// The offsets of `nr` and `arch` in `struct seccomp_data`.
+    const SECCOMP_DATA_NR: u32 = 0;
+    const SECCOMP_DATA_ARCH: u32 = 4;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xc000_003e;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xc000_00b7;
+    const X32_SYSCALL_BIT: u32 = 0x4000_0000;
+
+    /// The system calls allowed in the sandbox: the ones needed to talk to rustc over the socket,
+    /// manage memory, wait on locks and exit. Every other one fails with `EACCES`.
+    const ALLOWED_SYSCALLS: &[libc::c_long] = &[
+        libc::SYS_read,
+        libc::SYS_readv,
+        libc::SYS_recvfrom,
+        libc::SYS_write,
+        libc::SYS_writev,
+        libc::SYS_sendto,
+        libc::SYS_close,
+        libc::SYS_brk,
+        libc::SYS_mmap,
+        libc::SYS_mremap,
+        libc::SYS_munmap,
+        libc::SYS_mprotect,
+        libc::SYS_madvise,
+        libc::SYS_futex,
+        libc::SYS_sched_yield,
+        libc::SYS_getrandom,
+        libc::SYS_clock_gettime,
+        libc::SYS_rt_sigprocmask,
+        libc::SYS_rt_sigreturn,
+        libc::SYS_sigaltstack,
+        libc::SYS_exit,
+        libc::SYS_exit_group,
+    ];
+
+    fn statement(code: u16, k: u32) -> libc::sock_filter {
+        libc::sock_filter { code, jt: 0, jf: 0, k }
+    }
+
+    fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter { code, jt, jf, k }
+    }
+
+    /// Closes the files that the process inherited from rustc, except for the standard streams and
+    /// `socket`, which is moved to descriptor 3. They are closed because the seccomp filter still
+    /// allows reading and writing the files that are already open, like the jobserver pipes or
+    /// the incremental cache.
+    fn close_inherited_fds(socket: UnixStream) -> io::Result<UnixStream> {
+        const SOCKET_FD: libc::c_int = 3;
+        let fd = socket.into_raw_fd();
+        // SAFETY: this function owns `fd`, and the process is about to run only the client, so
+        // the other descriptors, including `SOCKET_FD` if it's not `fd`, aren't used anymore.
+        unsafe {
+            if fd != SOCKET_FD && libc::dup2(fd, SOCKET_FD) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            let first = SOCKET_FD as libc::c_uint + 1;
+            if libc::syscall(libc::SYS_close_range, first, libc::c_uint::MAX, 0 as libc::c_uint)
+                != 0
+            {
+                // `close_range` was added in Linux 5.9.
+                let err = io::Error::last_os_error();
+                if err.raw_os_error() != Some(libc::ENOSYS) {
+                    return Err(err);
+                }
+                let fds: Vec<libc::c_int> = fs::read_dir("/proc/self/fd")?
+                    .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+                    .collect();
+                for fd in fds {
+                    if fd > SOCKET_FD {
+                        libc::close(fd);
+                    }
+                }
+            }
+            Ok(UnixStream::from_raw_fd(SOCKET_FD))
+        }
+    }
+
+    /// Restricts the current process with a seccomp filter, which also applies to the processes
+    /// it could start.
+    fn install_sandbox() -> io::Result<()> {
+        let mut filter = vec![
+            statement(BPF_LD_W_ABS, SECCOMP_DATA_ARCH),
+            jump(BPF_JMP_JEQ_K, AUDIT_ARCH, 1, 0),
+            statement(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+            statement(BPF_LD_W_ABS, SECCOMP_DATA_NR),
+        ];
+        // x32 system calls have the same architecture as x86_64 ones, but other numbers.
+        if cfg!(target_arch = "x86_64") {
+            filter.push(jump(BPF_JMP_JGE_K, X32_SYSCALL_BIT, 0, 1));
+            filter.push(statement(BPF_RET_K, SECCOMP_RET_KILL_PROCESS));
+        }
+        for &syscall in ALLOWED_SYSCALLS {
+            filter.push(jump(BPF_JMP_JEQ_K, syscall as u32, 0, 1));
+            filter.push(statement(BPF_RET_K, SECCOMP_RET_ALLOW));
+        }
+        filter.push(statement(BPF_RET_K, SECCOMP_RET_ERRNO | libc::EACCES as u32));
+
+        let program =
+            libc::sock_fprog { len: filter.len() as libc::c_ushort, filter: filter.as_mut_ptr() };
+        // SAFETY: `program` points to a valid filter, which the kernel copies.
+        unsafe {
+            // `prctl` is variadic and reads its arguments as `unsigned long`s.
+            let (zero, one) = (0 as libc::c_ulong, 1 as libc::c_ulong);
+            if libc::prctl(libc::PR_SET_NO_NEW_PRIVS, one, zero, zero, zero) != 0
+                || libc::prctl(
+                    libc::PR_SET_SECCOMP,
+                    libc::SECCOMP_MODE_FILTER as libc::c_ulong,
+                    &program as *const libc::sock_fprog,
+                ) != 0
+            {
+                return Err(io::Error::last_os_error());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod unsupported {
+    pub(crate) enum ProcessPipe {}
+
+    impl pm::bridge::server::ProcessPipe for ProcessPipe {
+        fn spawn(_client: impl FnOnce(Self)) -> Result<Self, String> {
+            Err("`-Z proc-macro-sandbox` is only supported on x86_64 and aarch64 Linux".into())
+        }
+
+        fn send(&mut self, _tag: u8, _bytes: &[u8]) {
+            match *self {}
+        }
+
+        fn send_reply(&mut self, _bytes: &[u8]) {
+            match *self {}
+        }
+
+        fn recv(&mut self) -> Option<Vec<u8>> {
+            match *self {}
+        }
+
+        fn exit_status(self) -> String {
+            match self {}
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use crate::base::ExtCtxt;
+use crate::proc_macro_cache::{hash_bytes, read_dir_names, TrackedInput, TrackedInputs};
 use ast::token::IdentIsRaw;
 use pm::bridge::{
-    server, DelimSpan, Diagnostic, ExpnGlobals, Group, Ident, LitKind, Literal, Punct, TokenTree,
+    server, DelimSpan, Diagnostic, ExpnGlobals, Group, Ident, LitKind, LitValue, Literal, Punct,
+    TokenTree,
 };
-use pm::{Delimiter, Level};
+use pm::{Applicability, Delimiter, Level};
 use rustc_ast as ast;
 use rustc_ast::token;
 use rustc_ast::tokenstream::{self, DelimSpacing, Spacing, TokenStream};
@@ -15,12 +17,15 @@
 use rustc_parse::lexer::nfc_normalize;
 use rustc_parse::parser::Parser;
 use rustc_parse::{new_parser_from_source_str, source_str_to_stream, unwrap_or_emit_fatal};
+use rustc_session::lint::builtin::PROC_MACRO_WARNINGS;
+use rustc_session::lint::{BuiltinLintDiag, ProcMacroWarningDiag};
 use rustc_session::parse::ParseSess;
 use rustc_span::def_id::CrateNum;
 use rustc_span::symbol::{self, sym, Symbol};
 use rustc_span::{BytePos, FileName, Pos, SourceFile, Span};
 use smallvec::{smallvec, SmallVec};
 use std::ops::{Bound, Range};
+use std::path::Path;
 
 trait FromInternal<T> {
     fn from_internal(x: T) -> Self;
@@ -412,6 +417,18 @@ fn to_internal(self) -> rustc_errors::Level {
     }
 }
 
+impl ToInternal<rustc_errors::Applicability> for Applicability {
+    fn to_internal(self) -> rustc_errors::Applicability {
+        match self {
+            Applicability::MachineApplicable => rustc_errors::Applicability::MachineApplicable,
+            Applicability::MaybeIncorrect => rustc_errors::Applicability::MaybeIncorrect,
+            Applicability::HasPlaceholders => rustc_errors::Applicability::HasPlaceholders,
+            Applicability::Unspecified => rustc_errors::Applicability::Unspecified,
+            _ => unreachable!("unknown proc_macro::Applicability variant: {:?}", self),
+        }
+    }
+}
+
 pub struct FreeFunctions;
 
 pub(crate) struct Rustc<'a, 'b> {
@@ -421,10 +438,12 @@ pub(crate) struct Rustc<'a, 'b> {
     mixed_site: Span,
     krate: CrateNum,
     rebased_spans: FxHashMap<usize, Span>,
+    /// Where to record what the macro reads besides its input, if its expansion may be cached.
+    tracked_inputs: Option<&'a mut TrackedInputs>,
 }
 
 impl<'a, 'b> Rustc<'a, 'b> {
-    pub fn new(ecx: &'a mut ExtCtxt<'b>) -> Self {
+    pub fn new(ecx: &'a mut ExtCtxt<'b>, tracked_inputs: Option<&'a mut TrackedInputs>) -> Self {
         let expn_data = ecx.current_expansion.id.expn_data();
         Rustc {
             def_site: ecx.with_def_site_ctxt(expn_data.def_site),
@@ -432,6 +451,7 @@ pub fn new(ecx: &'a mut ExtCtxt<'b>) -> Self {
             mixed_site: ecx.with_mixed_site_ctxt(expn_data.call_site),
             krate: expn_data.macro_def_id.unwrap().krate,
             rebased_spans: FxHashMap::default(),
+            tracked_inputs,
             ecx,
         }
     }
@@ -439,6 +459,19 @@ pub fn new(ecx: &'a mut ExtCtxt<'b>) -> Self {
     fn psess(&self) -> &ParseSess {
         self.ecx.psess()
     }
+
+    fn track_input(&mut self, input: impl FnOnce() -> TrackedInput) {
+        if let Some(tracked_inputs) = &mut self.tracked_inputs {
+            tracked_inputs.inputs.push(input());
+        }
+    }
+
+    /// Notes that the macro did something that can't be replayed from a cached expansion.
+    fn note_side_effect(&mut self) {
+        if let Some(tracked_inputs) = &mut self.tracked_inputs {
+            tracked_inputs.has_side_effects = true;
+        }
+    }
 }
 
 impl server::Types for Rustc<'_, '_> {
@@ -459,10 +492,44 @@ fn track_env_var(&mut self, var: &str, value: Option<&str>) {
             .env_depinfo
             .borrow_mut()
             .insert((Symbol::intern(var), value.map(Symbol::intern)));
+        self.track_input(|| TrackedInput::EnvVar(var.to_owned(), value.map(str::to_owned)));
     }
 
     fn track_path(&mut self, path: &str) {
         self.psess().file_depinfo.borrow_mut().insert(Symbol::intern(path));
+        self.track_input(|| {
+            let hash = std::fs::read(path).ok().map(|bytes| hash_bytes(&bytes));
+            TrackedInput::Path(path.to_owned(), hash)
+        });
+    }
+
+    fn read_tracked_file(&mut self, path: &str) -> Result<Vec<u8>, String> {
+        // Loading the file in the source map puts it in the dep-info and in the hash of the crate,
+        // like for `include_bytes!`.
+        let bytes = self.psess().source_map().load_binary_file(Path::new(path));
+        self.track_input(|| {
+            let hash = bytes.as_ref().ok().map(|(bytes, _)| hash_bytes(bytes));
+            TrackedInput::File(path.to_owned(), hash)
+        });
+        match bytes {
+            Ok((bytes, _)) => Ok(bytes.to_vec()),
+            Err(err) => {
+                // The output of the macro may depend on the file not existing, so it still goes in
+                // the dep-info, for the build system to notice when it is created.
+                self.psess().file_depinfo.borrow_mut().insert(Symbol::intern(path));
+                Err(format!("couldn't read `{path}`: {err}"))
+            }
+        }
+    }
+
+    fn read_tracked_dir(&mut self, path: &str) -> Result<Vec<String>, String> {
+        let names = read_dir_names(Path::new(path));
+        self.track_input(|| TrackedInput::Dir(path.to_owned(), names.as_ref().ok().cloned()));
+        let names = names.map_err(|err| format!("couldn't read `{path}`: {err}"))?;
+        // The build system checks the modification time of the directory, which changes when
+        // entries are added or removed.
+        self.psess().file_depinfo.borrow_mut().insert(Symbol::intern(path));
+        Ok(names)
     }
 
     fn literal_from_str(&mut self, s: &str) -> Result<Literal<Self::Span, Self::Symbol>, ()> {
@@ -519,17 +586,96 @@ fn literal_from_str(&mut self, s: &str) -> Result<Literal<Self::Span, Self::Symb
         })
     }
 
+    fn literal_value(
+        &mut self,
+        literal: Literal<Self::Span, Self::Symbol>,
+    ) -> Result<LitValue, ()> {
+        // Numbers created from negative values, like `Literal::i32_unsuffixed(-1)`, have the minus
+        // sign in their symbol, which the compiler's decoding doesn't expect.
+        let (negative, symbol) = match literal.kind {
+            LitKind::Integer | LitKind::Float => match literal.symbol.as_str().strip_prefix('-') {
+                Some(symbol) => (true, Symbol::intern(symbol)),
+                None => (false, literal.symbol),
+            },
+            _ => (false, literal.symbol),
+        };
+        let lit = token::Lit::new(literal.kind.to_internal(), symbol, literal.suffix);
+        let value = match ast::LitKind::from_token_lit(lit).map_err(drop)? {
+            ast::LitKind::Str(symbol, _) => LitValue::Str(symbol.to_string()),
+            ast::LitKind::ByteStr(bytes, _) => LitValue::ByteStr(bytes.to_vec()),
+            ast::LitKind::CStr(bytes, _) => {
+                // Drop the nul terminator added by the compiler.
+                LitValue::CStr(bytes[..bytes.len() - 1].to_vec())
+            }
+            ast::LitKind::Byte(byte) => LitValue::Byte(byte),
+            ast::LitKind::Char(ch) => LitValue::Char(ch),
+            ast::LitKind::Int(n, _) if negative => LitValue::NegativeInt(n.get()),
+            ast::LitKind::Int(n, _) => LitValue::Int(n.get()),
+            ast::LitKind::Float(symbol, _) if negative => LitValue::Float(format!("-{symbol}")),
+            ast::LitKind::Float(symbol, _) => LitValue::Float(symbol.to_string()),
+            ast::LitKind::Bool(_) | ast::LitKind::Err(_) => return Err(()),
+        };
+        Ok(value)
+    }
+
+    // The messages come from the proc macro, and we are just reconstructing its diagnostic, so
+    // there's no need for translation.
+    #[allow(rustc::untranslatable_diagnostic)]
     fn emit_diagnostic(&mut self, diagnostic: Diagnostic<Self::Span>) {
+        self.note_side_effect();
+        let labels: Vec<_> =
+            diagnostic.labels.into_iter().map(|label| (label.span, label.message)).collect();
+        let suggestions: Vec<_> = diagnostic
+            .suggestions
+            .into_iter()
+            // A suggestion without any replacement can't be shown.
+            .filter(|suggestion| !suggestion.parts.is_empty())
+            .map(|suggestion| {
+                let parts =
+                    suggestion.parts.into_iter().map(|part| (part.span, part.message)).collect();
+                (suggestion.message, parts, suggestion.applicability.to_internal())
+            })
+            .collect();
+
+        if diagnostic.lint && matches!(diagnostic.level, Level::Warning) {
+            // Buffer the warning until the lint levels are known, so that it respects the
+            // `#[allow]`s around the invocation of the macro.
+            let (helps, notes) = diagnostic
+                .children
+                .into_iter()
+                .partition::<Vec<_>, _>(|child| matches!(child.level, Level::Help));
+            let to_messages = |children: Vec<Diagnostic<Span>>| {
+                children.into_iter().map(|child| (child.spans, child.message)).collect()
+            };
+            let warning = ProcMacroWarningDiag {
+                message: diagnostic.message,
+                labels,
+                notes: to_messages(notes),
+                helps: to_messages(helps),
+                suggestions,
+            };
+            self.psess().buffer_lint(
+                PROC_MACRO_WARNINGS,
+                MultiSpan::from_spans(diagnostic.spans),
+                self.ecx.current_expansion.lint_node_id,
+                BuiltinLintDiag::ProcMacroWarning { diag: warning },
+            );
+            return;
+        }
+
         let message = rustc_errors::DiagMessage::from(diagnostic.message);
         let mut diag: Diag<'_, ()> =
             Diag::new(self.psess().dcx(), diagnostic.level.to_internal(), message);
         diag.span(MultiSpan::from_spans(diagnostic.spans));
+        for (span, label) in labels {
+            diag.span_label(span, label);
+        }
         for child in diagnostic.children {
-            // This message comes from another diagnostic, and we are just reconstructing the
-            // diagnostic, so there's no need for translation.
-            #[allow(rustc::untranslatable_diagnostic)]
             diag.sub(child.level.to_internal(), child.message, MultiSpan::from_spans(child.spans));
         }
+        for (message, parts, applicability) in suggestions {
+            diag.multipart_suggestion(message, parts, applicability);
+        }
         diag.emit();
     }
 }
@@ -553,6 +699,7 @@ fn to_string(&mut self, stream: &Self::TokenStream) -> String {
     }
 
     fn expand_expr(&mut self, stream: &Self::TokenStream) -> Result<Self::TokenStream, ()> {
+        self.note_side_effect();
         // Parse the expression from our tokenstream.
         let expr: PResult<'_, _> = try {
             let mut p = Parser::new(self.psess(), stream.clone(), Some("proc_macro expand expr"));
@@ -353,12 +353,20 @@ pub struct BuiltinAttribute {
         macro_export, Normal, template!(Word, List: "local_inner_macros"),
         WarnFollowing, EncodeCrossCrate::Yes
     ),
+    gated!(
+        format_string, Normal, template!(List: "fmt, /*opt*/ args"), ErrorFollowing,
+        EncodeCrossCrate::Yes, macro_format_string, experimental!(format_string)
+    ),
     ungated!(proc_macro, Normal, template!(Word), ErrorFollowing, EncodeCrossCrate::No),
     ungated!(
         proc_macro_derive, Normal, template!(List: "TraitName, /*opt*/ attributes(name1, name2, ...)"),
         ErrorFollowing, EncodeCrossCrate::No,
     ),
     ungated!(proc_macro_attribute, Normal, template!(Word), ErrorFollowing, EncodeCrossCrate::No),
+    gated!(
+        proc_macro_pure, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::Yes,
+        experimental!(proc_macro_pure)
+    ),
 
     // Lints:
     ungated!(
@@ -514,6 +514,9 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, link_arg_attribute, "1.76.0", Some(99427)),
     /// Allows using `reason` in lint attributes and the `#[expect(lint)]` lint check.
     (unstable, lint_reasons, "1.31.0", Some(54503)),
+    /// Allows `#[format_string]` on declarative macros, to check their format strings like
+    /// `format_args!` does.
+    (unstable, macro_format_string, "CURRENT_RUSTC_VERSION", None),
     /// Give access to additional metadata about declarative macro meta-variables.
     (unstable, macro_metavar_expr, "1.61.0", Some(83527)),
     /// Provides a way to concatenate identifiers using metavariable expressions.
@@ -571,6 +574,9 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, precise_capturing, "1.79.0", Some(123432)),
     /// Allows macro attributes on expressions, statements and non-inline modules.
     (unstable, proc_macro_hygiene, "1.30.0", Some(54727)),
+    /// Allows `#[proc_macro_pure]` to declare that the output of a proc macro only depends on its
+    /// input and tracked inputs, so that it can be cached.
+    (unstable, proc_macro_pure, "CURRENT_RUSTC_VERSION", None),
     /// Allows `&raw const $place_expr` and `&raw mut $place_expr` expressions.
     (unstable, raw_ref_op, "1.41.0", Some(64490)),
     /// Makes `&` and `&mut` patterns eat only one layer of references in Rust 2024.
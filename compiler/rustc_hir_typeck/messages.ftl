@@ -154,6 +154,14 @@ hir_typeck_suggest_boxing_when_appropriate = store this in the heap by calling `
 
 hir_typeck_suggest_ptr_null_mut = consider using `core::ptr::null_mut` instead
 
+hir_typeck_transmute_not_proven_safe =
+    the safe transmute analysis can't prove that this transmute from `{$from}` to `{$to}` is safe
+    .note = {$explanation}
+
+hir_typeck_transmute_proven_safe =
+    the safe transmute analysis proves that this transmute from `{$from}` to `{$to}` is safe
+    .note = the analysis doesn't check lifetimes: make sure that the references in `{$to}` don't outlive the ones in `{$from}`
+
 hir_typeck_trivial_cast = trivial {$numeric ->
     [true] numeric cast
     *[false] cast
@@ -689,3 +689,25 @@ pub struct ReplaceWithName {
     pub span: Span,
     pub name: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(hir_typeck_transmute_proven_safe)]
+pub struct TransmuteProvenSafe<'tcx> {
+    #[primary_span]
+    pub span: Span,
+    pub from: Ty<'tcx>,
+    pub to: Ty<'tcx>,
+    #[note]
+    pub has_references: bool,
+}
+
+#[derive(Diagnostic)]
+#[diag(hir_typeck_transmute_not_proven_safe)]
+#[note]
+pub struct TransmuteNotProvenSafe<'tcx> {
+    #[primary_span]
+    pub span: Span,
+    pub from: Ty<'tcx>,
+    pub to: Ty<'tcx>,
+    pub explanation: String,
+}
@@ -1,13 +1,19 @@
 use hir::HirId;
+use rustc_data_structures::fx::FxHashSet;
 use rustc_errors::{codes::*, struct_span_code_err};
 use rustc_hir as hir;
 use rustc_index::Idx;
 use rustc_middle::bug;
+use rustc_middle::traits::ObligationCause;
 use rustc_middle::ty::layout::{LayoutError, SizeSkeleton};
 use rustc_middle::ty::{self, Ty, TyCtxt, TypeVisitableExt};
+use rustc_span::Span;
 use rustc_target::abi::{Pointer, VariantIdx};
+use rustc_transmute::layout::rustc::Ref;
+use rustc_transmute::{Answer, Condition};
 
 use super::FnCtxt;
+use crate::errors;
 
 /// If the type is `Option<T>`, it will return `T`, otherwise
 /// the type itself. Works on most `Option`-like types.
@@ -67,6 +73,9 @@ pub fn check_transmute(&self, from: Ty<'tcx>, to: Ty<'tcx>, hir_id: HirId) {
         // Check for same size using the skeletons.
         if let (Ok(sk_from), Ok(sk_to)) = (sk_from, sk_to) {
             if sk_from.same_size(sk_to) {
+                if tcx.sess.opts.unstable_opts.report_transmute_safety {
+                    self.report_transmute_safety(from, to, span);
+                }
                 return;
             }
 
@@ -137,4 +146,98 @@ pub fn check_transmute(&self, from: Ty<'tcx>, to: Ty<'tcx>, hir_id: HirId) {
             }
         }
     }
+
+    /// Reports whether the safe transmute analysis can prove that transmuting `from` into `to` is
+    /// safe, without any assumption, for `-Zreport-transmute-safety`.
+    fn report_transmute_safety(&self, from: Ty<'tcx>, to: Ty<'tcx>, span: Span) {
+        match self.prove_transmute_safety(from, to, &mut FxHashSet::default()) {
+            Ok(()) => {
+                let has_references = [from, to]
+                    .into_iter()
+                    .any(|ty| ty.walk().any(|arg| arg.as_type().is_some_and(|ty| ty.is_ref())));
+                self.dcx().emit_note(errors::TransmuteProvenSafe {
+                    span,
+                    from,
+                    to,
+                    has_references,
+                });
+            }
+            Err(explanation) => {
+                self.dcx().emit_note(errors::TransmuteNotProvenSafe {
+                    span,
+                    from,
+                    to,
+                    explanation,
+                });
+            }
+        }
+    }
+
+    /// Proves that `src` is transmutable into `dst`, or explains why it isn't.
+    fn prove_transmute_safety(
+        &self,
+        src: Ty<'tcx>,
+        dst: Ty<'tcx>,
+        in_progress: &mut FxHashSet<(Ty<'tcx>, Ty<'tcx>)>,
+    ) -> Result<(), String> {
+        // Recursive types can only be reached through references: we can assume that a
+        // transmutation we are already proving holds.
+        if !in_progress.insert((src, dst)) {
+            return Ok(());
+        }
+        let answer = rustc_transmute::TransmuteTypeEnv::new(self.infcx).is_transmutable(
+            ObligationCause::dummy(),
+            rustc_transmute::Types { src, dst },
+            rustc_transmute::Assume::default(),
+        );
+        let result = match answer {
+            Answer::Yes => Ok(()),
+            Answer::No(reason) => Err(reason
+                .explain(src, dst)
+                .unwrap_or_else(|| format!("`{src}` or `{dst}` references an error"))),
+            Answer::If(condition) => self.prove_transmute_condition(condition, in_progress),
+        };
+        in_progress.remove(&(src, dst));
+        result
+    }
+
+    fn prove_transmute_condition(
+        &self,
+        condition: Condition<Ref<'tcx>>,
+        in_progress: &mut FxHashSet<(Ty<'tcx>, Ty<'tcx>)>,
+    ) -> Result<(), String> {
+        match condition {
+            Condition::IfAll(conditions) => conditions
+                .into_iter()
+                .try_for_each(|condition| self.prove_transmute_condition(condition, in_progress)),
+            Condition::IfAny(conditions) => {
+                let mut explanation = None;
+                for condition in conditions {
+                    match self.prove_transmute_condition(condition, in_progress) {
+                        Ok(()) => return Ok(()),
+                        Err(err) => {
+                            explanation.get_or_insert(err);
+                        }
+                    }
+                }
+                Err(explanation.unwrap_or_default())
+            }
+            // These are the obligations that `BikeshedIntrinsicFrom` requires for references, see
+            // `confirm_transmutability_candidate`.
+            Condition::IfTransmutable { src, dst } => {
+                if src.mutability == hir::Mutability::Not {
+                    for ty in [src.ty, dst.ty] {
+                        if !ty.is_freeze(self.tcx, self.param_env) {
+                            return Err(format!("`{ty}` has interior mutability"));
+                        }
+                    }
+                }
+                self.prove_transmute_safety(src.ty, dst.ty, in_progress)?;
+                if dst.mutability == hir::Mutability::Mut {
+                    self.prove_transmute_safety(dst.ty, src.ty, in_progress)?;
+                }
+                Ok(())
+            }
+        }
+    }
 }
@@ -1,5 +1,7 @@
 use crate::util;
 
+use rustc_ast as ast;
+use rustc_ast::attr::AttrIdGenerator;
 use rustc_ast::token;
 use rustc_ast::{LitKind, MetaItemKind};
 use rustc_codegen_ssa::traits::CodegenBackend;
@@ -7,7 +9,7 @@
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_data_structures::jobserver;
 use rustc_data_structures::stable_hasher::StableHasher;
-use rustc_data_structures::sync::Lrc;
+use rustc_data_structures::sync::{Lrc, OnceLock};
 use rustc_errors::registry::Registry;
 use rustc_errors::{DiagCtxtHandle, ErrorGuaranteed};
 use rustc_lint::LintStore;
@@ -24,6 +26,7 @@
 use rustc_span::source_map::{FileLoader, RealFileLoader, SourceMapInputs};
 use rustc_span::symbol::sym;
 use rustc_span::FileName;
+use rustc_target::spec::Target;
 use std::path::PathBuf;
 use std::result;
 use std::sync::Arc;
@@ -43,8 +46,15 @@ pub struct Compiler {
     pub codegen_backend: Box<dyn CodegenBackend>,
     pub(crate) override_queries: Option<fn(&Session, &mut Providers)>,
     pub(crate) current_gcx: CurrentGcx,
+    /// The crate shared by the compilers of [`run_compilers`], see [`SharedCrate`].
+    pub(crate) shared_crate: Option<Lrc<SharedCrate>>,
 }
 
+/// The crate parsed by the first of the compilers run by [`run_compilers`], from which the other
+/// ones start instead of parsing the input again. It comes with the ids of the attributes made
+/// while parsing it, so that the other sessions don't reuse them.
+pub(crate) type SharedCrate = OnceLock<Result<(ast::Crate, AttrIdGenerator)>>;
+
 /// Converts strings provided as `--cfg [cfgspec]` into a `Cfg`.
 pub(crate) fn parse_cfg(dcx: DiagCtxtHandle<'_>, cfgs: Vec<String>) -> Cfg {
     cfgs.into_iter()
@@ -370,7 +380,7 @@ pub(crate) fn initialize_checked_jobserver(early_dcx: &EarlyDiagCtxt) {
 // JUSTIFICATION: before session exists, only config
 #[allow(rustc::bad_opt_access)]
 #[allow(rustc::untranslatable_diagnostic)] // FIXME: make this translatable
-pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Send) -> R {
+pub fn run_compiler<R: Send>(mut config: Config, f: impl FnOnce(&Compiler) -> R + Send) -> R {
     trace!("run_compiler");
 
     // Set parallel mode before thread pool creation, which will create `Lock`s.
@@ -384,7 +394,7 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
 
     let sysroot = filesearch::materialize_sysroot(config.opts.maybe_sysroot.clone());
     let target = config::build_target_config(&early_dcx, &config.opts, &sysroot);
-    let file_loader = config.file_loader.unwrap_or_else(|| Box::new(RealFileLoader));
+    let file_loader = config.file_loader.take().unwrap_or_else(|| Box::new(RealFileLoader));
     let path_mapping = config.opts.file_path_mapping();
     let hash_kind = config.opts.unstable_opts.src_hash_algorithm(&target);
 
@@ -394,98 +404,8 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
         config.opts.unstable_opts.threads,
         SourceMapInputs { file_loader, path_mapping, hash_kind },
         |current_gcx| {
-            // The previous `early_dcx` can't be reused here because it doesn't
-            // impl `Send`. Creating a new one is fine.
-            let early_dcx = EarlyDiagCtxt::new(config.opts.error_format);
-
-            let codegen_backend = match config.make_codegen_backend {
-                None => util::get_codegen_backend(
-                    &early_dcx,
-                    &sysroot,
-                    config.opts.unstable_opts.codegen_backend.as_deref(),
-                    &target,
-                ),
-                Some(make_codegen_backend) => {
-                    // N.B. `make_codegen_backend` takes precedence over
-                    // `target.default_codegen_backend`, which is ignored in this case.
-                    make_codegen_backend(&config.opts)
-                }
-            };
-
-            let temps_dir = config.opts.unstable_opts.temps_dir.as_deref().map(PathBuf::from);
-
-            let bundle = match rustc_errors::fluent_bundle(
-                config.opts.maybe_sysroot.clone(),
-                sysroot_candidates().to_vec(),
-                config.opts.unstable_opts.translate_lang.clone(),
-                config.opts.unstable_opts.translate_additional_ftl.as_deref(),
-                config.opts.unstable_opts.translate_directionality_markers,
-            ) {
-                Ok(bundle) => bundle,
-                Err(e) => early_dcx.early_fatal(format!("failed to load fluent bundle: {e}")),
-            };
-
-            let mut locale_resources = Vec::from(config.locale_resources);
-            locale_resources.push(codegen_backend.locale_resource());
-
-            let mut sess = rustc_session::build_session(
-                early_dcx,
-                config.opts,
-                CompilerIO {
-                    input: config.input,
-                    output_dir: config.output_dir,
-                    output_file: config.output_file,
-                    temps_dir,
-                },
-                bundle,
-                config.registry.clone(),
-                locale_resources,
-                config.lint_caps,
-                target,
-                sysroot,
-                util::rustc_version_str().unwrap_or("unknown"),
-                config.ice_file,
-                config.using_internal_features,
-                config.expanded_args,
-            );
-
-            codegen_backend.init(&sess);
-
-            let cfg = parse_cfg(sess.dcx(), config.crate_cfg);
-            let mut cfg = config::build_configuration(&sess, cfg);
-            util::add_configuration(&mut cfg, &mut sess, &*codegen_backend);
-            sess.psess.config = cfg;
-
-            let mut check_cfg = parse_check_cfg(sess.dcx(), config.crate_check_cfg);
-            check_cfg.fill_well_known(&sess.target);
-            sess.psess.check_config = check_cfg;
-
-            if let Some(psess_created) = config.psess_created {
-                psess_created(&mut sess.psess);
-            }
-
-            if let Some(hash_untracked_state) = config.hash_untracked_state {
-                let mut hasher = StableHasher::new();
-                hash_untracked_state(&sess, &mut hasher);
-                sess.opts.untracked_state_hash = hasher.finish()
-            }
-
-            // Even though the session holds the lint store, we can't build the
-            // lint store until after the session exists. And we wait until now
-            // so that `register_lints` sees the fully initialized session.
-            let mut lint_store = rustc_lint::new_lint_store(sess.enable_internal_lints());
-            if let Some(register_lints) = config.register_lints.as_deref() {
-                register_lints(&sess, &mut lint_store);
-                sess.registered_lints = true;
-            }
-            sess.lint_store = Some(Lrc::new(lint_store));
-
-            let compiler = Compiler {
-                sess,
-                codegen_backend,
-                override_queries: config.override_queries,
-                current_gcx,
-            };
+            let registry = config.registry.clone();
+            let compiler = create_compiler(config, sysroot, target, current_gcx, None);
 
             // There are two paths out of `f`.
             // - Normal exit.
@@ -496,7 +416,7 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
                 // If `f` panics, `finish_diagnostics` will run during
                 // unwinding because of the `defer`.
                 let sess_abort_guard = defer(|| {
-                    compiler.sess.finish_diagnostics(&config.registry);
+                    compiler.sess.finish_diagnostics(&registry);
                 });
 
                 let res = f(&compiler);
@@ -525,6 +445,194 @@ pub fn run_compiler<R: Send>(config: Config, f: impl FnOnce(&Compiler) -> R + Se
     )
 }
 
+/// Runs `f` with a compiler for each of `configs`, one after the other, like [`run_compiler`] does
+/// for a single configuration, but within the same session globals. The compilers share the
+/// interned symbols, the source map and the parsed crate: the input is only parsed by the first
+/// compiler, and the other ones expand and analyze a copy of its crate. Parse errors and the lints
+/// of the parser are therefore only reported by the first compiler.
+///
+/// The session globals and the thread pool are set up for the first configuration, whose file
+/// loader is used by all the compilers, so the configurations are expected to only differ in what
+/// is specific to a session, like their `crate_cfg`. Unlike with [`run_compiler`], the errors of a
+/// compilation don't stop the next ones, they are returned once all the compilers have run.
+// JUSTIFICATION: before session exists, only config
+#[allow(rustc::bad_opt_access)]
+#[allow(rustc::untranslatable_diagnostic)] // FIXME: make this translatable
+pub fn run_compilers(
+    mut configs: Vec<Config>,
+    mut f: impl FnMut(&Compiler) -> Result<()> + Send,
+) -> Result<()> {
+    trace!("run_compilers");
+
+    let Some(first) = configs.first_mut() else { return Ok(()) };
+
+    rustc_data_structures::sync::set_dyn_thread_safe_mode(first.opts.unstable_opts.threads > 1);
+
+    let early_dcx = EarlyDiagCtxt::new(first.opts.error_format);
+    initialize_checked_jobserver(&early_dcx);
+
+    crate::callbacks::setup_callbacks();
+
+    let sysroot = filesearch::materialize_sysroot(first.opts.maybe_sysroot.clone());
+    let target = config::build_target_config(&early_dcx, &first.opts, &sysroot);
+    let file_loader = first.file_loader.take().unwrap_or_else(|| Box::new(RealFileLoader));
+    let path_mapping = first.opts.file_path_mapping();
+    let hash_kind = first.opts.unstable_opts.src_hash_algorithm(&target);
+    let edition = first.opts.edition;
+    let threads = first.opts.unstable_opts.threads;
+
+    util::run_in_thread_pool_with_globals(
+        &early_dcx,
+        edition,
+        threads,
+        SourceMapInputs { file_loader, path_mapping, hash_kind },
+        |current_gcx| {
+            let shared_crate = Lrc::new(SharedCrate::new());
+            let mut result = Ok(());
+            for config in configs {
+                let early_dcx = EarlyDiagCtxt::new(config.opts.error_format);
+                let sysroot = filesearch::materialize_sysroot(config.opts.maybe_sysroot.clone());
+                let target = config::build_target_config(&early_dcx, &config.opts, &sysroot);
+                drop(early_dcx);
+
+                let registry = config.registry.clone();
+                let compiler = create_compiler(
+                    config,
+                    sysroot,
+                    target,
+                    current_gcx.clone(),
+                    Some(Lrc::clone(&shared_crate)),
+                );
+
+                let res = {
+                    // If `f` panics, `finish_diagnostics` will run during
+                    // unwinding because of the `defer`.
+                    let sess_abort_guard = defer(|| {
+                        compiler.sess.finish_diagnostics(&registry);
+                    });
+                    let res = f(&compiler);
+                    drop(sess_abort_guard);
+                    res
+                };
+                result = result.and(res);
+                if let Some(guar) = compiler.sess.dcx().has_errors() {
+                    result = result.and(Err(guar));
+                }
+
+                let prof = compiler.sess.prof.clone();
+                prof.generic_activity("drop_compiler").run(move || drop(compiler));
+            }
+            result
+        },
+    )
+}
+
+/// Creates the session and the compiler of `config`, within the session globals.
+// JUSTIFICATION: before session exists, only config
+#[allow(rustc::bad_opt_access)]
+#[allow(rustc::untranslatable_diagnostic)] // FIXME: make this translatable
+fn create_compiler(
+    config: Config,
+    sysroot: PathBuf,
+    target: Target,
+    current_gcx: CurrentGcx,
+    shared_crate: Option<Lrc<SharedCrate>>,
+) -> Compiler {
+    // The previous `early_dcx` can't be reused here because it doesn't
+    // impl `Send`. Creating a new one is fine.
+    let early_dcx = EarlyDiagCtxt::new(config.opts.error_format);
+
+    let codegen_backend = match config.make_codegen_backend {
+        None => util::get_codegen_backend(
+            &early_dcx,
+            &sysroot,
+            config.opts.unstable_opts.codegen_backend.as_deref(),
+            &target,
+        ),
+        Some(make_codegen_backend) => {
+            // N.B. `make_codegen_backend` takes precedence over
+            // `target.default_codegen_backend`, which is ignored in this case.
+            make_codegen_backend(&config.opts)
+        }
+    };
+
+    let temps_dir = config.opts.unstable_opts.temps_dir.as_deref().map(PathBuf::from);
+
+    let bundle = match rustc_errors::fluent_bundle(
+        config.opts.maybe_sysroot.clone(),
+        sysroot_candidates().to_vec(),
+        config.opts.unstable_opts.translate_lang.clone(),
+        config.opts.unstable_opts.translate_additional_ftl.as_deref(),
+        config.opts.unstable_opts.translate_directionality_markers,
+    ) {
+        Ok(bundle) => bundle,
+        Err(e) => early_dcx.early_fatal(format!("failed to load fluent bundle: {e}")),
+    };
+
+    let mut locale_resources = Vec::from(config.locale_resources);
+    locale_resources.push(codegen_backend.locale_resource());
+
+    let mut sess = rustc_session::build_session(
+        early_dcx,
+        config.opts,
+        CompilerIO {
+            input: config.input,
+            output_dir: config.output_dir,
+            output_file: config.output_file,
+            temps_dir,
+        },
+        bundle,
+        config.registry.clone(),
+        locale_resources,
+        config.lint_caps,
+        target,
+        sysroot,
+        util::rustc_version_str().unwrap_or("unknown"),
+        config.ice_file,
+        config.using_internal_features,
+        config.expanded_args,
+    );
+
+    codegen_backend.init(&sess);
+
+    let cfg = parse_cfg(sess.dcx(), config.crate_cfg);
+    let mut cfg = config::build_configuration(&sess, cfg);
+    util::add_configuration(&mut cfg, &mut sess, &*codegen_backend);
+    sess.psess.config = cfg;
+
+    let mut check_cfg = parse_check_cfg(sess.dcx(), config.crate_check_cfg);
+    check_cfg.fill_well_known(&sess.target);
+    sess.psess.check_config = check_cfg;
+
+    if let Some(psess_created) = config.psess_created {
+        psess_created(&mut sess.psess);
+    }
+
+    if let Some(hash_untracked_state) = config.hash_untracked_state {
+        let mut hasher = StableHasher::new();
+        hash_untracked_state(&sess, &mut hasher);
+        sess.opts.untracked_state_hash = hasher.finish()
+    }
+
+    // Even though the session holds the lint store, we can't build the
+    // lint store until after the session exists. And we wait until now
+    // so that `register_lints` sees the fully initialized session.
+    let mut lint_store = rustc_lint::new_lint_store(sess.enable_internal_lints());
+    if let Some(register_lints) = config.register_lints.as_deref() {
+        register_lints(&sess, &mut lint_store);
+        sess.registered_lints = true;
+    }
+    sess.lint_store = Some(Lrc::new(lint_store));
+
+    Compiler {
+        sess,
+        codegen_backend,
+        override_queries: config.override_queries,
+        current_gcx,
+        shared_crate,
+    }
+}
+
 pub fn try_print_query_stack(
     dcx: DiagCtxtHandle<'_>,
     num_frames: Option<usize>,
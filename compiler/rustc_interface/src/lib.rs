@@ -14,7 +14,7 @@
 pub mod util;
 
 pub use callbacks::setup_callbacks;
-pub use interface::{run_compiler, Config};
+pub use interface::{run_compiler, run_compilers, Config};
 pub use passes::DEFAULT_QUERY_PROVIDERS;
 pub use queries::Queries;
 
@@ -204,6 +204,7 @@ fn configure_and_expand(
             should_test: sess.is_test_crate(),
             span_debug: sess.opts.unstable_opts.span_debug,
             proc_macro_backtrace: sess.opts.unstable_opts.proc_macro_backtrace,
+            check_format_string: Some(rustc_builtin_macros::check_format_string),
         };
 
         let lint_store = LintStoreExpandImpl(lint_store);
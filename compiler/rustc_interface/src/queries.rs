@@ -3,6 +3,7 @@
 use crate::{errors, passes};
 
 use rustc_ast as ast;
+use rustc_ast::attr::AttrIdGenerator;
 use rustc_codegen_ssa::traits::CodegenBackend;
 use rustc_codegen_ssa::CodegenResults;
 use rustc_data_structures::steal::Steal;
@@ -100,7 +101,23 @@ pub fn finish(&self) -> FileEncodeResult {
     }
 
     pub fn parse(&self) -> Result<QueryResult<'_, ast::Crate>> {
-        self.parse.compute(|| passes::parse(&self.compiler.sess))
+        self.parse.compute(|| {
+            let sess = &self.compiler.sess;
+            let Some(shared_crate) = &self.compiler.shared_crate else {
+                return passes::parse(sess);
+            };
+            let (krate, attr_ids) = shared_crate
+                .get_or_init(|| {
+                    let krate = passes::parse(sess)?;
+                    let attr_ids = AttrIdGenerator::new();
+                    attr_ids.skip_ids_of(&sess.psess.attr_id_generator);
+                    Ok((krate, attr_ids))
+                })
+                .as_ref()
+                .map_err(|&guar| guar)?;
+            sess.psess.attr_id_generator.skip_ids_of(attr_ids);
+            Ok(krate.clone())
+        })
     }
 
     pub fn global_ctxt(&'tcx self) -> Result<QueryResult<'_, &'tcx GlobalCtxt<'tcx>>> {
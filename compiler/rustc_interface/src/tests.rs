@@ -7,9 +7,9 @@
     CollapseMacroDebuginfo, CoverageLevel, CoverageOptions, DebugInfo, DumpMonoStatsFormat,
     ErrorOutputType, ExternEntry, ExternLocation, Externs, FunctionReturn, InliningThreshold,
     Input, InstrumentCoverage, InstrumentXRay, LinkSelfContained, LinkerPluginLto, LocationDetail,
-    LtoCli, NextSolverConfig, OomStrategy, Options, OutFileName, OutputType, OutputTypes, PAuthKey,
-    PacRet, Passes, Polonius, ProcMacroExecutionStrategy, Strip, SwitchWithOptPath,
-    SymbolManglingVersion, WasiExecModel,
+    LtoCli, MirOptPipeline, MirPassRequest, NextSolverConfig, OomStrategy, Options, OutFileName,
+    OutputType, OutputTypes, PAuthKey, PacRet, Passes, Polonius, ProcMacroExecutionStrategy,
+    RegionGraphFormat, Strip, SwitchWithOptPath, SymbolManglingVersion, WasiExecModel,
 };
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
@@ -677,14 +677,21 @@ macro_rules! untracked {
 
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // tidy-alphabetical-start
+    untracked!(abi_baseline, Some(PathBuf::from("abc")));
     untracked!(assert_incr_state, Some(String::from("loaded")));
+    untracked!(cfg_sets, Some(PathBuf::from("cfg-sets.txt")));
+    untracked!(const_eval_profile_folded, Some(PathBuf::from("abc")));
     untracked!(deduplicate_diagnostics, false);
+    untracked!(dump_cfg_report, SwitchWithOptPath::Enabled(Some("cfg-reports/".into())));
     untracked!(dump_dep_graph, true);
+    untracked!(dump_macro_expansions, SwitchWithOptPath::Enabled(Some("expansions/".into())));
+    untracked!(dump_match_report, SwitchWithOptPath::Enabled(Some("match-reports/".into())));
     untracked!(dump_mir, Some(String::from("abc")));
     untracked!(dump_mir_dataflow, true);
     untracked!(dump_mir_dir, String::from("abc"));
     untracked!(dump_mir_exclude_pass_number, true);
     untracked!(dump_mir_graphviz, true);
+    untracked!(dump_mir_region_graph, Some(RegionGraphFormat::Html));
     untracked!(dump_mono_stats, SwitchWithOptPath::Enabled(Some("mono-items-dir/".into())));
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
     untracked!(dylib_lto, true);
@@ -699,6 +706,7 @@ macro_rules! untracked {
     untracked!(llvm_time_trace, true);
     untracked!(ls, vec!["all".to_owned()]);
     untracked!(macro_backtrace, true);
+    untracked!(macro_match_details, true);
     untracked!(meta_stats, true);
     untracked!(mir_include_spans, true);
     untracked!(nll_facts, true);
@@ -709,13 +717,16 @@ macro_rules! untracked {
     // `pre_link_arg` is omitted because it just forwards to `pre_link_args`.
     untracked!(pre_link_args, vec![String::from("abc"), String::from("def")]);
     untracked!(print_codegen_stats, true);
+    untracked!(print_const_eval_profile, true);
     untracked!(print_llvm_passes, true);
     untracked!(print_mono_items, Some(String::from("abc")));
     untracked!(print_type_sizes, true);
     untracked!(proc_macro_backtrace, true);
     untracked!(proc_macro_execution_strategy, ProcMacroExecutionStrategy::CrossThread);
+    untracked!(proc_macro_sandbox, true);
     untracked!(profile_closures, true);
     untracked!(query_dep_graph, true);
+    untracked!(report_transmute_safety, true);
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
     untracked!(self_profile_events, Some(vec![String::new()]));
     untracked!(shell_argfiles, true);
@@ -798,6 +809,13 @@ macro_rules! tracked {
     tracked!(mir_enable_passes, vec![("DestProp".to_string(), false)]);
     tracked!(mir_keep_place_mention, true);
     tracked!(mir_opt_level, Some(4));
+    tracked!(
+        mir_opt_pipeline,
+        Some(MirOptPipeline {
+            path: PathBuf::from("pipeline.txt"),
+            passes: vec![MirPassRequest { name: "GVN".to_string(), options: vec![] }]
+        })
+    );
     tracked!(move_size_limit, Some(4096));
     tracked!(mutable_noalias, false);
     tracked!(next_solver, Some(NextSolverConfig { coherence: true, globally: false }));
@@ -287,6 +287,9 @@ pub(super) fn decorate_lint(sess: &Session, diagnostic: BuiltinLintDiag, diag: &
         BuiltinLintDiag::AmbiguousGlobImports { diag: ambiguity } => {
             lints::AmbiguousGlobImports { ambiguity }.decorate_lint(diag);
         }
+        BuiltinLintDiag::ProcMacroWarning { diag: warning } => {
+            lints::ProcMacroWarning { warning }.decorate_lint(diag);
+        }
         BuiltinLintDiag::AmbiguousGlobReexports {
             name,
             namespace,
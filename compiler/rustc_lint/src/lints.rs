@@ -14,7 +14,10 @@
 use rustc_middle::ty::{
     inhabitedness::InhabitedPredicate, Clause, PolyExistentialTraitRef, Ty, TyCtxt,
 };
-use rustc_session::{lint::AmbiguityErrorDiag, Session};
+use rustc_session::{
+    lint::{AmbiguityErrorDiag, ProcMacroWarningDiag},
+    Session,
+};
 use rustc_span::{
     edition::Edition,
     sym,
@@ -2834,6 +2837,38 @@ fn decorate_lint<'b>(self, diag: &'b mut Diag<'a, G>) {
     }
 }
 
+// The messages come from the proc macro, so they can't be translated.
+pub struct ProcMacroWarning {
+    pub warning: ProcMacroWarningDiag,
+}
+
+impl<'a, G: EmissionGuarantee> LintDiagnostic<'a, G> for ProcMacroWarning {
+    fn decorate_lint<'b>(self, diag: &'b mut Diag<'a, G>) {
+        let warning = self.warning;
+        diag.primary_message(warning.message);
+        for (span, label) in warning.labels {
+            diag.span_label(span, label);
+        }
+        for (spans, note) in warning.notes {
+            if spans.is_empty() {
+                diag.note(note);
+            } else {
+                diag.span_note(spans, note);
+            }
+        }
+        for (spans, help) in warning.helps {
+            if spans.is_empty() {
+                diag.help(help);
+            } else {
+                diag.span_help(spans, help);
+            }
+        }
+        for (message, parts, applicability) in warning.suggestions {
+            diag.multipart_suggestion(message, parts, applicability);
+        }
+    }
+}
+
 #[derive(LintDiagnostic)]
 #[diag(lint_ambiguous_glob_reexport)]
 pub struct AmbiguousGlobReexports {
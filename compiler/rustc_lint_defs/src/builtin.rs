@@ -79,6 +79,7 @@
         PRIVATE_BOUNDS,
         PRIVATE_INTERFACES,
         PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
+        PROC_MACRO_WARNINGS,
         PUB_USE_OF_PRIVATE_EXTERN_CRATE,
         REDUNDANT_LIFETIMES,
         REFINING_IMPL_TRAIT_INTERNAL,
@@ -2162,6 +2163,59 @@
     };
 }
 
+declare_lint! {
+    /// The `proc_macro_warnings` lint detects the warnings that procedural macros emit with
+    /// `Diagnostic::as_lint`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs-dependency)
+    /// // foo.rs
+    /// #![feature(proc_macro_diagnostic)]
+    /// #![crate_type = "proc-macro"]
+    /// extern crate proc_macro;
+    /// use proc_macro::*;
+    ///
+    /// #[proc_macro_derive(Foo)]
+    /// pub fn foo(input: TokenStream) -> TokenStream {
+    ///     let span = input.into_iter().next().unwrap().span();
+    ///     Diagnostic::spanned(span, Level::Warning, "deriving `Foo` is deprecated").as_lint().emit();
+    ///     TokenStream::new()
+    /// }
+    /// ```
+    ///
+    /// ```rust,ignore (needs-dependency)
+    /// // bar.rs
+    /// #[macro_use]
+    /// extern crate foo;
+    ///
+    /// #[derive(Foo)]
+    /// struct Bar;
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: deriving `Foo` is deprecated
+    ///  --> src/main.rs:5:1
+    ///   |
+    /// 5 | struct Bar;
+    ///   | ^^^^^^
+    ///   |
+    ///   = note: `#[warn(proc_macro_warnings)]` on by default
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Procedural macros can emit warnings about their input. Unlike the warnings they emit
+    /// without `Diagnostic::as_lint`, these ones can be silenced with
+    /// `#[allow(proc_macro_warnings)]`, or turned into errors with `#[deny(proc_macro_warnings)]`,
+    /// like any other lint.
+    pub PROC_MACRO_WARNINGS,
+    Warn,
+    "detects warnings emitted by procedural macros",
+}
+
 declare_lint! {
     /// The `macro_use_extern_crate` lint detects the use of the [`macro_use` attribute].
     ///
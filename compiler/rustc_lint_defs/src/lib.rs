@@ -567,6 +567,18 @@ pub struct AmbiguityErrorDiag {
     pub b2_help_msgs: Vec<String>,
 }
 
+/// A warning emitted by a procedural macro as a `proc_macro_warnings` lint. The messages come
+/// from the macro, so they are not translatable.
+#[derive(Debug)]
+pub struct ProcMacroWarningDiag {
+    pub message: String,
+    pub labels: Vec<(Span, String)>,
+    pub notes: Vec<(Vec<Span>, String)>,
+    pub helps: Vec<(Vec<Span>, String)>,
+    /// The message, the replacements and the applicability of each suggestion.
+    pub suggestions: Vec<(String, Vec<(Span, String)>, Applicability)>,
+}
+
 #[derive(Debug, Clone)]
 pub enum DeprecatedSinceKind {
     InEffect,
@@ -667,6 +679,9 @@ pub enum BuiltinLintDiag {
     AmbiguousGlobImports {
         diag: AmbiguityErrorDiag,
     },
+    ProcMacroWarning {
+        diag: ProcMacroWarningDiag,
+    },
     AmbiguousGlobReexports {
         /// The name for which collision(s) have occurred.
         name: String,
@@ -572,6 +572,14 @@
         separate_provide_extern
     }
 
+    /// The passes of `-Zmir-opt-pipeline`, resolved and checked once for the whole crate: the
+    /// index of each valid pass request of the pipeline, with the index of the pass among the
+    /// default MIR optimizations.
+    query mir_opt_pipeline(_: ()) -> &'tcx [(usize, usize)] {
+        eval_always
+        desc { "resolving the passes of the custom MIR optimization pipeline" }
+    }
+
     /// Summarizes coverage IDs inserted by the `InstrumentCoverage` MIR pass
     /// (for compiler option `-Cinstrument-coverage`), after MIR optimizations
     /// have had a chance to potentially remove some of them.
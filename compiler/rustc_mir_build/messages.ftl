@@ -93,6 +93,8 @@ mir_build_const_pattern_depends_on_generic_parameter =
 
 mir_build_could_not_eval_const_pattern = could not evaluate constant pattern
 
+mir_build_couldnt_dump_match_report = could not write the match report: {$error}
+
 mir_build_deref_raw_pointer_requires_unsafe =
     dereference of raw pointer is unsafe and requires unsafe block
     .note = raw pointers may be null, dangling or unaligned; they can violate aliasing rules and cause data races: all of these are undefined behavior
@@ -994,3 +994,9 @@ fn add_to_diag_with<G: EmissionGuarantee, F: SubdiagMessageOp<G>>(
         diag.multipart_suggestion("desugar the match ergonomics", self.suggestion, applicability);
     }
 }
+
+#[derive(Diagnostic)]
+#[diag(mir_build_couldnt_dump_match_report)]
+pub(crate) struct CouldntDumpMatchReport {
+    pub(crate) error: String,
+}
@@ -1,3 +1,4 @@
+use super::match_report::{self, MatchJson};
 use crate::errors::*;
 
 use rustc_arena::{DroplessArena, TypedArena};
@@ -42,8 +43,12 @@ pub(crate) fn check_match(tcx: TyCtxt<'_>, def_id: LocalDefId) -> Result<(), Err
         pattern_arena: &pattern_arena,
         dropless_arena: &dropless_arena,
         error: Ok(()),
+        match_reports: Vec::new(),
     };
     visitor.visit_expr(&thir[expr]);
+    if !visitor.match_reports.is_empty() {
+        match_report::dump_match_reports(tcx, def_id, &visitor.match_reports);
+    }
 
     let origin = match tcx.def_kind(def_id) {
         DefKind::AssocFn | DefKind::Fn => "function argument",
@@ -92,6 +97,8 @@ struct MatchVisitor<'p, 'tcx> {
     /// report it stores it here. Some functions return `Result` to allow callers to short-circuit
     /// on error, but callers don't need to store it here again.
     error: Result<(), ErrorGuaranteed>,
+    /// The reports of the matches of this body, for `-Zdump-match-report`.
+    match_reports: Vec<MatchJson>,
 }
 
 // Visitor for a thir body. This calls `check_match`, `check_let` and `check_let_chain` as
@@ -461,6 +468,22 @@ fn check_match(
 
         let Ok(report) = self.analyze_patterns(&cx, &tarms, scrut.ty) else { return };
 
+        if self.tcx.sess.opts.unstable_opts.dump_match_report.enabled()
+            && matches!(source, hir::MatchSource::Normal | hir::MatchSource::Postfix)
+        {
+            let pattern_complexity_limit =
+                get_limit_size(cx.tcx.hir().krate_attrs(), cx.tcx.sess, sym::pattern_complexity);
+            if let Ok(match_report) = rustc_pattern_analysis::report_match(
+                &cx,
+                &tarms,
+                scrut.ty,
+                pattern_complexity_limit,
+            ) {
+                let json = match_report::match_to_json(&cx, &match_report, scrut.ty, expr_span);
+                self.match_reports.push(json);
+            }
+        }
+
         match source {
             // Don't report arm reachability of desugared `match $iter.into_iter() { iter => .. }`
             // when the iterator is an uninhabited type. unreachable_code will trigger instead.
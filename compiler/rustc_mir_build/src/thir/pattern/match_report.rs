@@ -0,0 +1,118 @@
+//! Implementation of `-Z dump-match-report`, which writes the exhaustiveness and arm reachability
+//! of each `match` of a body to a JSON file, for tools that want to check their matches.
+
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+use rustc_middle::ty::{Ty, TyCtxt};
+use rustc_pattern_analysis::rustc::{MatchReport, RustcPatCtxt as PatCtxt};
+use rustc_session::config::SwitchWithOptPath;
+use rustc_span::def_id::LocalDefId;
+use rustc_span::Span;
+
+use crate::errors::CouldntDumpMatchReport;
+
+#[derive(serde::Serialize)]
+pub(super) struct MatchJson {
+    span: String,
+    scrutinee_type: String,
+    exhaustive: bool,
+    /// The patterns that are not covered by any arm.
+    missing_patterns: Vec<String>,
+    arms: Vec<ArmJson>,
+}
+
+#[derive(serde::Serialize)]
+struct ArmJson {
+    span: String,
+    pattern: Option<String>,
+    has_guard: bool,
+    /// `reachable`, `only_reachable_via_guards` or `redundant`.
+    reachability: &'static str,
+    /// The spans of the or-pattern alternatives of this arm that are redundant.
+    redundant_subpatterns: Vec<String>,
+    /// The indices of the arms above this one that match some of the same values.
+    intersects_with: Vec<usize>,
+}
+
+#[derive(serde::Serialize)]
+struct BodyJson<'a> {
+    item: String,
+    matches: &'a [MatchJson],
+}
+
+pub(super) fn match_to_json<'p, 'tcx>(
+    cx: &PatCtxt<'p, 'tcx>,
+    report: &MatchReport<'p, 'tcx>,
+    scrut_ty: Ty<'tcx>,
+    span: Span,
+) -> MatchJson {
+    let source_map = cx.tcx.sess.source_map();
+    let span_to_string = |span: Span| source_map.span_to_embeddable_string(span);
+    let arms = report
+        .arms
+        .iter()
+        .map(|arm| {
+            let pat_span = arm.arm.pat.data().span;
+            let mut redundant_subpatterns: Vec<_> =
+                arm.redundant_subpatterns.iter().map(|pat| pat.data().span).collect();
+            redundant_subpatterns.sort_unstable();
+            ArmJson {
+                span: span_to_string(pat_span),
+                pattern: source_map.span_to_snippet(pat_span).ok(),
+                has_guard: arm.arm.has_guard,
+                reachability: arm.reachability.as_str(),
+                redundant_subpatterns: redundant_subpatterns
+                    .into_iter()
+                    .map(span_to_string)
+                    .collect(),
+                intersects_with: arm.intersects_with.clone(),
+            }
+        })
+        .collect();
+    MatchJson {
+        span: span_to_string(span),
+        scrutinee_type: scrut_ty.to_string(),
+        exhaustive: report.is_exhaustive(),
+        missing_patterns: report
+            .missing_witnesses
+            .iter()
+            .map(|witness| cx.hoist_witness_pat(witness).to_string())
+            .collect(),
+        arms,
+    }
+}
+
+/// Writes the reports of the matches of `def_id` to `<crate>.<item>.matches.json`, in the
+/// directory given to `-Z dump-match-report`.
+pub(super) fn dump_match_reports(tcx: TyCtxt<'_>, def_id: LocalDefId, matches: &[MatchJson]) {
+    let SwitchWithOptPath::Enabled(ref directory) = tcx.sess.opts.unstable_opts.dump_match_report
+    else {
+        return;
+    };
+    if let Err(err) = write_match_reports(tcx, def_id, directory.as_deref(), matches) {
+        tcx.dcx().emit_fatal(CouldntDumpMatchReport { error: err.to_string() });
+    }
+}
+
+fn write_match_reports(
+    tcx: TyCtxt<'_>,
+    def_id: LocalDefId,
+    directory: Option<&Path>,
+    matches: &[MatchJson],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let directory = if let Some(directory) = directory {
+        fs::create_dir_all(directory)?;
+        directory
+    } else {
+        Path::new(".")
+    };
+
+    let crate_name = tcx.crate_name(def_id.to_def_id().krate);
+    let item_name = tcx.def_path(def_id.to_def_id()).to_filename_friendly_no_crate();
+    let file = File::create(directory.join(format!("{crate_name}.{item_name}.matches.json")))?;
+    let body = BodyJson { item: tcx.def_path_str(def_id), matches };
+    serde_json::to_writer_pretty(BufWriter::new(file), &body)?;
+    Ok(())
+}
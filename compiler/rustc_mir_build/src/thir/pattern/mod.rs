@@ -2,6 +2,7 @@
 
 mod check_match;
 mod const_to_pat;
+mod match_report;
 
 pub(crate) use self::check_match::check_match;
 
@@ -27,3 +27,8 @@ mir_transform_unaligned_packed_ref = reference to packed field is unaligned
     .note = packed structs are only aligned by one byte, and many modern architectures penalize unaligned field accesses
     .note_ub = creating a misaligned reference is undefined behavior (even if that reference is never dereferenced)
     .help = copy the field contents to a local variable, or replace the reference with a raw pointer and use `read_unaligned`/`write_unaligned` (loads and stores via `*p` must be properly aligned even when using raw pointers)
+
+mir_transform_unknown_pipeline_pass = unknown MIR pass `{$name}` in `-Zmir-opt-pipeline`
+    .note = the available passes are: {$available}
+
+mir_transform_unknown_pipeline_pass_option = MIR pass `{$pass}` has no option `{$option}`
@@ -120,3 +120,18 @@ pub(crate) struct MustNotSuspendReason {
     pub span: Span,
     pub reason: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(mir_transform_unknown_pipeline_pass)]
+#[note]
+pub(crate) struct UnknownPipelinePass<'a> {
+    pub name: &'a str,
+    pub available: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(mir_transform_unknown_pipeline_pass_option)]
+pub(crate) struct UnknownPipelinePassOption<'a> {
+    pub pass: &'a str,
+    pub option: &'a str,
+}
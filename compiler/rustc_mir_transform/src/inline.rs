@@ -31,6 +31,13 @@
 
 pub struct Inline;
 
+/// `Inline` with explicit thresholds, used by `-Zmir-opt-pipeline` in place of
+/// `-Zinline-mir-threshold` and `-Zinline-mir-hint-threshold`.
+pub struct InlineWithThresholds {
+    pub threshold: Option<usize>,
+    pub hint_threshold: Option<usize>,
+}
+
 #[derive(Copy, Clone, Debug)]
 struct CallSite<'tcx> {
     callee: Instance<'tcx>,
@@ -56,10 +63,24 @@ fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
         }
     }
 
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        InlineWithThresholds { threshold: None, hint_threshold: None }.run_pass(tcx, body)
+    }
+}
+
+impl<'tcx> MirPass<'tcx> for InlineWithThresholds {
+    fn name(&self) -> &'static str {
+        Inline.name()
+    }
+
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        Inline.is_enabled(sess)
+    }
+
     fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         let span = trace_span!("inline", body = %tcx.def_path_str(body.source.def_id()));
         let _guard = span.enter();
-        if inline(tcx, body) {
+        if inline(tcx, body, self) {
             debug!("running simplify cfg on {:?}", body.source);
             simplify_cfg(body);
             deref_finder(tcx, body);
@@ -67,7 +88,11 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
     }
 }
 
-fn inline<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) -> bool {
+fn inline<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &mut Body<'tcx>,
+    thresholds: &InlineWithThresholds,
+) -> bool {
     let def_id = body.source.def_id().expect_local();
 
     // Only do inlining into fn bodies.
@@ -86,10 +111,13 @@ fn inline<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) -> bool {
 
     let param_env = tcx.param_env_reveal_all_normalized(def_id);
 
+    let opts = &tcx.sess.opts.unstable_opts;
     let mut this = Inliner {
         tcx,
         param_env,
         codegen_fn_attrs: tcx.codegen_fn_attrs(def_id),
+        threshold: thresholds.threshold.or(opts.inline_mir_threshold).unwrap_or(50),
+        hint_threshold: thresholds.hint_threshold.or(opts.inline_mir_hint_threshold).unwrap_or(100),
         history: Vec::new(),
         changed: false,
     };
@@ -103,6 +131,10 @@ struct Inliner<'tcx> {
     param_env: ParamEnv<'tcx>,
     /// Caller codegen attributes.
     codegen_fn_attrs: &'tcx CodegenFnAttrs,
+    /// Inlining threshold for callees that are not cross-crate inlinable.
+    threshold: usize,
+    /// Inlining threshold for cross-crate inlinable callees.
+    hint_threshold: usize,
     /// Stack of inlined instances.
     /// We only check the `DefId` and not the args because we want to
     /// avoid inlining cases of polymorphic recursion.
@@ -475,11 +507,8 @@ fn check_mir_body(
     ) -> Result<(), &'static str> {
         let tcx = self.tcx;
 
-        let mut threshold = if cross_crate_inlinable {
-            self.tcx.sess.opts.unstable_opts.inline_mir_hint_threshold.unwrap_or(100)
-        } else {
-            self.tcx.sess.opts.unstable_opts.inline_mir_threshold.unwrap_or(50)
-        };
+        let mut threshold =
+            if cross_crate_inlinable { self.hint_threshold } else { self.threshold };
 
         // Give a bonus functions with a small number of blocks,
         // We normally have two or three blocks for even
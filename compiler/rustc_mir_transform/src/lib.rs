@@ -82,13 +82,16 @@
 mod jump_threading;
 mod known_panics_lint;
 mod large_enums;
+mod licm;
 mod lint;
+mod loops;
 mod lower_intrinsics;
 mod lower_slice_len;
 mod match_branches;
 mod mentioned_items;
 mod multiple_return_terminators;
 mod nrvo;
+mod pass_pipeline;
 mod prettify;
 mod promote_consts;
 mod ref_prop;
@@ -110,7 +113,8 @@
 mod sroa;
 mod unreachable_enum_branching;
 mod unreachable_prop;
-mod validate;
+// This pass is public to allow external drivers to validate the MIR they produce
+pub mod validate;
 
 use rustc_const_eval::check_consts::{self, ConstCx};
 use rustc_mir_dataflow::rustc_peek;
@@ -131,6 +135,7 @@ pub fn provide(providers: &mut Providers) {
         mir_for_ctfe,
         mir_coroutine_witnesses: coroutine::mir_coroutine_witnesses,
         optimized_mir,
+        mir_opt_pipeline: pass_pipeline::mir_opt_pipeline,
         is_mir_available,
         is_ctfe_mir_available: |tcx, did| is_mir_available(tcx, did),
         mir_callgraph_reachable: inline::cycle::mir_callgraph_reachable,
@@ -552,77 +557,102 @@ fn run_runtime_cleanup_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
     }
 }
 
-fn run_optimization_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+/// Calls `f` with the main optimizations that we do on MIR, which `-Zmir-opt-pipeline` can
+/// replace.
+fn with_optimization_passes<'tcx, R>(f: impl FnOnce(&[&dyn MirPass<'tcx>]) -> R) -> R {
     fn o1<T>(x: T) -> WithMinOptLevel<T> {
         WithMinOptLevel(1, x)
     }
 
-    // The main optimizations that we do on MIR.
-    pm::run_passes(
-        tcx,
-        body,
-        &[
+    let optimizations: &[&dyn MirPass<'tcx>] = &[
+        // Before inlining: trim down MIR with passes to reduce inlining work.
+
+        // Has to be done before inlining, otherwise actual call will be almost always inlined.
+        // Also simple, so can just do first
+        &lower_slice_len::LowerSliceLenCalls,
+        // Perform inlining, which may add a lot of code.
+        &inline::Inline,
+        // Code from other crates may have storage markers, so this needs to happen after inlining.
+        &remove_storage_markers::RemoveStorageMarkers,
+        // Inlining and instantiation may introduce ZST and useless drops.
+        &remove_zsts::RemoveZsts,
+        &remove_unneeded_drops::RemoveUnneededDrops,
+        // Type instantiation may create uninhabited enums.
+        // Also eliminates some unreachable branches based on variants of enums.
+        &unreachable_enum_branching::UnreachableEnumBranching,
+        &unreachable_prop::UnreachablePropagation,
+        &o1(simplify::SimplifyCfg::AfterUnreachableEnumBranching),
+        // Inlining may have introduced a lot of redundant code and a large move pattern.
+        // Now, we need to shrink the generated MIR.
+        &ref_prop::ReferencePropagation,
+        &sroa::ScalarReplacementOfAggregates,
+        &match_branches::MatchBranchSimplification,
+        // inst combine is after MatchBranchSimplification to clean up Ne(_1, false)
+        &multiple_return_terminators::MultipleReturnTerminators,
+        &instsimplify::InstSimplify,
+        &simplify::SimplifyLocals::BeforeConstProp,
+        &dead_store_elimination::DeadStoreElimination::Initial,
+        &gvn::GVN,
+        &simplify::SimplifyLocals::AfterGVN,
+        &dataflow_const_prop::DataflowConstProp,
+        &single_use_consts::SingleUseConsts,
+        &o1(simplify_branches::SimplifyConstCondition::AfterConstProp),
+        &jump_threading::JumpThreading,
+        // Hoist the computations and bounds checks that do not depend on the iteration out of
+        // loops. This needs the SSA locals that destination propagation would merge.
+        &licm::LoopInvariantCodeMotion,
+        &early_otherwise_branch::EarlyOtherwiseBranch,
+        &simplify_comparison_integral::SimplifyComparisonIntegral,
+        &dest_prop::DestinationPropagation,
+        &o1(simplify_branches::SimplifyConstCondition::Final),
+        &o1(remove_noop_landing_pads::RemoveNoopLandingPads),
+        &o1(simplify::SimplifyCfg::Final),
+        &copy_prop::CopyProp,
+        &dead_store_elimination::DeadStoreElimination::Final,
+        &nrvo::RenameReturnPlace,
+        &simplify::SimplifyLocals::Final,
+        &multiple_return_terminators::MultipleReturnTerminators,
+        &deduplicate_blocks::DeduplicateBlocks,
+        &large_enums::EnumSizeOpt { discrepancy: 128 },
+    ];
+
+    f(optimizations)
+}
+
+fn run_optimization_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+    with_optimization_passes(|optimizations| {
+        let pipeline = tcx
+            .sess
+            .opts
+            .unstable_opts
+            .mir_opt_pipeline
+            .as_ref()
+            .map(|pipeline| pass_pipeline::build_pipeline(tcx, pipeline, optimizations));
+        let optimizations: Vec<&dyn MirPass<'tcx>> = match &pipeline {
+            Some(pipeline) => pipeline.iter().map(|pass| pass as &dyn MirPass<'tcx>).collect(),
+            None => optimizations.to_vec(),
+        };
+
+        let mut passes: Vec<&dyn MirPass<'tcx>> = vec![
             // Before doing anything, remember which items are being mentioned so that the set of items
             // visited does not depend on the optimization level.
             &mentioned_items::MentionedItems,
             // Add some UB checks before any UB gets optimized away.
             &check_alignment::CheckAlignment,
-            // Before inlining: trim down MIR with passes to reduce inlining work.
-
-            // Has to be done before inlining, otherwise actual call will be almost always inlined.
-            // Also simple, so can just do first
-            &lower_slice_len::LowerSliceLenCalls,
-            // Perform inlining, which may add a lot of code.
-            &inline::Inline,
-            // Code from other crates may have storage markers, so this needs to happen after inlining.
-            &remove_storage_markers::RemoveStorageMarkers,
-            // Inlining and instantiation may introduce ZST and useless drops.
-            &remove_zsts::RemoveZsts,
-            &remove_unneeded_drops::RemoveUnneededDrops,
-            // Type instantiation may create uninhabited enums.
-            // Also eliminates some unreachable branches based on variants of enums.
-            &unreachable_enum_branching::UnreachableEnumBranching,
-            &unreachable_prop::UnreachablePropagation,
-            &o1(simplify::SimplifyCfg::AfterUnreachableEnumBranching),
-            // Inlining may have introduced a lot of redundant code and a large move pattern.
-            // Now, we need to shrink the generated MIR.
-            &ref_prop::ReferencePropagation,
-            &sroa::ScalarReplacementOfAggregates,
-            &match_branches::MatchBranchSimplification,
-            // inst combine is after MatchBranchSimplification to clean up Ne(_1, false)
-            &multiple_return_terminators::MultipleReturnTerminators,
-            &instsimplify::InstSimplify,
-            &simplify::SimplifyLocals::BeforeConstProp,
-            &dead_store_elimination::DeadStoreElimination::Initial,
-            &gvn::GVN,
-            &simplify::SimplifyLocals::AfterGVN,
-            &dataflow_const_prop::DataflowConstProp,
-            &single_use_consts::SingleUseConsts,
-            &o1(simplify_branches::SimplifyConstCondition::AfterConstProp),
-            &jump_threading::JumpThreading,
-            &early_otherwise_branch::EarlyOtherwiseBranch,
-            &simplify_comparison_integral::SimplifyComparisonIntegral,
-            &dest_prop::DestinationPropagation,
-            &o1(simplify_branches::SimplifyConstCondition::Final),
-            &o1(remove_noop_landing_pads::RemoveNoopLandingPads),
-            &o1(simplify::SimplifyCfg::Final),
-            &copy_prop::CopyProp,
-            &dead_store_elimination::DeadStoreElimination::Final,
-            &nrvo::RenameReturnPlace,
-            &simplify::SimplifyLocals::Final,
-            &multiple_return_terminators::MultipleReturnTerminators,
-            &deduplicate_blocks::DeduplicateBlocks,
-            &large_enums::EnumSizeOpt { discrepancy: 128 },
+        ];
+        passes.extend(optimizations);
+        passes.extend([
             // Some cleanup necessary at least for LLVM and potentially other codegen backends.
-            &add_call_guards::CriticalCallEdges,
+            &add_call_guards::CriticalCallEdges as &dyn MirPass<'tcx>,
             // Cleanup for human readability, off by default.
             &prettify::ReorderBasicBlocks,
             &prettify::ReorderLocals,
             // Dump the end result for testing and debugging purposes.
             &dump_mir::Marker("PreCodegen"),
-        ],
-        Some(MirPhase::Runtime(RuntimePhase::Optimized)),
-    );
+        ]);
+
+        pm::run_passes(tcx, body, &passes, Some(MirPhase::Runtime(RuntimePhase::Optimized)));
+    })
 }
 
 /// Optimize the MIR and prepare it for codegen.
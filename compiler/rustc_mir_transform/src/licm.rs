@@ -0,0 +1,444 @@
+//! Loop-invariant code motion.
+//!
+//! This pass moves computations whose result does not change between iterations of a loop to a
+//! preheader, a block that runs once before the loop is entered:
+//!
+//! - an assignment `_x = rvalue` is hoisted when `_x` is SSA, `rvalue` cannot have any effect
+//!   other than computing its result and all its operands are loop-invariant copies or constants.
+//!   This includes the `Len` of a slice behind an invariant pointer and the comparisons that feed
+//!   the bounds checks;
+//! - an `Assert` whose condition and message are loop-invariant is hoisted when it is reached
+//!   from the loop header through side-effect free code. Such an assertion runs on the first
+//!   iteration and has the same outcome on every later one, so checking it once before the loop
+//!   does not change which panic is observed.
+//!
+//! Hoisted assignments may run even when the loop body would not have executed them, so we only
+//! hoist operations that cannot cause UB: no division, unchecked arithmetic, pointer offset,
+//! transmute or read through a pointer.
+
+use rustc_index::bit_set::BitSet;
+use rustc_index::IndexVec;
+use rustc_middle::bug;
+use rustc_middle::mir::visit::*;
+use rustc_middle::mir::*;
+use rustc_middle::ty::TyCtxt;
+use rustc_mir_dataflow::impls::borrowed_locals;
+
+use crate::loops::{natural_loops, NaturalLoop};
+use crate::ssa::SsaLocals;
+
+pub struct LoopInvariantCodeMotion;
+
+impl<'tcx> MirPass<'tcx> for LoopInvariantCodeMotion {
+    fn is_enabled(&self, sess: &rustc_session::Session) -> bool {
+        sess.mir_opt_level() >= 2
+    }
+
+    #[instrument(level = "trace", skip(self, tcx, body))]
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        debug!(def_id = ?body.source.def_id());
+
+        let mut loops = natural_loops(body);
+        if loops.is_empty() {
+            return;
+        }
+
+        let param_env = tcx.param_env_reveal_all_normalized(body.source.def_id());
+        let ssa = SsaLocals::new(tcx, body, param_env);
+        let mut def_blocks = DefinitionBlocks(IndexVec::from_elem(None, &body.local_decls));
+        def_blocks.visit_body(body);
+
+        let mut hoister = Hoister {
+            tcx,
+            ssa,
+            def_blocks: def_blocks.0,
+            borrowed: borrowed_locals(body),
+            hoisted_locals: BitSet::new_empty(body.local_decls.len()),
+        };
+
+        // Loops are sorted from the innermost, so code hoisted out of a nested loop can then be
+        // hoisted out of the loop that contains it.
+        for i in 0..loops.len() {
+            let new_blocks = hoister.hoist_out_of(body, &loops[i]);
+            let header = loops[i].header;
+            for outer in &mut loops[i + 1..] {
+                if outer.blocks.contains(header) {
+                    for &block in &new_blocks {
+                        outer.blocks.insert(block);
+                    }
+                }
+            }
+        }
+
+        if !hoister.hoisted_locals.is_empty() {
+            StorageRemover { tcx, hoisted_locals: hoister.hoisted_locals }
+                .visit_body_preserves_cfg(body);
+        }
+    }
+}
+
+/// Records the block that assigns each local. This is only meaningful for SSA locals.
+struct DefinitionBlocks(IndexVec<Local, Option<BasicBlock>>);
+
+impl<'tcx> Visitor<'tcx> for DefinitionBlocks {
+    fn visit_local(&mut self, local: Local, ctxt: PlaceContext, location: Location) {
+        if let PlaceContext::MutatingUse(MutatingUseContext::Store | MutatingUseContext::Call) =
+            ctxt
+        {
+            self.0[local] = Some(location.block);
+        }
+    }
+}
+
+struct Hoister<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    ssa: SsaLocals,
+    /// Block that assigns each SSA local, updated as assignments are hoisted. `None` for
+    /// arguments, which are available everywhere.
+    def_blocks: IndexVec<Local, Option<BasicBlock>>,
+    borrowed: BitSet<Local>,
+    /// Hoisted locals. They are now assigned before the loop, so their storage statements
+    /// must go and they can no longer be moved from inside of it.
+    hoisted_locals: BitSet<Local>,
+}
+
+impl<'tcx> Hoister<'tcx> {
+    /// Hoists the invariant code out of `natural_loop` and returns the blocks that were created
+    /// for it, which belong to the loops that contain `natural_loop`.
+    fn hoist_out_of(
+        &mut self,
+        body: &mut Body<'tcx>,
+        natural_loop: &NaturalLoop,
+    ) -> Vec<BasicBlock> {
+        let header = natural_loop.header;
+        // The entry block and cleanup blocks cannot be given a preheader.
+        if header == START_BLOCK || body.basic_blocks[header].is_cleanup {
+            return vec![];
+        }
+
+        let mut candidates = Candidates {
+            natural_loop,
+            hoisted: BitSet::new_empty(body.local_decls.len()),
+            statements: vec![],
+            asserts: vec![],
+        };
+        self.find_invariant_statements(body, &mut candidates);
+        self.find_invariant_asserts(body, &mut candidates);
+        if candidates.statements.is_empty() && candidates.asserts.is_empty() {
+            return vec![];
+        }
+        debug!(?header, ?candidates.statements, ?candidates.asserts);
+
+        let Some(mut preheader) = self.preheader(body, natural_loop) else { return vec![] };
+        let mut new_blocks = vec![preheader];
+
+        let basic_blocks = body.basic_blocks_mut();
+        for location in candidates.statements {
+            let statement =
+                basic_blocks[location.block].statements[location.statement_index].replace_nop();
+            let StatementKind::Assign(box (place, _)) = statement.kind else { bug!() };
+            self.def_blocks[place.local] = Some(preheader);
+            self.hoisted_locals.insert(place.local);
+            basic_blocks[preheader].statements.push(statement);
+        }
+
+        // Each hoisted assertion ends the current preheader, and continues in a new one.
+        for block in candidates.asserts {
+            let terminator = basic_blocks[block].terminator_mut();
+            let TerminatorKind::Assert { target, .. } = terminator.kind else { bug!() };
+            let source_info = terminator.source_info;
+            let TerminatorKind::Assert { cond, expected, msg, unwind, .. } =
+                std::mem::replace(&mut terminator.kind, TerminatorKind::Goto { target })
+            else {
+                bug!()
+            };
+
+            let next = basic_blocks.push(BasicBlockData::new(Some(Terminator {
+                source_info,
+                kind: TerminatorKind::Goto { target: header },
+            })));
+            basic_blocks[preheader].terminator_mut().kind =
+                TerminatorKind::Assert { cond, expected, msg, target: next, unwind };
+            preheader = next;
+            new_blocks.push(next);
+        }
+
+        new_blocks
+    }
+
+    /// Finds the assignments of `natural_loop` that can be hoisted, in an order where each
+    /// definition comes before its uses.
+    fn find_invariant_statements(&self, body: &Body<'tcx>, candidates: &mut Candidates<'_>) {
+        for &block in body.basic_blocks.reverse_postorder() {
+            if !candidates.natural_loop.blocks.contains(block) {
+                continue;
+            }
+            for (statement_index, statement) in
+                body.basic_blocks[block].statements.iter().enumerate()
+            {
+                let StatementKind::Assign(box (place, ref rvalue)) = statement.kind else {
+                    continue;
+                };
+                let Some(local) = place.as_local() else { continue };
+                if self.ssa.is_ssa(local) && self.is_invariant_rvalue(body, candidates, rvalue) {
+                    candidates.hoisted.insert(local);
+                    candidates.statements.push(Location { block, statement_index });
+                }
+            }
+        }
+    }
+
+    /// Finds the invariant assertions that are always executed when the loop header is, with no
+    /// observable side effect in between.
+    fn find_invariant_asserts(&self, body: &Body<'tcx>, candidates: &mut Candidates<'_>) {
+        let natural_loop = candidates.natural_loop;
+        let mut visited = BitSet::new_empty(body.basic_blocks.len());
+        let mut block = natural_loop.header;
+        loop {
+            let data = &body.basic_blocks[block];
+            if !data.statements.iter().all(|statement| self.is_unobservable(statement)) {
+                return;
+            }
+
+            let next = match data.terminator().kind {
+                TerminatorKind::Goto { target } => target,
+                TerminatorKind::Assert { ref cond, ref msg, target, unwind, .. }
+                    if !matches!(unwind, UnwindAction::Cleanup(_))
+                        && self.is_invariant_assert_operand(body, candidates, cond)
+                        && self.is_invariant_assert_message(body, candidates, msg) =>
+                {
+                    candidates.asserts.push(block);
+                    target
+                }
+                _ => return,
+            };
+            if next == natural_loop.header
+                || !natural_loop.blocks.contains(next)
+                || !visited.insert(next)
+            {
+                return;
+            }
+            block = next;
+        }
+    }
+
+    /// Whether executing `statement` later than an assertion cannot be observed if the
+    /// assertion fails.
+    fn is_unobservable(&self, statement: &Statement<'tcx>) -> bool {
+        match statement.kind {
+            StatementKind::StorageLive(_) | StatementKind::StorageDead(_) | StatementKind::Nop => {
+                true
+            }
+            StatementKind::Assign(box (place, _)) => {
+                place.as_local().is_some_and(|local| !self.borrowed.contains(local))
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether `local` has the same value on every iteration of the loop, and that value is
+    /// available in the preheader.
+    fn is_invariant_local(&self, candidates: &Candidates<'_>, local: Local) -> bool {
+        if !self.ssa.is_ssa(local) {
+            return false;
+        }
+        // As the definition of an SSA local dominates its uses, a definition outside the loop
+        // that reaches a use inside of it dominates the loop header, and therefore the preheader.
+        candidates.hoisted.contains(local)
+            || self.def_blocks[local]
+                .map_or(true, |block| !candidates.natural_loop.blocks.contains(block))
+    }
+
+    fn is_invariant_place(
+        &self,
+        body: &Body<'tcx>,
+        candidates: &Candidates<'_>,
+        place: Place<'tcx>,
+    ) -> bool {
+        self.is_invariant_local(candidates, place.local)
+            && place.iter_projections().all(|(base, elem)| match elem {
+                // The pointee may be modified by the loop, or may not be dereferenceable on the
+                // paths that do not execute the loop body.
+                ProjectionElem::Deref => false,
+                // An unchecked index may be out of bounds, and the value of an inactive variant or
+                // union field may be invalid.
+                ProjectionElem::Index(_) | ProjectionElem::Downcast(..) => false,
+                ProjectionElem::Field(..) => !base.ty(body, self.tcx).ty.is_union(),
+                ProjectionElem::ConstantIndex { .. }
+                | ProjectionElem::Subslice { .. }
+                | ProjectionElem::OpaqueCast(_)
+                | ProjectionElem::Subtype(_) => true,
+            })
+    }
+
+    /// Hoisted assignments may run on paths that never reached them in the loop body, and those
+    /// paths may still use a place that the assignment moves out of, so moves are never invariant.
+    fn is_invariant_operand(
+        &self,
+        body: &Body<'tcx>,
+        candidates: &Candidates<'_>,
+        operand: &Operand<'tcx>,
+    ) -> bool {
+        match operand {
+            Operand::Constant(_) => true,
+            Operand::Copy(place) => self.is_invariant_place(body, candidates, *place),
+            Operand::Move(_) => false,
+        }
+    }
+
+    /// Unlike assignments, a hoisted assertion is only taken from code that runs on every
+    /// iteration, and its operands are booleans and integers, so they may also be moved.
+    fn is_invariant_assert_operand(
+        &self,
+        body: &Body<'tcx>,
+        candidates: &Candidates<'_>,
+        operand: &Operand<'tcx>,
+    ) -> bool {
+        match operand {
+            Operand::Move(place) => self.is_invariant_place(body, candidates, *place),
+            _ => self.is_invariant_operand(body, candidates, operand),
+        }
+    }
+
+    fn is_invariant_rvalue(
+        &self,
+        body: &Body<'tcx>,
+        candidates: &Candidates<'_>,
+        rvalue: &Rvalue<'tcx>,
+    ) -> bool {
+        match rvalue {
+            Rvalue::Use(operand) | Rvalue::Repeat(operand, _) | Rvalue::UnaryOp(_, operand) => {
+                self.is_invariant_operand(body, candidates, operand)
+            }
+            Rvalue::Cast(kind, operand, _) => {
+                !matches!(kind, CastKind::Transmute | CastKind::PointerExposeProvenance)
+                    && self.is_invariant_operand(body, candidates, operand)
+            }
+            Rvalue::BinaryOp(op, box (lhs, rhs)) => {
+                !matches!(
+                    op,
+                    BinOp::Div
+                        | BinOp::Rem
+                        | BinOp::Offset
+                        | BinOp::AddUnchecked
+                        | BinOp::SubUnchecked
+                        | BinOp::MulUnchecked
+                        | BinOp::ShlUnchecked
+                        | BinOp::ShrUnchecked
+                ) && self.is_invariant_operand(body, candidates, lhs)
+                    && self.is_invariant_operand(body, candidates, rhs)
+            }
+            Rvalue::Aggregate(_, operands) => {
+                operands.iter().all(|operand| self.is_invariant_operand(body, candidates, operand))
+            }
+            Rvalue::NullaryOp(..) => true,
+            // The length of a slice only depends on the metadata of the pointer to it.
+            Rvalue::Len(place) => match place.projection[..] {
+                [ProjectionElem::Deref] => self.is_invariant_local(candidates, place.local),
+                _ => self.is_invariant_place(body, candidates, *place),
+            },
+            Rvalue::Discriminant(place) => self.is_invariant_place(body, candidates, *place),
+            Rvalue::Ref(..)
+            | Rvalue::AddressOf(..)
+            | Rvalue::ThreadLocalRef(_)
+            | Rvalue::ShallowInitBox(..)
+            | Rvalue::CopyForDeref(_) => false,
+        }
+    }
+
+    fn is_invariant_assert_message(
+        &self,
+        body: &Body<'tcx>,
+        candidates: &Candidates<'_>,
+        msg: &AssertMessage<'tcx>,
+    ) -> bool {
+        let is_invariant =
+            |operand: &Operand<'tcx>| self.is_invariant_assert_operand(body, candidates, operand);
+        match msg {
+            AssertKind::BoundsCheck { len, index } => is_invariant(len) && is_invariant(index),
+            AssertKind::Overflow(_, lhs, rhs) => is_invariant(lhs) && is_invariant(rhs),
+            AssertKind::OverflowNeg(operand)
+            | AssertKind::DivisionByZero(operand)
+            | AssertKind::RemainderByZero(operand) => is_invariant(operand),
+            AssertKind::MisalignedPointerDereference { required, found } => {
+                is_invariant(required) && is_invariant(found)
+            }
+            AssertKind::ResumedAfterReturn(_) | AssertKind::ResumedAfterPanic(_) => true,
+        }
+    }
+
+    /// Returns a block that jumps to the loop header and is the only way to enter the loop,
+    /// creating it if needed.
+    fn preheader(&self, body: &mut Body<'tcx>, natural_loop: &NaturalLoop) -> Option<BasicBlock> {
+        let header = natural_loop.header;
+        let entries: Vec<BasicBlock> = body.basic_blocks.predecessors()[header]
+            .iter()
+            .copied()
+            .filter(|&pred| !natural_loop.blocks.contains(pred))
+            .collect();
+
+        let &first_entry = entries.first()?;
+        if let [pred] = entries[..]
+            && let TerminatorKind::Goto { .. } = body.basic_blocks[pred].terminator().kind
+        {
+            return Some(pred);
+        }
+
+        let source_info = body.basic_blocks[first_entry].terminator().source_info;
+        let basic_blocks = body.basic_blocks_mut();
+        let preheader = basic_blocks.push(BasicBlockData::new(Some(Terminator {
+            source_info,
+            kind: TerminatorKind::Goto { target: header },
+        })));
+        for pred in entries {
+            for target in basic_blocks[pred].terminator_mut().successors_mut() {
+                if *target == header {
+                    *target = preheader;
+                }
+            }
+        }
+        Some(preheader)
+    }
+}
+
+struct Candidates<'a> {
+    natural_loop: &'a NaturalLoop,
+    /// Locals whose assignment will be hoisted.
+    hoisted: BitSet<Local>,
+    /// Assignments to hoist.
+    statements: Vec<Location>,
+    /// Blocks whose `Assert` terminator is hoisted, in execution order.
+    asserts: Vec<BasicBlock>,
+}
+
+struct StorageRemover<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    hoisted_locals: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for StorageRemover<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, _: Location) {
+        if let Operand::Move(place) = *operand
+            && !place.is_indirect_first_projection()
+            && self.hoisted_locals.contains(place.local)
+        {
+            *operand = Operand::Copy(place);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement<'tcx>, loc: Location) {
+        match stmt.kind {
+            // When removing storage statements, we need to remove both (#107511).
+            StatementKind::StorageLive(l) | StatementKind::StorageDead(l)
+                if self.hoisted_locals.contains(l) =>
+            {
+                stmt.make_nop()
+            }
+            _ => self.super_statement(stmt, loc),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Natural loops of a MIR body.
+//!
+//! An edge `latch -> header` is a back edge when `header` dominates `latch`. The natural loop of
+//! such an edge is made of `header` and of all the blocks that can reach `latch` without going
+//! through `header`. Loops that share a header are merged, so each header has a single loop.
+//!
+//! Irreducible control flow, where a cycle can be entered through several blocks, has no back
+//! edge by this definition and is therefore not reported.
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_index::bit_set::GrowableBitSet;
+use rustc_middle::mir::*;
+
+#[derive(Debug)]
+pub struct NaturalLoop {
+    /// The only block of the loop that can be reached from outside of it.
+    pub header: BasicBlock,
+    /// All the blocks of the loop, including the header and those of the nested loops.
+    ///
+    /// This is growable so that passes which add blocks to a loop can keep it up to date.
+    pub blocks: GrowableBitSet<BasicBlock>,
+}
+
+/// Computes the natural loops of `body`. Nested loops are returned before the loops that
+/// contain them.
+pub fn natural_loops(body: &Body<'_>) -> Vec<NaturalLoop> {
+    let dominators = body.basic_blocks.dominators();
+    let predecessors = body.basic_blocks.predecessors();
+
+    let mut loops: FxIndexMap<BasicBlock, GrowableBitSet<BasicBlock>> = FxIndexMap::default();
+    for &latch in body.basic_blocks.reverse_postorder() {
+        for header in body.basic_blocks[latch].terminator().successors() {
+            if !dominators.dominates(header, latch) {
+                continue;
+            }
+
+            let blocks = loops.entry(header).or_insert_with(|| {
+                let mut blocks = GrowableBitSet::with_capacity(body.basic_blocks.len());
+                blocks.insert(header);
+                blocks
+            });
+            // Walk the predecessors backwards from the latch. As the header dominates the latch,
+            // every reachable block we visit is dominated by the header, and we stop there.
+            let mut stack = vec![latch];
+            while let Some(block) = stack.pop() {
+                if blocks.insert(block) {
+                    stack.extend(
+                        predecessors[block].iter().filter(|&&pred| dominators.is_reachable(pred)),
+                    );
+                }
+            }
+        }
+    }
+
+    let mut loops: Vec<_> =
+        loops.into_iter().map(|(header, blocks)| NaturalLoop { header, blocks }).collect();
+    // A nested loop is a strict subset of the loops that contain it.
+    loops.sort_by_key(|natural_loop| natural_loop.blocks.len());
+    loops
+}
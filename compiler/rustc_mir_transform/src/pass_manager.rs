@@ -119,7 +119,11 @@ fn run_passes_inner<'tcx>(
     let prof_arg = tcx.sess.prof.enabled().then(|| format!("{:?}", body.source.def_id()));
 
     if !body.should_skip() {
-        let validate = validate_each & tcx.sess.opts.unstable_opts.validate_mir;
+        // Custom pipelines may run passes in an order they were not designed for, so the MIR
+        // is validated after each pass.
+        let validate = validate_each
+            & (tcx.sess.opts.unstable_opts.validate_mir
+                || tcx.sess.opts.unstable_opts.mir_opt_pipeline.is_some());
         let lint = tcx.sess.opts.unstable_opts.lint_mir;
 
         for pass in passes {
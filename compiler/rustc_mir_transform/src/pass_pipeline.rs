@@ -0,0 +1,132 @@
+//! Support for `-Zmir-opt-pipeline`, which replaces the MIR optimization passes by a
+//! user-provided sequence of passes.
+
+use rustc_middle::mir::{Body, MirPass};
+use rustc_middle::ty::TyCtxt;
+use rustc_session::config::{MirOptPipeline, MirPassRequest};
+use rustc_session::Session;
+
+use crate::errors;
+use crate::inline::InlineWithThresholds;
+use crate::large_enums::EnumSizeOpt;
+
+/// A pass of a custom pipeline. Passes are run even when they are not enabled by the
+/// current MIR optimization level, since they were explicitly requested.
+pub(crate) enum PipelinePass<'a, 'tcx> {
+    /// One of the default optimization passes, used unchanged.
+    Default(&'a dyn MirPass<'tcx>),
+    /// A pass that was given options in the pipeline.
+    Configured(Box<dyn MirPass<'tcx>>),
+}
+
+impl<'a, 'tcx> PipelinePass<'a, 'tcx> {
+    fn pass(&self) -> &dyn MirPass<'tcx> {
+        match self {
+            PipelinePass::Default(pass) => *pass,
+            PipelinePass::Configured(pass) => &**pass,
+        }
+    }
+}
+
+impl<'a, 'tcx> MirPass<'tcx> for PipelinePass<'a, 'tcx> {
+    fn name(&self) -> &'static str {
+        self.pass().name()
+    }
+
+    fn profiler_name(&self) -> &'static str {
+        self.pass().profiler_name()
+    }
+
+    fn is_enabled(&self, _sess: &Session) -> bool {
+        true
+    }
+
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        self.pass().run_pass(tcx, body)
+    }
+
+    fn is_mir_dump_enabled(&self) -> bool {
+        self.pass().is_mir_dump_enabled()
+    }
+}
+
+/// Resolves the passes of `-Zmir-opt-pipeline` by name among the optimization passes that are
+/// run when no custom pipeline is given, and checks their options. Unknown passes and options are
+/// reported and skipped.
+pub(crate) fn mir_opt_pipeline(tcx: TyCtxt<'_>, (): ()) -> &[(usize, usize)] {
+    let Some(pipeline) = &tcx.sess.opts.unstable_opts.mir_opt_pipeline else {
+        return &[];
+    };
+    crate::with_optimization_passes(|default_passes| {
+        tcx.arena.alloc_from_iter(pipeline.passes.iter().enumerate().filter_map(
+            |(request_index, request)| {
+                let Some(pass_index) =
+                    default_passes.iter().position(|pass| pass.name() == request.name)
+                else {
+                    let mut available: Vec<_> =
+                        default_passes.iter().map(|pass| pass.name()).collect();
+                    available.sort_unstable();
+                    available.dedup();
+                    tcx.dcx().emit_err(errors::UnknownPipelinePass {
+                        name: &request.name,
+                        available: available.join(", "),
+                    });
+                    return None;
+                };
+
+                if !request.options.is_empty()
+                    && let Err(option) = configure_pass(request)
+                {
+                    tcx.dcx().emit_err(errors::UnknownPipelinePassOption {
+                        pass: &request.name,
+                        option,
+                    });
+                    return None;
+                }
+                Some((request_index, pass_index))
+            },
+        ))
+    })
+}
+
+/// Builds the passes of `pipeline` out of `default_passes`, the optimization passes that are run
+/// when no custom pipeline is given, as resolved by the `mir_opt_pipeline` query.
+pub(crate) fn build_pipeline<'a, 'tcx>(
+    tcx: TyCtxt<'tcx>,
+    pipeline: &MirOptPipeline,
+    default_passes: &[&'a dyn MirPass<'tcx>],
+) -> Vec<PipelinePass<'a, 'tcx>> {
+    tcx.mir_opt_pipeline(())
+        .iter()
+        .map(|&(request_index, pass_index)| {
+            let request = &pipeline.passes[request_index];
+            if request.options.is_empty() {
+                PipelinePass::Default(default_passes[pass_index])
+            } else {
+                // The options were checked by the `mir_opt_pipeline` query.
+                PipelinePass::Configured(configure_pass(request).unwrap())
+            }
+        })
+        .collect()
+}
+
+/// Builds the pass of `request`, which has options, or returns the first option that the pass
+/// doesn't support.
+fn configure_pass<'tcx>(request: &MirPassRequest) -> Result<Box<dyn MirPass<'tcx>>, &str> {
+    let mut inline = InlineWithThresholds { threshold: None, hint_threshold: None };
+    let mut enum_size_opt = EnumSizeOpt { discrepancy: 128 };
+    for (option, value) in &request.options {
+        match (&*request.name, &**option) {
+            ("Inline", "threshold") => inline.threshold = Some(*value),
+            ("Inline", "hint-threshold") => inline.hint_threshold = Some(*value),
+            ("EnumSizeOpt", "discrepancy") => enum_size_opt.discrepancy = *value as u64,
+            _ => return Err(option),
+        }
+    }
+
+    match &*request.name {
+        "Inline" => Ok(Box::new(inline)),
+        "EnumSizeOpt" => Ok(Box::new(enum_size_opt)),
+        _ => unreachable!("only passes with options are configured"),
+    }
+}
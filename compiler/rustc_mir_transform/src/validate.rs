@@ -1,5 +1,7 @@
 //! Validates the MIR to ensure that invariants are upheld.
 
+use std::cell::RefCell;
+
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_hir::LangItem;
 use rustc_index::bit_set::BitSet;
@@ -40,12 +42,30 @@ pub struct Validator {
 
 impl<'tcx> MirPass<'tcx> for Validator {
     fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        for (location, msg) in self.validate(tcx, body) {
+            // We might see broken MIR when other errors have already occurred.
+            assert!(
+                tcx.dcx().has_errors().is_some(),
+                "broken MIR in {:?} ({}) at {:?}:\n{}",
+                body.source.instance,
+                self.when,
+                location,
+                msg,
+            );
+        }
+    }
+}
+
+impl Validator {
+    /// Checks that `body` upholds the invariants of `self.mir_phase`, and returns the location
+    /// and description of each violation instead of reporting them.
+    pub fn validate<'tcx>(&self, tcx: TyCtxt<'tcx>, body: &Body<'tcx>) -> Vec<(Location, String)> {
         // FIXME(JakobDegen): These bodies never instantiated in codegend anyway, so it's not
         // terribly important that they pass the validator. However, I think other passes might
         // still see them, in which case they might be surprised. It would probably be better if we
         // didn't put this through the MIR pipeline at all.
         if matches!(body.source.instance, InstanceKind::Intrinsic(..) | InstanceKind::Virtual(..)) {
-            return;
+            return Vec::new();
         }
         let def_id = body.source.def_id();
         let mir_phase = self.mir_phase;
@@ -67,7 +87,7 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
                 ty::CoroutineClosure(..) => Abi::RustCall,
                 ty::Coroutine(..) => Abi::Rust,
                 // No need to do MIR validation on error bodies
-                ty::Error(_) => return,
+                ty::Error(_) => return Vec::new(),
                 _ => {
                     span_bug!(body.span, "unexpected body ty: {:?} phase {:?}", body_ty, mir_phase)
                 }
@@ -85,6 +105,7 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
             reachable_blocks: traversal::reachable_as_bitset(body),
             value_cache: FxHashSet::default(),
             can_unwind,
+            failures: RefCell::new(Vec::new()),
         };
         cfg_checker.visit_body(body);
         cfg_checker.check_cleanup_control_flow();
@@ -123,6 +144,8 @@ fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
                 );
             }
         }
+
+        cfg_checker.failures.into_inner()
     }
 }
 
@@ -137,20 +160,12 @@ struct CfgChecker<'a, 'tcx> {
     // If `false`, then the MIR must not contain `UnwindAction::Continue` or
     // `TerminatorKind::Resume`.
     can_unwind: bool,
+    failures: RefCell<Vec<(Location, String)>>,
 }
 
 impl<'a, 'tcx> CfgChecker<'a, 'tcx> {
-    #[track_caller]
     fn fail(&self, location: Location, msg: impl AsRef<str>) {
-        // We might see broken MIR when other errors have already occurred.
-        assert!(
-            self.tcx.dcx().has_errors().is_some(),
-            "broken MIR in {:?} ({}) at {:?}:\n{}",
-            self.body.source.instance,
-            self.when,
-            location,
-            msg.as_ref(),
-        );
+        self.failures.borrow_mut().push((location, msg.as_ref().to_owned()));
     }
 
     fn check_edge(&mut self, location: Location, bb: BasicBlock, edge_kind: EdgeKind) {
@@ -0,0 +1,218 @@
+//! Parsing of standalone fragments of Rust code, for tools.
+//!
+//! The rest of this crate parses whole files, and reports the errors it recovers from through the
+//! `DiagCtxt` of a compiler session. Tools like rustfmt or code indexers often want to reparse a
+//! single item or expression that was just edited, and inspect the errors rather than print them.
+//! [`parse_fragment`] does that: it parses a string with a detached diagnostics sink, and returns
+//! the AST along with the errors as data.
+
+use std::ops::Range;
+use std::panic;
+
+use rustc_ast::ptr::P;
+use rustc_ast::{self as ast, token};
+use rustc_data_structures::sync::{Lock, Lrc};
+use rustc_errors::emitter::Emitter;
+use rustc_errors::translation::{to_fluent_args, Translate};
+use rustc_errors::{
+    fallback_fluent_bundle, DiagCtxt, DiagInner, FatalErrorMarker, FluentBundle,
+    LazyFallbackBundle, Level, PResult,
+};
+use rustc_session::parse::ParseSess;
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+use rustc_span::{FileName, Pos, Span};
+
+use crate::parser::{CommaRecoveryMode, ForceCollect, Parser, RecoverColon, RecoverComma};
+
+/// The syntactic category of a fragment.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FragmentKind {
+    Item,
+    Expr,
+    Ty,
+    /// A pattern, which may have a top-level `|`, as in `match` arms.
+    Pat,
+}
+
+/// The AST of a fragment.
+#[derive(Debug)]
+pub enum Fragment {
+    Item(P<ast::Item>),
+    Expr(P<ast::Expr>),
+    Ty(P<ast::Ty>),
+    Pat(P<ast::Pat>),
+}
+
+/// A diagnostic emitted while parsing a fragment.
+#[derive(Clone, Debug)]
+pub struct FragmentDiagnostic {
+    pub level: Level,
+    pub message: String,
+    /// The byte range of the primary span of the diagnostic in the source of the fragment, if it
+    /// has one.
+    pub range: Option<Range<usize>>,
+    /// The messages of the notes and helps attached to the diagnostic.
+    pub children: Vec<String>,
+}
+
+impl FragmentDiagnostic {
+    pub fn is_error(&self) -> bool {
+        matches!(self.level, Level::Bug | Level::Fatal | Level::Error | Level::DelayedBug)
+    }
+}
+
+/// The result of [`parse_fragment`].
+#[derive(Debug)]
+pub struct ParsedFragment {
+    /// The AST of the fragment. The parser recovers from many errors, so this can be `Some` even
+    /// if `diagnostics` has errors, in which case it contains error nodes (like `ExprKind::Err`).
+    /// It is `None` when the parser could not recover.
+    pub fragment: Option<Fragment>,
+    /// The errors and warnings, in the order they were emitted.
+    pub diagnostics: Vec<FragmentDiagnostic>,
+    /// The source map holding the source of the fragment, which the spans of `fragment` point
+    /// into.
+    pub source_map: Lrc<SourceMap>,
+}
+
+impl ParsedFragment {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(FragmentDiagnostic::is_error)
+    }
+}
+
+/// A diagnostic translated by `CollectingEmitter`, with a span not yet made relative to the
+/// fragment.
+struct CollectedDiagnostic {
+    level: Level,
+    message: String,
+    span: Option<Span>,
+    children: Vec<String>,
+}
+
+/// An emitter that stores the diagnostics instead of printing them.
+struct CollectingEmitter {
+    fallback_bundle: LazyFallbackBundle,
+    diagnostics: Lrc<Lock<Vec<CollectedDiagnostic>>>,
+}
+
+impl Translate for CollectingEmitter {
+    fn fluent_bundle(&self) -> Option<&Lrc<FluentBundle>> {
+        None
+    }
+
+    fn fallback_fluent_bundle(&self) -> &FluentBundle {
+        &self.fallback_bundle
+    }
+}
+
+impl Emitter for CollectingEmitter {
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        None
+    }
+
+    fn emit_diagnostic(&mut self, diag: DiagInner) {
+        let args = to_fluent_args(diag.args.iter());
+        let message = self.translate_messages(&diag.messages, &args).into_owned();
+        let children = diag
+            .children
+            .iter()
+            .map(|child| self.translate_messages(&child.messages, &args).into_owned())
+            .collect();
+        self.diagnostics.lock().push(CollectedDiagnostic {
+            level: diag.level(),
+            message,
+            span: diag.span.primary_span(),
+            children,
+        });
+    }
+}
+
+/// Parses `source` as a fragment of the given kind, without needing a compiler session.
+///
+/// The whole of `source` must be the fragment: tokens left after it are reported as an error.
+/// Parsing uses the edition of the session globals, so this must be called within
+/// `rustc_span::create_session_globals_then`, and the returned AST must not outlive these globals.
+pub fn parse_fragment(kind: FragmentKind, source: String) -> ParsedFragment {
+    let source_map = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+    let diagnostics = Lrc::new(Lock::new(Vec::new()));
+    let emitter = CollectingEmitter {
+        fallback_bundle: fallback_fluent_bundle(
+            vec![crate::DEFAULT_LOCALE_RESOURCE, rustc_session::DEFAULT_LOCALE_RESOURCE],
+            false,
+        ),
+        diagnostics: diagnostics.clone(),
+    };
+    let psess = ParseSess::with_dcx(DiagCtxt::new(Box::new(emitter)), source_map.clone());
+
+    let source_file = source_map.new_source_file(FileName::Custom("fragment".into()), source);
+    let (start, end) = (source_file.start_pos, source_file.end_position());
+
+    // Some paths of the parser give up with a fatal error, which unwinds: the error has been
+    // emitted by then, so we can stop at that point like when the parser returns an error.
+    let fragment = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        let mut parser = match crate::new_parser_from_source_file(&psess, source_file) {
+            Ok(parser) => parser,
+            Err(errs) => {
+                for err in errs {
+                    err.emit();
+                }
+                return None;
+            }
+        };
+        match parse_fragment_with(&mut parser, kind) {
+            Ok(fragment) => Some(fragment),
+            Err(err) => {
+                err.emit();
+                None
+            }
+        }
+    }))
+    .unwrap_or_else(|payload| {
+        if !payload.is::<FatalErrorMarker>() {
+            panic::resume_unwind(payload);
+        }
+        None
+    });
+    psess.dcx().emit_stashed_diagnostics();
+    drop(psess);
+
+    let diagnostics = diagnostics
+        .lock()
+        .drain(..)
+        .map(|diag| FragmentDiagnostic {
+            level: diag.level,
+            message: diag.message,
+            range: diag
+                .span
+                .filter(|span| start <= span.lo() && span.hi() <= end)
+                .map(|span| (span.lo() - start).to_usize()..(span.hi() - start).to_usize()),
+            children: diag.children,
+        })
+        .collect();
+    ParsedFragment { fragment, diagnostics, source_map }
+}
+
+fn parse_fragment_with<'a>(parser: &mut Parser<'a>, kind: FragmentKind) -> PResult<'a, Fragment> {
+    let fragment = match kind {
+        FragmentKind::Item => match parser.parse_item(ForceCollect::Yes)? {
+            Some(item) => Fragment::Item(item),
+            None => return parser.unexpected_any(),
+        },
+        FragmentKind::Expr => Fragment::Expr(parser.parse_expr()?),
+        FragmentKind::Ty => Fragment::Ty(parser.parse_ty()?),
+        FragmentKind::Pat => Fragment::Pat(parser.parse_pat_allow_top_alt(
+            None,
+            RecoverComma::Yes,
+            RecoverColon::Yes,
+            CommaRecoveryMode::LikelyTuple,
+        )?),
+    };
+    if parser.token != token::Eof {
+        // The fragment itself is fine, so keep it and report the trailing tokens on the side.
+        if let Err(err) = parser.unexpected() {
+            err.emit();
+        }
+    }
+    Ok(fragment)
+}
@@ -29,6 +29,7 @@
 #[macro_use]
 pub mod parser;
 use parser::{make_unclosed_delims_error, Parser};
+pub mod fragment;
 pub mod lexer;
 pub mod validate_attr;
 
@@ -1,3 +1,4 @@
+use crate::fragment::{parse_fragment, Fragment, FragmentKind};
 use crate::parser::ForceCollect;
 use crate::{
     new_parser_from_source_str, parser::Parser, source_str_to_stream, unwrap_or_emit_fatal,
@@ -8,7 +9,7 @@
 use rustc_ast::tokenstream::{DelimSpacing, DelimSpan, Spacing, TokenStream, TokenTree};
 use rustc_ast::visit;
 use rustc_ast::{self as ast, PatKind};
-use rustc_ast_pretty::pprust::item_to_string;
+use rustc_ast_pretty::pprust::{self, item_to_string};
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::emitter::HumanEmitter;
 use rustc_errors::{DiagCtxt, MultiSpan, PResult};
@@ -1425,3 +1426,52 @@ fn non_pattern_whitespace() {
     assert_eq!(matches_codepattern("\u{205F}a   b", "ab"), false);
     assert_eq!(matches_codepattern("a  \u{3000}b", "ab"), false);
 }
+
+#[test]
+fn parse_fragment_without_errors() {
+    create_default_session_globals_then(|| {
+        let parsed = parse_fragment(FragmentKind::Expr, "a + b * 2".to_string());
+        assert!(parsed.diagnostics.is_empty(), "{:?}", parsed.diagnostics);
+        let Some(Fragment::Expr(expr)) = parsed.fragment else { panic!() };
+        assert_eq!(pprust::expr_to_string(&expr), "a + b * 2");
+
+        let parsed = parse_fragment(FragmentKind::Item, "fn f(x: u8) -> u8 { x }".to_string());
+        assert!(matches!(parsed.fragment, Some(Fragment::Item(_))));
+        assert!(!parsed.has_errors());
+
+        let parsed = parse_fragment(FragmentKind::Ty, "Vec<&'a [u8]>".to_string());
+        assert!(matches!(parsed.fragment, Some(Fragment::Ty(_))));
+        assert!(!parsed.has_errors());
+
+        let parsed = parse_fragment(FragmentKind::Pat, "Some(0) | None".to_string());
+        let Some(Fragment::Pat(pat)) = parsed.fragment else { panic!() };
+        assert!(matches!(pat.kind, PatKind::Or(_)));
+        assert!(!parsed.has_errors());
+    })
+}
+
+#[test]
+fn parse_fragment_reports_errors_as_data() {
+    create_default_session_globals_then(|| {
+        // The parser recovers from the ambiguous `<` and keeps the expression.
+        let parsed = parse_fragment(FragmentKind::Expr, "a as usize < b".to_string());
+        assert!(parsed.has_errors());
+        assert!(matches!(parsed.fragment, Some(Fragment::Expr(_))));
+        assert_eq!(parsed.diagnostics[0].range, Some(11..12));
+
+        // Trailing tokens are reported, but the fragment before them is kept.
+        let parsed = parse_fragment(FragmentKind::Ty, "u8 u16".to_string());
+        assert!(matches!(parsed.fragment, Some(Fragment::Ty(_))));
+        assert_eq!(parsed.diagnostics.len(), 1);
+        assert_eq!(parsed.diagnostics[0].range, Some(3..6));
+
+        // Unclosed delimiters are reported by the lexer, before any parsing.
+        let parsed = parse_fragment(FragmentKind::Item, "fn f() {".to_string());
+        assert!(parsed.fragment.is_none());
+        assert!(parsed.has_errors());
+
+        let parsed = parse_fragment(FragmentKind::Item, "".to_string());
+        assert!(parsed.fragment.is_none());
+        assert!(parsed.has_errors());
+    })
+}
@@ -305,6 +305,10 @@ passes_ffi_const_invalid_target =
 passes_ffi_pure_invalid_target =
     `#[ffi_pure]` may only be used on foreign functions
 
+passes_format_string_not_macro =
+    `#[format_string]` may only be used on declarative macros
+    .label = not a declarative macro
+
 passes_has_incoherent_inherent_impl =
     `rustc_has_incoherent_inherent_impls` attribute should be applied to types or traits
     .label = only adts, extern types and traits are supported
@@ -582,6 +586,10 @@ passes_pass_by_value =
 
 passes_proc_macro_bad_sig = {$kind} has incorrect signature
 
+passes_proc_macro_pure_not_proc_macro =
+    `#[proc_macro_pure]` may only be used on proc macros
+    .label = not a proc macro
+
 passes_remove_fields =
     consider removing { $num ->
       [one] this
@@ -240,6 +240,8 @@ fn check_attributes(
                     self.check_generic_attr(hir_id, attr, target, Target::Fn);
                     self.check_proc_macro(hir_id, target, ProcMacroKind::Derive)
                 }
+                sym::proc_macro_pure => self.check_proc_macro_pure(attr, span, target, attrs),
+                sym::format_string => self.check_format_string(attr, span, target),
                 _ => {}
             }
 
@@ -1295,6 +1297,26 @@ fn check_ffi_pure(&self, attr_span: Span, attrs: &[Attribute], target: Target) -
         }
     }
 
+    /// Checks that `#[proc_macro_pure]` is applied to a proc macro.
+    fn check_proc_macro_pure(
+        &self,
+        attr: &Attribute,
+        span: Span,
+        target: Target,
+        attrs: &[Attribute],
+    ) {
+        if target != Target::Fn || !attrs.iter().any(|attr| attr.is_proc_macro_attr()) {
+            self.dcx().emit_err(errors::ProcMacroPureNotProcMacro { attr_span: attr.span, span });
+        }
+    }
+
+    /// Checks that `#[format_string]` is applied to a declarative macro.
+    fn check_format_string(&self, attr: &Attribute, span: Span, target: Target) {
+        if target != Target::MacroDef {
+            self.dcx().emit_err(errors::FormatStringNotMacro { attr_span: attr.span, span });
+        }
+    }
+
     fn check_ffi_const(&self, attr_span: Span, target: Target) -> bool {
         if target == Target::ForeignFn {
             true
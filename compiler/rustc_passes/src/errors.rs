@@ -1602,6 +1602,24 @@ pub(crate) struct ProcMacroBadSig {
     pub kind: ProcMacroKind,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_format_string_not_macro)]
+pub(crate) struct FormatStringNotMacro {
+    #[primary_span]
+    pub attr_span: Span,
+    #[label]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_proc_macro_pure_not_proc_macro)]
+pub(crate) struct ProcMacroPureNotProcMacro {
+    #[primary_span]
+    pub attr_span: Span,
+    #[label]
+    pub span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(passes_skipping_const_checks)]
 pub struct SkippingConstChecks {
@@ -12,6 +12,7 @@
 pub(crate) mod lints;
 pub mod pat;
 pub mod pat_column;
+pub mod report;
 #[cfg(feature = "rustc")]
 pub mod rustc;
 pub mod usefulness;
@@ -157,3 +158,20 @@ pub fn analyze_match<'p, 'tcx>(
 
     Ok(report)
 }
+
+/// Like [`analyze_match`], but computes the more detailed [`report::MatchReport`] meant for tools,
+/// and doesn't run lints.
+#[cfg(feature = "rustc")]
+pub fn report_match<'p, 'tcx>(
+    tycx: &rustc::RustcPatCtxt<'p, 'tcx>,
+    arms: &[rustc::MatchArm<'p, 'tcx>],
+    scrut_ty: Ty<'tcx>,
+    pattern_complexity_limit: Option<usize>,
+) -> Result<rustc::MatchReport<'p, 'tcx>, ErrorGuaranteed> {
+    use report::compute_match_report;
+    use usefulness::PlaceValidity;
+
+    let scrut_ty = tycx.reveal_opaque_ty(scrut_ty);
+    let scrut_validity = PlaceValidity::from_bool(tycx.known_valid_scrutinee);
+    compute_match_report(tycx, arms, scrut_ty, scrut_validity, pattern_complexity_limit)
+}
@@ -0,0 +1,120 @@
+//! A summary of the analysis of a match, meant for tools rather than diagnostics.
+//!
+//! [`compute_match_usefulness`] reports what the compiler needs to emit its errors and lints. A
+//! [`MatchReport`] additionally distinguishes the arms that are only reachable because an arm above
+//! them has a guard, and lists for each arm the arms above it that overlap with it. Tools that
+//! want to check that a match is "tight", i.e. that no arm is dead and that guards are not hiding
+//! a redundant arm, can use [`compute_match_report`] as their entry point.
+
+use crate::pat::{DeconstructedPat, WitnessPat};
+use crate::usefulness::{compute_match_usefulness, PlaceValidity, Usefulness};
+use crate::{MatchArm, PatCx};
+
+/// Whether an arm can be reached.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArmReachability {
+    /// The arm matches values that no arm above it matches.
+    Reachable,
+    /// The arm matches values that no arm above it matches, but only because some of these arms
+    /// have guards: if all the guards above it were `true`, the arm would be redundant.
+    OnlyReachableViaGuards,
+    /// The arm is redundant and can be removed without changing the behavior of the match.
+    Redundant,
+}
+
+impl ArmReachability {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ArmReachability::Reachable => "reachable",
+            ArmReachability::OnlyReachableViaGuards => "only_reachable_via_guards",
+            ArmReachability::Redundant => "redundant",
+        }
+    }
+}
+
+/// What we know about an arm of the match.
+#[derive(Debug)]
+pub struct ArmReport<'p, Cx: PatCx> {
+    pub arm: MatchArm<'p, Cx>,
+    pub reachability: ArmReachability,
+    /// The or-pattern alternatives of a reachable arm that are redundant.
+    pub redundant_subpatterns: Vec<&'p DeconstructedPat<Cx>>,
+    /// The indices of the arms above this one that match some of the same values. This may miss
+    /// real intersections.
+    pub intersects_with: Vec<usize>,
+}
+
+/// The result of [`compute_match_report`].
+#[derive(Debug)]
+pub struct MatchReport<'p, Cx: PatCx> {
+    /// One entry per arm of the input, in order.
+    pub arms: Vec<ArmReport<'p, Cx>>,
+    /// If the match is exhaustive, this is empty. If not, this contains witnesses for the lack of
+    /// exhaustiveness.
+    pub missing_witnesses: Vec<WitnessPat<Cx>>,
+}
+
+impl<'p, Cx: PatCx> MatchReport<'p, Cx> {
+    pub fn is_exhaustive(&self) -> bool {
+        self.missing_witnesses.is_empty()
+    }
+}
+
+/// Computes whether a match is exhaustive, and the reachability of each of its arms.
+///
+/// This runs the usefulness algorithm a second time with all the guards removed to detect the arms
+/// that are only reachable via guards, so it is twice as expensive as [`compute_match_usefulness`].
+pub fn compute_match_report<'p, Cx: PatCx>(
+    tycx: &Cx,
+    arms: &[MatchArm<'p, Cx>],
+    scrut_ty: Cx::Ty,
+    scrut_validity: PlaceValidity,
+    complexity_limit: Option<usize>,
+) -> Result<MatchReport<'p, Cx>, Cx::Error> {
+    let report =
+        compute_match_usefulness(tycx, arms, scrut_ty.clone(), scrut_validity, complexity_limit)?;
+
+    let unguarded_usefulness = if arms.iter().any(|arm| arm.has_guard) {
+        let unguarded_arms: Vec<_> =
+            arms.iter().map(|&arm| MatchArm { has_guard: false, ..arm }).collect();
+        let unguarded = compute_match_usefulness(
+            tycx,
+            &unguarded_arms,
+            scrut_ty,
+            scrut_validity,
+            complexity_limit,
+        )?;
+        Some(unguarded.arm_usefulness)
+    } else {
+        None
+    };
+
+    let arms = report
+        .arm_usefulness
+        .into_iter()
+        .zip(report.arm_intersections)
+        .enumerate()
+        .map(|(i, ((arm, usefulness), intersections))| {
+            let redundant_without_guards = unguarded_usefulness
+                .as_ref()
+                .is_some_and(|unguarded| matches!(unguarded[i].1, Usefulness::Redundant));
+            let (reachability, redundant_subpatterns) = match usefulness {
+                Usefulness::Redundant => (ArmReachability::Redundant, Vec::new()),
+                Usefulness::Useful(redundant_subpats) if redundant_without_guards => {
+                    (ArmReachability::OnlyReachableViaGuards, redundant_subpats)
+                }
+                Usefulness::Useful(redundant_subpats) => {
+                    (ArmReachability::Reachable, redundant_subpats)
+                }
+            };
+            ArmReport {
+                arm,
+                reachability,
+                redundant_subpatterns,
+                intersects_with: intersections.iter().collect(),
+            }
+        })
+        .collect();
+
+    Ok(MatchReport { arms, missing_witnesses: report.non_exhaustiveness_witnesses })
+}
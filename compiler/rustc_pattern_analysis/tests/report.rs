@@ -0,0 +1,94 @@
+//! Test the match reports meant for tools.
+
+use common::*;
+use rustc_pattern_analysis::{
+    pat::DeconstructedPat,
+    report::{compute_match_report, ArmReachability},
+    usefulness::PlaceValidity,
+    MatchArm,
+};
+
+#[macro_use]
+mod common;
+
+/// Analyze a match made of these patterns, where the arms at the indices in `guarded` have guards,
+/// and returns the reachability of each arm and whether the match is exhaustive.
+fn check(patterns: Vec<DeconstructedPat<Cx>>, guarded: &[usize]) -> (Vec<ArmReachability>, bool) {
+    init_tracing();
+    let ty = *patterns[0].ty();
+    let arms: Vec<_> = patterns
+        .iter()
+        .enumerate()
+        .map(|(i, pat)| MatchArm { pat, has_guard: guarded.contains(&i), arm_data: () })
+        .collect();
+    let report =
+        compute_match_report(&Cx, arms.as_slice(), ty, PlaceValidity::ValidOnly, None).unwrap();
+    let is_exhaustive = report.is_exhaustive();
+    (report.arms.into_iter().map(|arm| arm.reachability).collect(), is_exhaustive)
+}
+
+#[test]
+fn test_without_guards() {
+    use ArmReachability::*;
+    let ty = Ty::Bool;
+    assert_eq!(
+        check(
+            pats!(ty;
+                true,
+                false,
+                _,
+            ),
+            &[],
+        ),
+        (vec![Reachable, Reachable, Redundant], true),
+    );
+    assert_eq!(
+        check(
+            pats!(ty;
+                true,
+            ),
+            &[],
+        ),
+        (vec![Reachable], false),
+    );
+}
+
+#[test]
+fn test_guards() {
+    use ArmReachability::*;
+    let ty = Ty::Bool;
+    assert_eq!(
+        check(
+            pats!(ty;
+                true,
+                false,
+                true,
+            ),
+            &[0],
+        ),
+        (vec![Reachable, Reachable, OnlyReachableViaGuards], true),
+    );
+    assert_eq!(
+        check(
+            pats!(ty;
+                _,
+                true,
+                false,
+            ),
+            &[0],
+        ),
+        (vec![Reachable, OnlyReachableViaGuards, OnlyReachableViaGuards], true),
+    );
+    // A guard doesn't make the arm below it reachable if the arm is redundant anyway.
+    assert_eq!(
+        check(
+            pats!(ty;
+                true,
+                _,
+                false,
+            ),
+            &[0],
+        ),
+        (vec![Reachable, Reachable, Redundant], true),
+    );
+}
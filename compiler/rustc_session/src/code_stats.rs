@@ -3,7 +3,10 @@
 use rustc_span::def_id::DefId;
 use rustc_span::Symbol;
 use rustc_target::abi::{Align, Size};
+use serde_json::json;
 use std::cmp;
+use std::ops::AddAssign;
+use std::time::Duration;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -59,6 +62,52 @@ pub enum DataTypeKind {
     Coroutine,
 }
 
+impl DataTypeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            DataTypeKind::Struct => "struct",
+            DataTypeKind::Union => "union",
+            DataTypeKind::Enum => "enum",
+            DataTypeKind::Closure => "closure",
+            DataTypeKind::Coroutine => "coroutine",
+        }
+    }
+}
+
+/// The largest niche of a type, i.e. the invalid values of a scalar inside
+/// of it that an enclosing enum can use to store its tag.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct NicheInfo {
+    pub offset: u64,
+    pub size: u64,
+    pub valid_range_start: u128,
+    pub valid_range_end: u128,
+    /// Number of invalid values available for use as a niche.
+    pub available: u128,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum TagEncodingInfo {
+    /// The tag directly stores the discriminant.
+    Direct,
+    /// The tag is stored in the niche of the untagged variant. Variants in
+    /// `niche_variants_start..=niche_variants_end` are encoded starting at
+    /// the value `niche_start`.
+    Niche {
+        untagged_variant: u32,
+        niche_variants_start: u32,
+        niche_variants_end: u32,
+        niche_start: u128,
+    },
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct TagInfo {
+    pub offset: u64,
+    pub size: u64,
+    pub encoding: TagEncodingInfo,
+}
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct TypeSizeInfo {
     pub kind: DataTypeKind,
@@ -67,6 +116,10 @@ pub struct TypeSizeInfo {
     pub overall_size: u64,
     pub packed: bool,
     pub opt_discr_size: Option<u64>,
+    pub niche: Option<NicheInfo>,
+    pub tag: Option<TagInfo>,
+    /// Variants in declaration order, except for coroutines, whose variants
+    /// are sorted according to their yield points.
     pub variants: Vec<VariantInfo>,
 }
 
@@ -89,10 +142,28 @@ pub struct VTableSizeInfo {
     pub upcasting_cost_percent: f64,
 }
 
+/// The cost of evaluating some code at compile-time.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct ConstEvalCost {
+    /// The steps counted towards the `long_running_const_eval` lint.
+    pub steps: u64,
+    pub time: Duration,
+}
+
+impl AddAssign for ConstEvalCost {
+    fn add_assign(&mut self, other: ConstEvalCost) {
+        self.steps += other.steps;
+        self.time += other.time;
+    }
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
     vtable_sizes: Lock<FxHashMap<DefId, VTableSizeInfo>>,
+    /// The cost of compile-time evaluation spent in each call stack, excluding its callees. The
+    /// first element of a stack is the constant being evaluated, the others are functions.
+    const_eval_costs: Lock<FxHashMap<Vec<String>, ConstEvalCost>>,
 }
 
 impl CodeStats {
@@ -104,16 +175,10 @@ pub fn record_type_size<S: ToString>(
         overall_size: Size,
         packed: bool,
         opt_discr_size: Option<Size>,
-        mut variants: Vec<VariantInfo>,
+        niche: Option<NicheInfo>,
+        tag: Option<TagInfo>,
+        variants: Vec<VariantInfo>,
     ) {
-        // Sort variants so the largest ones are shown first. A stable sort is
-        // used here so that source code order is preserved for all variants
-        // that have the same size.
-        // Except for Coroutines, whose variants are already sorted according to
-        // their yield points in `variant_info_for_coroutine`.
-        if kind != DataTypeKind::Coroutine {
-            variants.sort_by_key(|info| cmp::Reverse(info.size));
-        }
         let info = TypeSizeInfo {
             kind,
             type_description: type_desc.to_string(),
@@ -121,6 +186,8 @@ pub fn record_type_size<S: ToString>(
             overall_size: overall_size.bytes(),
             packed,
             opt_discr_size: opt_discr_size.map(|s| s.bytes()),
+            niche,
+            tag,
             variants,
         };
         self.type_sizes.borrow_mut().insert(info);
@@ -136,15 +203,7 @@ pub fn record_vtable_size(&self, trait_did: DefId, trait_name: &str, info: VTabl
 
     pub fn print_type_sizes(&self) {
         let type_sizes = self.type_sizes.borrow();
-        // We will soon sort, so the initial order does not matter.
-        #[allow(rustc::potential_query_instability)]
-        let mut sorted: Vec<_> = type_sizes.iter().collect();
-
-        // Primary sort: large-to-small.
-        // Secondary sort: description (dictionary order)
-        sorted.sort_by_key(|info| (cmp::Reverse(info.overall_size), &info.type_description));
-
-        for info in sorted {
+        for info in sorted_type_sizes(&type_sizes) {
             let TypeSizeInfo { type_description, overall_size, align, kind, variants, .. } = info;
             println!(
                 "print-type-size type: `{type_description}`: {overall_size} bytes, alignment: {align} bytes"
@@ -168,6 +227,16 @@ pub fn print_type_sizes(&self) {
                 DataTypeKind::Struct | DataTypeKind::Closure => true,
                 DataTypeKind::Enum | DataTypeKind::Union | DataTypeKind::Coroutine => false,
             };
+
+            // Sort variants so the largest ones are shown first. A stable sort is
+            // used here so that source code order is preserved for all variants
+            // that have the same size.
+            // Except for Coroutines, whose variants are already sorted according to
+            // their yield points in `variant_info_for_coroutine`.
+            let mut variants: Vec<_> = variants.iter().collect();
+            if *kind != DataTypeKind::Coroutine {
+                variants.sort_by_key(|info| cmp::Reverse(info.size));
+            }
             for (i, variant_info) in variants.into_iter().enumerate() {
                 let VariantInfo { ref name, kind: _, align: _, size, ref fields } = *variant_info;
                 let indent = if !struct_like {
@@ -238,6 +307,171 @@ pub fn print_type_sizes(&self) {
         }
     }
 
+    /// Renders the recorded layouts as a JSON document, for `--print=type-layouts`.
+    ///
+    /// Scalar values (valid ranges, niche starts and the number of available
+    /// niche values) are emitted as strings, since they may exceed what JSON
+    /// consumers can represent as numbers.
+    pub fn type_layouts_json(&self) -> String {
+        let type_sizes = self.type_sizes.borrow();
+        let types: Vec<_> = sorted_type_sizes(&type_sizes)
+            .into_iter()
+            .map(|info| {
+                let niche = info.niche.map(|niche| {
+                    json!({
+                        "offset": niche.offset,
+                        "size": niche.size,
+                        "valid_range": {
+                            "start": niche.valid_range_start.to_string(),
+                            "end": niche.valid_range_end.to_string(),
+                        },
+                        "available": niche.available.to_string(),
+                    })
+                });
+                let tag = info.tag.map(|tag| {
+                    let encoding = match tag.encoding {
+                        TagEncodingInfo::Direct => json!({ "kind": "direct" }),
+                        TagEncodingInfo::Niche {
+                            untagged_variant,
+                            niche_variants_start,
+                            niche_variants_end,
+                            niche_start,
+                        } => json!({
+                            "kind": "niche",
+                            "untagged_variant": untagged_variant,
+                            "niche_variants": [niche_variants_start, niche_variants_end],
+                            "niche_start": niche_start.to_string(),
+                        }),
+                    };
+                    json!({ "offset": tag.offset, "size": tag.size, "encoding": encoding })
+                });
+                let variants: Vec<_> = info
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let fields: Vec<_> = variant
+                            .fields
+                            .iter()
+                            .map(|field| {
+                                json!({
+                                    "kind": field.kind.to_string(),
+                                    "name": field.name.as_str(),
+                                    "offset": field.offset,
+                                    "size": field.size,
+                                    "align": field.align,
+                                    "type": field.type_name.map(|name| name.to_string()),
+                                })
+                            })
+                            .collect();
+                        json!({
+                            "name": variant.name.map(|name| name.to_string()),
+                            "sized": variant.kind == SizeKind::Exact,
+                            "size": variant.size,
+                            "align": variant.align,
+                            "fields": fields,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "type": info.type_description,
+                    "kind": info.kind.as_str(),
+                    "size": info.overall_size,
+                    "align": info.align,
+                    "packed": info.packed,
+                    "niche": niche,
+                    "tag": tag,
+                    "variants": variants,
+                })
+            })
+            .collect();
+
+        let mut json = serde_json::to_string_pretty(&json!({ "types": types })).unwrap();
+        json.push('\n');
+        json
+    }
+
+    pub fn record_const_eval_costs(
+        &self,
+        costs: impl IntoIterator<Item = (Vec<String>, ConstEvalCost)>,
+    ) {
+        let mut const_eval_costs = self.const_eval_costs.lock();
+        for (stack, cost) in costs {
+            *const_eval_costs.entry(stack).or_default() += cost;
+        }
+    }
+
+    /// Prints the constants and the functions with the largest compile-time evaluation cost.
+    pub fn print_const_eval_profile(&self) {
+        const TOP: usize = 10;
+
+        let const_eval_costs = self.const_eval_costs.lock();
+        let mut items: FxHashMap<&str, ConstEvalCost> = FxHashMap::default();
+        // The self cost and the total cost, including callees, of each function.
+        let mut functions: FxHashMap<&str, (ConstEvalCost, ConstEvalCost)> = FxHashMap::default();
+        #[allow(rustc::potential_query_instability)]
+        for (stack, &cost) in const_eval_costs.iter() {
+            *items.entry(&stack[0]).or_default() += cost;
+            if let [_, .., callee] = &stack[..] {
+                functions.entry(callee).or_default().0 += cost;
+            }
+            // Only count the cost once for recursive functions.
+            let mut seen = FxHashSet::default();
+            for function in &stack[1..] {
+                if seen.insert(function) {
+                    functions.entry(function).or_default().1 += cost;
+                }
+            }
+        }
+
+        // We will soon sort, so the initial order does not matter.
+        #[allow(rustc::potential_query_instability)]
+        let mut items: Vec<_> = items.into_iter().collect();
+        items.sort_by_key(|&(name, cost)| (cmp::Reverse(cost.steps), name));
+        for (name, cost) in items.into_iter().take(TOP) {
+            println!(
+                "print-const-eval-profile item `{name}`: {} steps, {:.3}ms",
+                cost.steps,
+                cost.time.as_secs_f64() * 1000.0,
+            );
+        }
+
+        #[allow(rustc::potential_query_instability)]
+        let mut functions: Vec<_> = functions.into_iter().collect();
+        functions.sort_by_key(|&(name, (self_cost, total_cost))| {
+            (cmp::Reverse(self_cost.steps), cmp::Reverse(total_cost.steps), name)
+        });
+        for (name, (self_cost, total_cost)) in functions.into_iter().take(TOP) {
+            println!(
+                "print-const-eval-profile fn `{name}`: \
+                {} steps ({} total), {:.3}ms ({:.3}ms total)",
+                self_cost.steps,
+                total_cost.steps,
+                self_cost.time.as_secs_f64() * 1000.0,
+                total_cost.time.as_secs_f64() * 1000.0,
+            );
+        }
+    }
+
+    /// Renders the compile-time evaluation steps of each call stack in the folded format used
+    /// by flamegraph tools: one line per stack, with its frames separated by `;`.
+    pub fn const_eval_profile_folded(&self) -> String {
+        // We will soon sort, so the initial order does not matter.
+        #[allow(rustc::potential_query_instability)]
+        let mut lines: Vec<_> = self
+            .const_eval_costs
+            .lock()
+            .iter()
+            .filter(|(_, cost)| cost.steps > 0)
+            .map(|(stack, cost)| {
+                // `;` separates frames, so it must not appear in them, e.g. in array types.
+                let frames: Vec<_> = stack.iter().map(|frame| frame.replace(';', ",")).collect();
+                format!("{} {}\n", frames.join(";"), cost.steps)
+            })
+            .collect();
+        lines.sort();
+        lines.concat()
+    }
+
     pub fn print_vtable_sizes(&self, crate_name: Symbol) {
         // We will soon sort, so the initial order does not matter.
         #[allow(rustc::potential_query_instability)]
@@ -267,3 +501,14 @@ pub fn print_vtable_sizes(&self, crate_name: Symbol) {
         }
     }
 }
+
+fn sorted_type_sizes(type_sizes: &FxHashSet<TypeSizeInfo>) -> Vec<&TypeSizeInfo> {
+    // We will soon sort, so the initial order does not matter.
+    #[allow(rustc::potential_query_instability)]
+    let mut sorted: Vec<_> = type_sizes.iter().collect();
+
+    // Primary sort: large-to-small.
+    // Secondary sort: description (dictionary order)
+    sorted.sort_by_key(|info| (cmp::Reverse(info.overall_size), &info.type_description));
+    sorted
+}
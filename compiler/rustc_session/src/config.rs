@@ -813,6 +813,8 @@ pub enum PrintKind {
     LinkArgs,
     SplitDebuginfo,
     DeploymentTarget,
+    TypeLayouts,
+    ExportedAbi,
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -1903,6 +1905,7 @@ fn collect_print_requests(
         ("code-models", PrintKind::CodeModels),
         ("crate-name", PrintKind::CrateName),
         ("deployment-target", PrintKind::DeploymentTarget),
+        ("exported-abi", PrintKind::ExportedAbi),
         ("file-names", PrintKind::FileNames),
         ("link-args", PrintKind::LinkArgs),
         ("native-static-libs", PrintKind::NativeStaticLibs),
@@ -1916,6 +1919,7 @@ fn collect_print_requests(
         ("target-list", PrintKind::TargetList),
         ("target-spec-json", PrintKind::TargetSpec),
         ("tls-models", PrintKind::TlsModels),
+        ("type-layouts", PrintKind::TypeLayouts),
         // tidy-alphabetical-end
     ];
 
@@ -1959,6 +1963,26 @@ fn collect_print_requests(
                     );
                 }
             }
+            Some((_, PrintKind::ExportedAbi)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::ExportedAbi
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the exported-abi print option",
+                    );
+                }
+            }
+            Some((_, PrintKind::TypeLayouts)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::TypeLayouts
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the type-layouts print option",
+                    );
+                }
+            }
             Some(&(_, print_kind)) => print_kind,
             None => {
                 let prints =
@@ -2404,6 +2428,16 @@ pub fn build_session_options(early_dcx: &mut EarlyDiagCtxt, matches: &getopts::M
         .unwrap_or_else(|e| early_dcx.early_fatal(e));
 
     let mut unstable_opts = UnstableOptions::build(early_dcx, matches);
+    if let Some(pipeline) = &mut unstable_opts.mir_opt_pipeline {
+        // The pipeline is read eagerly so that its contents, and not just its path, are tracked
+        // for incremental compilation.
+        if let Err(error) = pipeline.read() {
+            early_dcx.early_fatal(format!(
+                "invalid MIR pass pipeline `{}`: {error}",
+                pipeline.path.display()
+            ));
+        }
+    }
     let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(early_dcx, matches);
 
     check_error_format_stability(early_dcx, &unstable_opts, error_format);
@@ -2964,8 +2998,8 @@ pub(crate) mod dep_tracking {
         BranchProtection, CFGuard, CFProtection, CollapseMacroDebuginfo, CoverageOptions,
         CrateType, DebugInfo, DebugInfoCompression, ErrorOutputType, FunctionReturn,
         InliningThreshold, InstrumentCoverage, InstrumentXRay, LinkerPluginLto, LocationDetail,
-        LtoCli, NextSolverConfig, OomStrategy, OptLevel, OutFileName, OutputType, OutputTypes,
-        Polonius, RemapPathScopeComponents, ResolveDocLinks, SourceFileHashAlgorithm,
+        LtoCli, MirOptPipeline, NextSolverConfig, OomStrategy, OptLevel, OutFileName, OutputType,
+        OutputTypes, Polonius, RemapPathScopeComponents, ResolveDocLinks, SourceFileHashAlgorithm,
         SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion, WasiExecModel,
     };
     use crate::lint;
@@ -3024,6 +3058,7 @@ fn hash(
     impl_dep_tracking_hash_via_hash!(
         bool,
         usize,
+        MirOptPipeline,
         NonZero<usize>,
         u64,
         Hash64,
@@ -3250,6 +3285,24 @@ pub fn extension(self) -> &'static str {
     }
 }
 
+/// Which format to use for `-Z dump-mir-region-graph`
+#[derive(Clone, Copy, PartialEq, Hash, Debug)]
+pub enum RegionGraphFormat {
+    /// A graphviz `.dot` file
+    Graphviz,
+    /// A standalone HTML page, with the constraint graph rendered as an interactive table
+    Html,
+}
+
+impl RegionGraphFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Graphviz => "dot",
+            Self::Html => "html",
+        }
+    }
+}
+
 /// `-Zpolonius` values, enabling the borrow checker polonius analysis, and which version: legacy,
 /// or future prototype.
 #[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
@@ -3261,7 +3314,7 @@ pub enum Polonius {
     /// Legacy version, using datalog and the `polonius-engine` crate. Historical value for `-Zpolonius`.
     Legacy,
 
-    /// In-tree prototype, extending the NLL infrastructure.
+    /// In-tree location-sensitive analysis, extending the NLL infrastructure.
     Next,
 }
 
@@ -3277,6 +3330,76 @@ pub fn is_next_enabled(&self) -> bool {
     }
 }
 
+/// A MIR optimization pipeline, as described by the file passed to `-Zmir-opt-pipeline`.
+///
+/// The file lists one pass per line, by the name used in `-Zmir-enable-passes`, optionally
+/// followed by `key=value` options. The `repeat=N` option runs the pass `N` times in a row;
+/// other options are specific to the pass. Empty lines and lines starting with `#` are ignored:
+///
+/// ```text
+/// # Inline aggressively, then clean up.
+/// Inline threshold=200 hint-threshold=400
+/// GVN repeat=2
+/// SimplifyCfg-final
+/// ```
+#[derive(Clone, PartialEq, Hash, Debug, Default)]
+pub struct MirOptPipeline {
+    pub path: PathBuf,
+    pub passes: Vec<MirPassRequest>,
+}
+
+/// A single pass of a [`MirOptPipeline`], with the options given to it.
+#[derive(Clone, PartialEq, Hash, Debug)]
+pub struct MirPassRequest {
+    pub name: String,
+    pub options: Vec<(String, usize)>,
+}
+
+impl MirOptPipeline {
+    /// Reads the passes of the pipeline from its file.
+    pub fn read(&mut self) -> Result<(), String> {
+        let source = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        self.passes = Self::parse(&source)?;
+        Ok(())
+    }
+
+    fn parse(source: &str) -> Result<Vec<MirPassRequest>, String> {
+        let mut passes = Vec::new();
+        for (line_index, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut words = line.split_whitespace();
+            let name = words.next().unwrap().to_string();
+            let mut repeat = 1;
+            let mut options = Vec::new();
+            for option in words {
+                let parsed = option.split_once('=').and_then(|(key, value)| {
+                    Some((key.to_string(), value.parse::<usize>().ok()?))
+                });
+                let Some((key, value)) = parsed else {
+                    return Err(format!(
+                        "line {}: expected an option of the form `key=number`, found `{option}`",
+                        line_index + 1
+                    ));
+                };
+                if key == "repeat" {
+                    repeat = value;
+                } else {
+                    options.push((key, value));
+                }
+            }
+
+            for _ in 0..repeat {
+                passes.push(MirPassRequest { name: name.clone(), options: options.clone() });
+            }
+        }
+        Ok(passes)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Hash, Debug)]
 pub enum InliningThreshold {
     Always,
@@ -394,6 +394,7 @@ mod desc {
     pub const parse_linker_flavor: &str = ::rustc_target::spec::LinkerFlavorCli::one_of();
     pub const parse_optimization_fuel: &str = "crate=integer";
     pub const parse_dump_mono_stats: &str = "`markdown` (default) or `json`";
+    pub const parse_region_graph_format: &str = "`dot` or `html`";
     pub const parse_instrument_coverage: &str = parse_bool;
     pub const parse_coverage_options: &str =
         "`block` | `branch` | `condition` | `mcdc` | `no-mir-spans`";
@@ -442,6 +443,8 @@ mod desc {
         "either a boolean (`yes`, `no`, `on`, `off`, etc), or a non-negative number";
     pub const parse_llvm_module_flag: &str = "<key>:<type>:<value>:<behavior>. Type must currently be `u32`. Behavior should be one of (`error`, `warning`, `require`, `override`, `append`, `appendunique`, `max`, `min`)";
     pub const parse_function_return: &str = "`keep` or `thunk-extern`";
+    pub const parse_mir_opt_pipeline: &str =
+        "a path to a readable file describing a MIR pass pipeline";
     pub const parse_wasm_c_abi: &str = "`legacy` or `spec`";
 }
 
@@ -925,6 +928,23 @@ pub(crate) fn parse_time_passes_format(slot: &mut TimePassesFormat, v: Option<&s
         }
     }
 
+    pub(crate) fn parse_region_graph_format(
+        slot: &mut Option<RegionGraphFormat>,
+        v: Option<&str>,
+    ) -> bool {
+        match v {
+            Some("dot") => {
+                *slot = Some(RegionGraphFormat::Graphviz);
+                true
+            }
+            Some("html") => {
+                *slot = Some(RegionGraphFormat::Html);
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub(crate) fn parse_dump_mono_stats(slot: &mut DumpMonoStatsFormat, v: Option<&str>) -> bool {
         match v {
             None => true,
@@ -1392,6 +1412,16 @@ pub(crate) fn parse_proc_macro_execution_strategy(
         true
     }
 
+    pub(crate) fn parse_mir_opt_pipeline(
+        slot: &mut Option<MirOptPipeline>,
+        v: Option<&str>,
+    ) -> bool {
+        // The file is read by `build_session_options`, which can report why it's invalid.
+        let Some(path) = v else { return false };
+        *slot = Some(MirOptPipeline { path: PathBuf::from(path), passes: Vec::new() });
+        true
+    }
+
     pub(crate) fn parse_inlining_threshold(slot: &mut InliningThreshold, v: Option<&str>) -> bool {
         match v {
             Some("always" | "yes") => {
@@ -1599,6 +1629,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
     // - src/doc/unstable-book/src/compiler-flags
 
     // tidy-alphabetical-start
+    abi_baseline: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "compare the exported ABI of the crate against a file produced by \
+        `--print=exported-abi` and report changes that break binary compatibility"),
     allow_features: Option<Vec<String>> = (None, parse_opt_comma_list, [TRACKED],
         "only allow the listed language features to be enabled in code (comma separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
@@ -1620,12 +1653,18 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "set options for branch target identification and pointer authentication on AArch64"),
     cf_protection: CFProtection = (CFProtection::None, parse_cfprotection, [TRACKED],
         "instrument control-flow architecture protection"),
+    cfg_sets: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "type-check the crate once per configuration listed in the given file, one line of \
+        space-separated `--cfg` specs per configuration, instead of compiling it"),
     check_cfg_all_expected: bool = (false, parse_bool, [UNTRACKED],
         "show all expected values in check-cfg diagnostics (default: no)"),
     codegen_backend: Option<String> = (None, parse_opt_string, [TRACKED],
         "the backend to use"),
     combine_cgu: bool = (false, parse_bool, [TRACKED],
         "combine CGUs into a single one"),
+    const_eval_profile_folded: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the call stacks evaluated at compile-time to this file, in the folded format \
+        used by flamegraph tools"),
     coverage_options: CoverageOptions = (CoverageOptions::default(), parse_coverage_options, [TRACKED],
         "control details of coverage instrumentation"),
     crate_attr: Vec<String> = (Vec::new(), parse_string_push, [TRACKED],
@@ -1647,9 +1686,22 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "Direct or use GOT indirect to reference external data symbols"),
     dual_proc_macros: bool = (false, parse_bool, [TRACKED],
         "load proc macros for both target and host, but only link to the target (default: no)"),
+    dump_cfg_report: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "output a JSON report of each `cfg` and `cfg_attr` attribute evaluated, with its predicate \
+        and whether it holds, in the given directory (default: the current directory)"),
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv) \
         (default: no)"),
+    dump_macro_expansions: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "output a JSON trace of each expansion of a declarative macro, with the arm it matched, the \
+        bindings of its metavariables and its output, in the given directory \
+        (default: the current directory)"),
+    dump_match_report: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "output a JSON report of the exhaustiveness and arm reachability of each `match`, \
+        one file per body, in the given directory (default: the current directory)"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "dump MIR state to file.
         `val` is used to select which passes and functions to dump. For example:
@@ -1666,6 +1718,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "exclude the pass number when dumping MIR (used in tests) (default: no)"),
     dump_mir_graphviz: bool = (false, parse_bool, [UNTRACKED],
         "in addition to `.mir` files, create graphviz `.dot` files (default: no)"),
+    dump_mir_region_graph: Option<RegionGraphFormat> = (None, parse_region_graph_format, [UNTRACKED],
+        "in addition to the `nll` `.mir` files, dump the region constraint graph with spans, \
+        liveness, and the constraint paths of region errors, as `dot` or `html` (default: no)"),
     dump_mono_stats: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
         parse_switch_with_opt_path, [UNTRACKED],
         "output statistics about monomorphization collection"),
@@ -1787,6 +1842,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         (space separated)"),
     macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
         "show macro backtraces (default: no)"),
+    macro_match_details: bool = (false, parse_bool, [UNTRACKED],
+        "when a call to a declarative macro matches none of its rules, explain how far each rule \
+        matched and why it failed (default: no)"),
     maximal_hir_to_mir_coverage: bool = (false, parse_bool, [TRACKED],
         "save as much information as possible about the correspondence between MIR and HIR \
         as source scopes (default: no)"),
@@ -1811,6 +1869,10 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
     #[rustc_lint_opt_deny_field_access("use `Session::mir_opt_level` instead of this field")]
     mir_opt_level: Option<usize> = (None, parse_opt_number, [TRACKED],
         "MIR optimization level (0-4; default: 1 in non optimized builds and 2 in optimized builds)"),
+    mir_opt_pipeline: Option<MirOptPipeline> = (None, parse_mir_opt_pipeline, [TRACKED],
+        "replace the MIR optimization passes by the pipeline described in the given file \
+        (one pass per line, with optional `key=value` options), validating the MIR after \
+        each pass"),
     move_size_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
         "the size at which the `large_assignments` lint starts to be emitted"),
     mutable_noalias: bool = (true, parse_bool, [TRACKED],
@@ -1878,6 +1940,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
     #[rustc_lint_opt_deny_field_access("use `Session::print_codegen_stats` instead of this field")]
     print_codegen_stats: bool = (false, parse_bool, [UNTRACKED],
         "print codegen statistics (default: no)"),
+    print_const_eval_profile: bool = (false, parse_bool, [UNTRACKED],
+        "print the constants and functions that take the most steps to evaluate at \
+        compile-time (default: no)"),
     print_fuel: Option<String> = (None, parse_opt_string, [TRACKED],
         "make rustc print the total optimization fuel used by a crate"),
     print_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
@@ -1895,6 +1960,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
     proc_macro_execution_strategy: ProcMacroExecutionStrategy = (ProcMacroExecutionStrategy::SameThread,
         parse_proc_macro_execution_strategy, [UNTRACKED],
         "how to run proc-macro code (default: same-thread)"),
+    proc_macro_sandbox: bool = (false, parse_bool, [UNTRACKED],
+        "run proc macros in a separate process that cannot open files, create sockets or run \
+        programs (default: no)"),
     profile: bool = (false, parse_bool, [TRACKED],
         "insert profiling code (default: no)"),
     profile_closures: bool = (false, parse_no_flag, [UNTRACKED],
@@ -1919,6 +1987,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
     remark_dir: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
         "directory into which to write optimization remarks (if not specified, they will be \
 written to standard error output)"),
+    report_transmute_safety: bool = (false, parse_bool, [UNTRACKED],
+        "report, for each call to `transmute`, whether the safe transmute analysis can prove it \
+        safe, and why not (default: no)"),
     sanitizer: SanitizerSet = (SanitizerSet::empty(), parse_sanitizers, [TRACKED],
         "use a sanitizer"),
     sanitizer_cfi_canonical_jump_tables: Option<bool> = (Some(true), parse_opt_bool, [TRACKED],
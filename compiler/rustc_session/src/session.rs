@@ -1,5 +1,7 @@
 use crate::code_stats::CodeStats;
-pub use crate::code_stats::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
+pub use crate::code_stats::{
+    DataTypeKind, FieldInfo, FieldKind, NicheInfo, SizeKind, TagEncodingInfo, TagInfo, VariantInfo,
+};
 use crate::config::{
     self, CoverageLevel, CrateType, FunctionReturn, InstrumentCoverage, OptLevel, OutFileName,
     OutputType, RemapPathScopeComponents, SwitchWithOptPath,
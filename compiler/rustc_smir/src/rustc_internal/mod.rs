@@ -13,7 +13,7 @@
 use rustc_span::Span;
 use scoped_tls::scoped_thread_local;
 use stable_mir::abi::Layout;
-use stable_mir::ty::IndexedVal;
+use stable_mir::ty::{IndexedVal, ParamEnv};
 use stable_mir::Error;
 use std::cell::Cell;
 use std::cell::RefCell;
@@ -23,6 +23,7 @@
 
 mod internal;
 pub mod pretty;
+pub mod transform;
 
 /// Convert an internal Rust compiler item into its stable counterpart, if one exists.
 ///
@@ -171,6 +172,10 @@ pub(crate) fn static_def(&mut self, did: DefId) -> stable_mir::mir::mono::Static
     pub(crate) fn layout_id(&mut self, layout: rustc_target::abi::Layout<'tcx>) -> Layout {
         self.layouts.create_or_fetch(layout)
     }
+
+    pub(crate) fn param_env_id(&mut self, param_env: ty::ParamEnv<'tcx>) -> ParamEnv {
+        self.param_envs.create_or_fetch(param_env)
+    }
 }
 
 pub fn crate_num(item: &stable_mir::Crate) -> CrateNum {
@@ -217,6 +222,7 @@ pub fn run<F, T>(tcx: TyCtxt<'_>, f: F) -> Result<T, Error>
         ty_consts: IndexMap::default(),
         mir_consts: IndexMap::default(),
         layouts: IndexMap::default(),
+        param_envs: IndexMap::default(),
     }));
     stable_mir::compiler_interface::run(&tables, || init(&tables, f))
 }
@@ -0,0 +1,422 @@
+//! Support for drivers that rewrite the MIR of the local crate through StableMIR.
+//!
+//! A driver registers a [`BodyTransform`] from its `Config::override_queries` callback using
+//! [`set_body_transform`]. The transform is then applied to the optimized MIR of every local
+//! function, and the body it returns is the one used for code generation.
+
+use std::sync::RwLock;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_index::IndexVec;
+use rustc_middle::mir;
+use rustc_middle::ty::TyCtxt;
+use rustc_middle::util::Providers;
+use rustc_mir_transform::validate::Validator;
+use rustc_span::def_id::LocalDefId;
+use rustc_span::source_map::Spanned;
+use stable_mir::mir::visit::Location;
+use stable_mir::mir::{
+    BasicBlock, Body, Local, MirVisitor, Statement, StatementKind, Terminator, TerminatorKind,
+    UnwindAction, VarDebugInfo,
+};
+use stable_mir::{CrateItem, Error};
+
+use crate::rustc_internal::{self, RustcInternal};
+use crate::rustc_smir::{Stable, Tables};
+
+/// A transformation of the body of a local function.
+///
+/// It is given the item that is being compiled and its optimized MIR, and returns the body that
+/// should be used instead, or `None` to keep the original one.
+pub type BodyTransform = fn(CrateItem, &Body) -> Option<Body>;
+
+static BODY_TRANSFORM: RwLock<Option<BodyTransform>> = RwLock::new(None);
+
+/// Overrides the `optimized_mir` query in `providers` so that `transform` is applied to the
+/// optimized MIR of every local function before it is used.
+///
+/// This must be called from the `Config::override_queries` callback of the driver.
+///
+/// The returned body is converted back to the compiler representation and validated. If it
+/// cannot be converted, or if it is not valid MIR, an error is emitted for the item.
+///
+/// # Warning
+///
+/// This function is unstable, and its behavior may change at any point.
+/// The transform is not tracked by incremental compilation, so it must only depend on the body
+/// it is given. It must not request the body of the item it is transforming either, as that
+/// would cause a query cycle. Coroutine bodies are not transformed, and neither are bodies that
+/// are first requested from within a StableMIR call.
+pub fn set_body_transform(providers: &mut Providers, transform: BodyTransform) {
+    *BODY_TRANSFORM.write().unwrap() = Some(transform);
+    providers.queries.optimized_mir = optimized_mir;
+}
+
+#[allow(rustc::untranslatable_diagnostic)]
+#[allow(rustc::diagnostic_outside_of_impl)]
+fn optimized_mir(tcx: TyCtxt<'_>, did: LocalDefId) -> &mir::Body<'_> {
+    let mut providers = Providers::default();
+    rustc_mir_transform::provide(&mut providers);
+    let body = (providers.queries.optimized_mir)(tcx, did);
+
+    let Some(transform) = *BODY_TRANSFORM.read().unwrap() else { return body };
+    if !tcx.def_kind(did).is_fn_like()
+        || body.coroutine.is_some()
+        || body.tainted_by_errors.is_some()
+    {
+        return body;
+    }
+
+    // A body requested from within a StableMIR call, e.g. by `CrateItem::body`, is requested
+    // while the tables are borrowed, so the transform cannot use them.
+    if rustc_internal::TLV.is_set() {
+        return body;
+    }
+
+    match rustc_internal::run(tcx, || transform_body(tcx, did, body, transform))
+        .and_then(|result| result)
+    {
+        Ok(Some(new_body)) => tcx.arena.alloc(new_body),
+        Ok(None) => body,
+        Err(error) => {
+            tcx.dcx().span_err(body.span, format!("invalid transformed MIR body: {error}"));
+            body
+        }
+    }
+}
+
+fn transform_body<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    did: LocalDefId,
+    body: &mir::Body<'tcx>,
+    transform: BodyTransform,
+) -> Result<Option<mir::Body<'tcx>>, Error> {
+    // The tables must not be borrowed while the transform runs, since it may use StableMIR.
+    let (item, stable_body) = rustc_internal::with_tables(|tables| {
+        (tables.crate_item(did.to_def_id()), body.stable(tables))
+    });
+    let Some(new_body) = transform(item, &stable_body) else { return Ok(None) };
+    let new_body =
+        rustc_internal::with_tables(|tables| internal_body(tables, tcx, body, &new_body))?;
+
+    let validator = Validator {
+        when: format!("after transforming {:?} through StableMIR", did),
+        mir_phase: body.phase,
+    };
+    if let Some((location, msg)) = validator.validate(tcx, &new_body).into_iter().next() {
+        return Err(Error::new(format!("{msg} at {location:?}")));
+    }
+    Ok(Some(new_body))
+}
+
+/// Builds the compiler representation of `new_body`, which replaces `original`.
+///
+/// The parts of the body that StableMIR does not describe, such as source scopes, are taken from
+/// `original`.
+fn internal_body<'tcx>(
+    tables: &mut Tables<'_>,
+    tcx: TyCtxt<'tcx>,
+    original: &mir::Body<'tcx>,
+    new_body: &Body,
+) -> Result<mir::Body<'tcx>, Error> {
+    if new_body.arg_locals().len() != original.arg_count {
+        return Err(Error::new(format!(
+            "expected {} arguments, but the body has {}",
+            original.arg_count,
+            new_body.arg_locals().len()
+        )));
+    }
+    if new_body.spread_arg() != original.spread_arg.map(|local| local.as_usize()) {
+        return Err(Error::new("the spread argument of a body cannot be changed".to_string()));
+    }
+    let mut bounds = LocalBounds { num_locals: new_body.locals().len(), out_of_bounds: None };
+    bounds.visit_body(new_body);
+    if let Some(local) = bounds.out_of_bounds {
+        return Err(Error::new(format!("use of undeclared local `_{local}`")));
+    }
+
+    let mut builder = BodyBuilder {
+        tables,
+        tcx,
+        scopes: source_scopes(original),
+        is_cleanup: cleanup_blocks(new_body),
+    };
+
+    let mut body = original.clone();
+    *body.basic_blocks_mut() = new_body
+        .blocks
+        .iter()
+        .enumerate()
+        .map(|(idx, block)| builder.block(idx, block))
+        .collect::<Result<IndexVec<_, _>, _>>()?;
+    body.local_decls = new_body
+        .local_decls()
+        .map(|(local, decl)| {
+            let ty = decl.ty.internal(builder.tables, tcx);
+            let span = decl.span.internal(builder.tables, tcx);
+            let mut internal = match original.local_decls.get(mir::Local::from_usize(local)) {
+                Some(original_decl) => original_decl.clone(),
+                None => mir::LocalDecl::new(ty, span),
+            };
+            internal.ty = ty;
+            internal.mutability = decl.mutability.internal(builder.tables, tcx);
+            internal
+        })
+        .collect();
+    body.var_debug_info = new_body
+        .var_debug_info
+        .iter()
+        .map(|info| builder.var_debug_info(original, info))
+        .collect::<Result<_, _>>()?;
+    Ok(body)
+}
+
+/// Records the first local that is used but not declared.
+struct LocalBounds {
+    num_locals: usize,
+    out_of_bounds: Option<Local>,
+}
+
+impl MirVisitor for LocalBounds {
+    fn visit_local(
+        &mut self,
+        local: &Local,
+        _ptx: stable_mir::mir::visit::PlaceContext,
+        _location: Location,
+    ) {
+        if *local >= self.num_locals && self.out_of_bounds.is_none() {
+            self.out_of_bounds = Some(*local);
+        }
+    }
+}
+
+/// Maps the spans of `body` to the scope they are in, so that statements that were kept or moved
+/// by the transform are put back in their scope.
+fn source_scopes(body: &mir::Body<'_>) -> FxHashMap<rustc_span::Span, mir::SourceScope> {
+    let mut scopes = FxHashMap::default();
+    for decl in &body.local_decls {
+        scopes.insert(decl.source_info.span, decl.source_info.scope);
+    }
+    for block in body.basic_blocks.iter() {
+        for statement in &block.statements {
+            scopes.insert(statement.source_info.span, statement.source_info.scope);
+        }
+        let source_info = block.terminator().source_info;
+        scopes.insert(source_info.span, source_info.scope);
+    }
+    scopes
+}
+
+/// Returns whether each block of `body` is only reachable while unwinding.
+fn cleanup_blocks(body: &Body) -> Vec<bool> {
+    let mut is_cleanup = vec![false; body.blocks.len()];
+    let mut worklist: Vec<_> = body
+        .blocks
+        .iter()
+        .filter_map(|block| match block.terminator.kind.unwind() {
+            Some(UnwindAction::Cleanup(target)) => Some(*target),
+            _ => None,
+        })
+        .collect();
+    while let Some(idx) = worklist.pop() {
+        // Jumps to missing blocks are reported by the validator.
+        if idx < is_cleanup.len() && !std::mem::replace(&mut is_cleanup[idx], true) {
+            worklist.extend(body.blocks[idx].terminator.successors());
+        }
+    }
+    is_cleanup
+}
+
+struct BodyBuilder<'a, 'b, 'tcx> {
+    tables: &'a mut Tables<'b>,
+    tcx: TyCtxt<'tcx>,
+    scopes: FxHashMap<rustc_span::Span, mir::SourceScope>,
+    is_cleanup: Vec<bool>,
+}
+
+impl<'a, 'b, 'tcx> BodyBuilder<'a, 'b, 'tcx> {
+    fn source_info(&mut self, span: stable_mir::ty::Span) -> mir::SourceInfo {
+        let span = span.internal(self.tables, self.tcx);
+        let scope = self.scopes.get(&span).copied().unwrap_or(mir::OUTERMOST_SOURCE_SCOPE);
+        mir::SourceInfo { span, scope }
+    }
+
+    fn block(
+        &mut self,
+        idx: usize,
+        block: &BasicBlock,
+    ) -> Result<mir::BasicBlockData<'tcx>, Error> {
+        let is_cleanup = self.is_cleanup[idx];
+        let statements = block
+            .statements
+            .iter()
+            .map(|statement| self.statement(statement))
+            .collect::<Result<_, _>>()?;
+        let terminator = self.terminator(&block.terminator, is_cleanup)?;
+        Ok(mir::BasicBlockData { statements, terminator: Some(terminator), is_cleanup })
+    }
+
+    fn statement(&mut self, statement: &Statement) -> Result<mir::Statement<'tcx>, Error> {
+        let (tables, tcx) = (&mut *self.tables, self.tcx);
+        let kind = match &statement.kind {
+            StatementKind::Assign(place, rvalue) => mir::StatementKind::Assign(Box::new((
+                place.internal(tables, tcx),
+                rvalue.internal(tables, tcx),
+            ))),
+            StatementKind::SetDiscriminant { place, variant_index } => {
+                mir::StatementKind::SetDiscriminant {
+                    place: Box::new(place.internal(tables, tcx)),
+                    variant_index: variant_index.internal(tables, tcx),
+                }
+            }
+            StatementKind::Deinit(place) => {
+                mir::StatementKind::Deinit(Box::new(place.internal(tables, tcx)))
+            }
+            StatementKind::StorageLive(local) => {
+                mir::StatementKind::StorageLive(mir::Local::from_usize(*local))
+            }
+            StatementKind::StorageDead(local) => {
+                mir::StatementKind::StorageDead(mir::Local::from_usize(*local))
+            }
+            StatementKind::Retag(kind, place) => mir::StatementKind::Retag(
+                kind.internal(tables, tcx),
+                Box::new(place.internal(tables, tcx)),
+            ),
+            StatementKind::PlaceMention(place) => {
+                mir::StatementKind::PlaceMention(Box::new(place.internal(tables, tcx)))
+            }
+            StatementKind::Intrinsic(intrinsic) => {
+                mir::StatementKind::Intrinsic(Box::new(intrinsic.internal(tables, tcx)))
+            }
+            StatementKind::ConstEvalCounter => mir::StatementKind::ConstEvalCounter,
+            StatementKind::Nop => mir::StatementKind::Nop,
+            StatementKind::FakeRead(..)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Coverage(..) => {
+                return Err(Error::new(format!(
+                    "unsupported statement in a transformed body: {:?}",
+                    statement.kind
+                )));
+            }
+        };
+        Ok(mir::Statement { source_info: self.source_info(statement.span), kind })
+    }
+
+    fn terminator(
+        &mut self,
+        terminator: &Terminator,
+        is_cleanup: bool,
+    ) -> Result<mir::Terminator<'tcx>, Error> {
+        let source_info = self.source_info(terminator.span);
+        let (tables, tcx) = (&mut *self.tables, self.tcx);
+        let terminate_reason = if is_cleanup {
+            mir::UnwindTerminateReason::InCleanup
+        } else {
+            mir::UnwindTerminateReason::Abi
+        };
+        let unwind = |action: &UnwindAction| match action {
+            UnwindAction::Continue => mir::UnwindAction::Continue,
+            UnwindAction::Unreachable => mir::UnwindAction::Unreachable,
+            UnwindAction::Terminate => mir::UnwindAction::Terminate(terminate_reason),
+            UnwindAction::Cleanup(target) => {
+                mir::UnwindAction::Cleanup(mir::BasicBlock::from_usize(*target))
+            }
+        };
+        let kind = match &terminator.kind {
+            TerminatorKind::Goto { target } => {
+                mir::TerminatorKind::Goto { target: mir::BasicBlock::from_usize(*target) }
+            }
+            TerminatorKind::SwitchInt { discr, targets } => mir::TerminatorKind::SwitchInt {
+                discr: discr.internal(tables, tcx),
+                targets: mir::SwitchTargets::new(
+                    targets
+                        .branches()
+                        .map(|(value, target)| (value, mir::BasicBlock::from_usize(target))),
+                    mir::BasicBlock::from_usize(targets.otherwise()),
+                ),
+            },
+            TerminatorKind::Resume => mir::TerminatorKind::UnwindResume,
+            TerminatorKind::Abort => mir::TerminatorKind::UnwindTerminate(terminate_reason),
+            TerminatorKind::Return => mir::TerminatorKind::Return,
+            TerminatorKind::Unreachable => mir::TerminatorKind::Unreachable,
+            TerminatorKind::Drop { place, target, unwind: unwind_action } => {
+                mir::TerminatorKind::Drop {
+                    place: place.internal(tables, tcx),
+                    target: mir::BasicBlock::from_usize(*target),
+                    unwind: unwind(unwind_action),
+                    replace: false,
+                }
+            }
+            TerminatorKind::Call { func, args, destination, target, unwind: unwind_action } => {
+                mir::TerminatorKind::Call {
+                    func: func.internal(tables, tcx),
+                    args: args
+                        .iter()
+                        .map(|arg| Spanned {
+                            node: arg.internal(tables, tcx),
+                            span: source_info.span,
+                        })
+                        .collect(),
+                    destination: destination.internal(tables, tcx),
+                    target: target.map(mir::BasicBlock::from_usize),
+                    unwind: unwind(unwind_action),
+                    call_source: mir::CallSource::Normal,
+                    fn_span: source_info.span,
+                }
+            }
+            TerminatorKind::Assert { cond, expected, msg, target, unwind: unwind_action } => {
+                mir::TerminatorKind::Assert {
+                    cond: cond.internal(tables, tcx),
+                    expected: *expected,
+                    msg: Box::new(msg.internal(tables, tcx)),
+                    target: mir::BasicBlock::from_usize(*target),
+                    unwind: unwind(unwind_action),
+                }
+            }
+            TerminatorKind::InlineAsm { .. } => {
+                return Err(Error::new(
+                    "inline assembly is not supported in a transformed body".to_string(),
+                ));
+            }
+        };
+        Ok(mir::Terminator { source_info, kind })
+    }
+
+    fn var_debug_info(
+        &mut self,
+        original: &mir::Body<'tcx>,
+        info: &VarDebugInfo,
+    ) -> Result<mir::VarDebugInfo<'tcx>, Error> {
+        let (tables, tcx) = (&mut *self.tables, self.tcx);
+        let scope = info.source_info.scope as usize;
+        if scope >= original.source_scopes.len() {
+            return Err(Error::new(format!(
+                "debug info of `{}` refers to an unknown scope",
+                info.name
+            )));
+        }
+        let value = match &info.value {
+            stable_mir::mir::VarDebugInfoContents::Place(place) => {
+                mir::VarDebugInfoContents::Place(place.internal(tables, tcx))
+            }
+            stable_mir::mir::VarDebugInfoContents::Const(constant) => {
+                mir::VarDebugInfoContents::Const(constant.internal(tables, tcx))
+            }
+        };
+        Ok(mir::VarDebugInfo {
+            name: rustc_span::Symbol::intern(&info.name),
+            source_info: mir::SourceInfo {
+                span: info.source_info.span.internal(tables, tcx),
+                scope: mir::SourceScope::from_usize(scope),
+            },
+            composite: info.composite.as_ref().map(|fragment| {
+                Box::new(mir::VarDebugInfoFragment {
+                    ty: fragment.ty.internal(tables, tcx),
+                    projection: fragment.projection.internal(tables, tcx),
+                })
+            }),
+            value,
+            argument_index: info.argument_index,
+        })
+    }
+}
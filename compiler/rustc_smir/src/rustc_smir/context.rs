@@ -16,8 +16,12 @@
 };
 use rustc_middle::{mir, ty};
 use rustc_span::def_id::LOCAL_CRATE;
+use rustc_trait_selection::infer::TyCtxtInferExt;
+use rustc_trait_selection::traits;
+use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt;
 use stable_mir::abi::{FnAbi, Layout, LayoutShape};
 use stable_mir::compiler_interface::Context;
+use stable_mir::crate_def::Attribute;
 use stable_mir::mir::alloc::GlobalAlloc;
 use stable_mir::mir::mono::{InstanceDef, StaticDef};
 use stable_mir::mir::{BinOp, Body, Place, UnOp};
@@ -228,6 +232,21 @@ fn def_name(&self, def_id: stable_mir::DefId, trimmed: bool) -> Symbol {
         }
     }
 
+    fn get_all_attrs(&self, def_id: stable_mir::DefId) -> Vec<Attribute> {
+        let mut tables = self.0.borrow_mut();
+        let tcx = tables.tcx;
+        let did = tables[def_id];
+        tcx.get_attrs_unchecked(did).iter().map(|attr| attr.stable(&mut *tables)).collect()
+    }
+
+    fn get_attrs_by_path(&self, def_id: stable_mir::DefId, attr: &[Symbol]) -> Vec<Attribute> {
+        let mut tables = self.0.borrow_mut();
+        let tcx = tables.tcx;
+        let did = tables[def_id];
+        let attr: Vec<_> = attr.iter().map(|segment| rustc_span::Symbol::intern(segment)).collect();
+        tcx.get_attrs_by_path(did, &attr).map(|attr| attr.stable(&mut *tables)).collect()
+    }
+
     fn span_to_string(&self, span: stable_mir::ty::Span) -> String {
         let tables = self.0.borrow();
         tables.tcx.sess.source_map().span_to_diagnostic_string(tables[span])
@@ -739,6 +758,68 @@ fn unop_ty(&self, un_op: UnOp, arg: Ty) -> Ty {
         let ty = un_op.internal(&mut *tables, tcx).ty(tcx, arg_internal);
         ty.stable(&mut *tables)
     }
+
+    fn param_env(&self, def_id: DefId) -> stable_mir::ty::ParamEnv {
+        let mut tables = self.0.borrow_mut();
+        let tcx = tables.tcx;
+        let def_id = def_id.internal(&mut *tables, tcx);
+        tcx.param_env(def_id).stable(&mut *tables)
+    }
+
+    fn param_env_reveal_all(&self) -> stable_mir::ty::ParamEnv {
+        let mut tables = self.0.borrow_mut();
+        ParamEnv::reveal_all().stable(&mut *tables)
+    }
+
+    fn predicate_must_hold_modulo_regions(
+        &self,
+        env: stable_mir::ty::ParamEnv,
+        trait_ref: &stable_mir::ty::TraitRef,
+    ) -> bool {
+        let mut tables = self.0.borrow_mut();
+        let tcx = tables.tcx;
+        let param_env = env.internal(&mut *tables, tcx);
+        let trait_ref = trait_ref.internal(&mut *tables, tcx);
+        let obligation =
+            traits::Obligation::new(tcx, traits::ObligationCause::dummy(), param_env, trait_ref);
+        tcx.infer_ctxt().build().predicate_must_hold_modulo_regions(&obligation)
+    }
+
+    fn normalize_erasing_regions(
+        &self,
+        env: stable_mir::ty::ParamEnv,
+        ty: Ty,
+    ) -> Result<Ty, Error> {
+        let mut tables = self.0.borrow_mut();
+        let tcx = tables.tcx;
+        let param_env = env.internal(&mut *tables, tcx);
+        let internal_ty = ty.internal(&mut *tables, tcx);
+        match tcx.try_normalize_erasing_regions(param_env, internal_ty) {
+            Ok(normalized) => Ok(normalized.stable(&mut *tables)),
+            Err(_) => Err(Error::new(format!("Failed to normalize type `{internal_ty}`"))),
+        }
+    }
+
+    fn ty_is_copy(&self, env: stable_mir::ty::ParamEnv, ty: Ty) -> bool {
+        let mut tables = self.0.borrow_mut();
+        let tcx = tables.tcx;
+        let param_env = env.internal(&mut *tables, tcx);
+        ty.internal(&mut *tables, tcx).is_copy_modulo_regions(tcx, param_env)
+    }
+
+    fn ty_is_sized(&self, env: stable_mir::ty::ParamEnv, ty: Ty) -> bool {
+        let mut tables = self.0.borrow_mut();
+        let tcx = tables.tcx;
+        let param_env = env.internal(&mut *tables, tcx);
+        ty.internal(&mut *tables, tcx).is_sized(tcx, param_env)
+    }
+
+    fn ty_needs_drop(&self, env: stable_mir::ty::ParamEnv, ty: Ty) -> bool {
+        let mut tables = self.0.borrow_mut();
+        let tcx = tables.tcx;
+        let param_env = env.internal(&mut *tables, tcx);
+        ty.internal(&mut *tables, tcx).needs_drop(tcx, param_env)
+    }
 }
 
 pub struct TablesWrapper<'tcx>(pub RefCell<Tables<'tcx>>);
@@ -85,3 +85,43 @@ fn stable(&self, tables: &mut Tables<'_>) -> Self::T {
         tables.create_span(*self)
     }
 }
+
+impl<'tcx> Stable<'tcx> for rustc_ast::Attribute {
+    type T = stable_mir::crate_def::Attribute;
+
+    fn stable(&self, tables: &mut Tables<'_>) -> Self::T {
+        use rustc_ast::tokenstream::{DelimSpacing, Spacing, TokenTree};
+        use rustc_ast::{AttrArgs, AttrArgsEq, AttrKind, DelimArgs};
+        use rustc_ast_pretty::pprust;
+
+        let doc = self.doc_str();
+        let tokens = match &self.kind {
+            // Doc comments are equivalent to `#[doc = "..."]`.
+            AttrKind::DocComment(_, data) => format!("= {:?}", data.as_str()),
+            AttrKind::Normal(normal) => match &normal.item.args {
+                AttrArgs::Empty => String::new(),
+                AttrArgs::Delimited(DelimArgs { dspan, delim, tokens }) => {
+                    let spacing = DelimSpacing::new(Spacing::Alone, Spacing::Alone);
+                    pprust::tt_to_string(&TokenTree::Delimited(
+                        *dspan,
+                        spacing,
+                        *delim,
+                        tokens.clone(),
+                    ))
+                }
+                AttrArgs::Eq(_, AttrArgsEq::Ast(expr)) => {
+                    format!("= {}", pprust::expr_to_string(expr))
+                }
+                // Attributes lowered to HIR or loaded from other crates only keep the literal.
+                AttrArgs::Eq(_, AttrArgsEq::Hir(lit)) => format!("= {}", lit.as_token_lit()),
+            },
+        };
+        stable_mir::crate_def::Attribute {
+            path: self.path().iter().map(|segment| segment.to_string()).collect(),
+            tokens,
+            doc: doc.map(|doc| doc.to_string()),
+            value: pprust::attribute_to_string(self),
+            span: self.span.stable(tables),
+        }
+    }
+}
@@ -565,6 +565,14 @@ fn stable(&self, tables: &mut Tables<'_>) -> Self::T {
     }
 }
 
+impl<'tcx> Stable<'tcx> for ty::ParamEnv<'tcx> {
+    type T = stable_mir::ty::ParamEnv;
+
+    fn stable(&self, tables: &mut Tables<'_>) -> Self::T {
+        tables.param_env_id(tables.tcx.lift(*self).unwrap())
+    }
+}
+
 impl<'tcx> Stable<'tcx> for ty::Generics {
     type T = stable_mir::ty::Generics;
 
@@ -36,6 +36,7 @@ pub struct Tables<'tcx> {
     pub(crate) ty_consts: IndexMap<ty::Const<'tcx>, TyConstId>,
     pub(crate) mir_consts: IndexMap<mir::Const<'tcx>, MirConstId>,
     pub(crate) layouts: IndexMap<rustc_target::abi::Layout<'tcx>, Layout>,
+    pub(crate) param_envs: IndexMap<ty::ParamEnv<'tcx>, stable_mir::ty::ParamEnv>,
 }
 
 impl<'tcx> Tables<'tcx> {
@@ -902,6 +902,7 @@
         format_count,
         format_macro,
         format_placeholder,
+        format_string,
         format_unsafe_arg,
         freeze,
         freeze_impls,
@@ -1122,6 +1123,7 @@
         macro_attributes_in_derive_output,
         macro_escape,
         macro_export,
+        macro_format_string,
         macro_lifetime_matcher,
         macro_literal_matcher,
         macro_metavar_expr,
@@ -1439,6 +1441,7 @@
         proc_macro_mod,
         proc_macro_non_items,
         proc_macro_path_invoc,
+        proc_macro_pure,
         process_exit,
         profiler_builtins,
         profiler_runtime,
@@ -48,6 +48,12 @@ pub enum Reason<T> {
     DstIsNotYetSupported,
     /// The layout of the destination type is bit-incompatible with the source type.
     DstIsBitIncompatible,
+    /// At the given byte offset, the source type may have a value that isn't a valid value of the
+    /// destination type, e.g. because the destination type has a niche there.
+    DstIsBitIncompatibleAt { offset: usize },
+    /// At the given byte offset, the source type may be uninitialized, e.g. because it is padding,
+    /// but the destination type requires an initialized byte.
+    SrcMayBeUninitAt { offset: usize },
     /// The destination type is uninhabited.
     DstUninhabited,
     /// The destination type may carry safety invariants.
@@ -127,6 +133,81 @@ pub fn is_transmutable(
         }
     }
 
+    impl<'tcx> Reason<crate::layout::rustc::Ref<'tcx>> {
+        /// Explains why `src` is not transmutable into `dst`, for diagnostics. Returns `None` if
+        /// the reason is an error that was already reported.
+        pub fn explain(&self, src: Ty<'tcx>, dst: Ty<'tcx>) -> Option<String> {
+            let explanation = match self {
+                Reason::SrcIsNotYetSupported => {
+                    format!("analyzing the transmutability of `{src}` is not yet supported")
+                }
+
+                Reason::DstIsNotYetSupported => {
+                    format!("analyzing the transmutability of `{dst}` is not yet supported")
+                }
+
+                Reason::DstIsBitIncompatible => {
+                    format!("at least one value of `{src}` isn't a bit-valid value of `{dst}`")
+                }
+
+                Reason::DstIsBitIncompatibleAt { offset } => {
+                    format!(
+                        "at least one value of `{src}` isn't a bit-valid value of `{dst}` (at byte offset {offset})"
+                    )
+                }
+
+                Reason::SrcMayBeUninitAt { offset } => {
+                    format!(
+                        "the byte at offset {offset} of `{src}` may be uninitialized, but `{dst}` requires it to be initialized"
+                    )
+                }
+
+                Reason::DstUninhabited => {
+                    format!("`{dst}` is uninhabited")
+                }
+
+                Reason::DstMayHaveSafetyInvariants => {
+                    format!("`{dst}` may carry safety invariants")
+                }
+                Reason::DstIsTooBig => {
+                    format!("the size of `{src}` is smaller than the size of `{dst}`")
+                }
+                Reason::DstRefIsTooBig { src, dst } => {
+                    let src_size = src.size;
+                    let dst_size = dst.size;
+                    format!(
+                        "the referent size of `{src}` ({src_size} bytes) is smaller than that of `{dst}` ({dst_size} bytes)"
+                    )
+                }
+                Reason::SrcSizeOverflow => {
+                    format!("values of the type `{src}` are too big for the current architecture")
+                }
+                Reason::DstSizeOverflow => {
+                    format!("values of the type `{dst}` are too big for the current architecture")
+                }
+                Reason::DstHasStricterAlignment { src_min_align, dst_min_align } => {
+                    format!(
+                        "the minimum alignment of `{src}` ({src_min_align}) should be greater than that of `{dst}` ({dst_min_align})"
+                    )
+                }
+                Reason::DstIsMoreUnique => {
+                    format!("`{src}` is a shared reference, but `{dst}` is a unique reference")
+                }
+                // Already reported by rustc
+                Reason::TypeError => {
+                    return None;
+                }
+                Reason::SrcLayoutUnknown => {
+                    format!("`{src}` has an unknown layout")
+                }
+                Reason::DstLayoutUnknown => {
+                    format!("`{dst}` has an unknown layout")
+                }
+            };
+            Some(explanation)
+        }
+    }
+
     impl Assume {
         /// Constructs an `Assume` from a given const-`Assume`.
         pub fn from_const<'tcx>(
@@ -165,9 +165,11 @@ impl<C> MaybeTransmutableQuery<Dfa<<C as QueryContext>::Ref>, C>
 {
     /// Answers whether a `Dfa` is transmutable into another `Dfa`.
     pub(crate) fn answer(self) -> Answer<<C as QueryContext>::Ref> {
-        self.answer_memo(&mut Map::default(), self.src.start, self.dst.start)
+        self.answer_memo(&mut Map::default(), self.src.start, self.dst.start, 0)
     }
 
+    /// Answers whether the `Dfa`s are transmutable from `src_state` and `dst_state` onwards, where
+    /// `offset` is the offset in bytes of these states, used to explain why they aren't.
     #[inline(always)]
     #[instrument(level = "debug", skip(self))]
     fn answer_memo(
@@ -175,6 +177,7 @@ fn answer_memo(
         cache: &mut Map<(dfa::State, dfa::State), Answer<<C as QueryContext>::Ref>>,
         src_state: dfa::State,
         dst_state: dfa::State,
+        offset: usize,
     ) -> Answer<<C as QueryContext>::Ref> {
         if let Some(answer) = cache.get(&(src_state, dst_state)) {
             answer.clone()
@@ -206,7 +209,7 @@ fn answer_memo(
             } else if src_state == self.src.accepting {
                 // extension: `size_of(Src) >= size_of(Dst)`
                 if let Some(dst_state_prime) = self.dst.byte_from(dst_state, Byte::Uninit) {
-                    self.answer_memo(cache, src_state, dst_state_prime)
+                    self.answer_memo(cache, src_state, dst_state_prime, offset + 1)
                 } else {
                     Answer::No(Reason::DstIsTooBig)
                 }
@@ -231,17 +234,31 @@ fn answer_memo(
                             if let Some(dst_state_prime) =
                                 self.dst.byte_from(dst_state, src_validity)
                             {
-                                self.answer_memo(cache, src_state_prime, dst_state_prime)
+                                self.answer_memo(
+                                    cache,
+                                    src_state_prime,
+                                    dst_state_prime,
+                                    offset + 1,
+                                )
                             } else if let Some(dst_state_prime) =
                                 // otherwise, see if `dst_state` has any outgoing `Uninit` transitions
                                 // (any init byte is a valid uninit byte)
                                 self.dst.byte_from(dst_state, Byte::Uninit)
                             {
-                                self.answer_memo(cache, src_state_prime, dst_state_prime)
+                                self.answer_memo(
+                                    cache,
+                                    src_state_prime,
+                                    dst_state_prime,
+                                    offset + 1,
+                                )
+                            } else if src_validity == Byte::Uninit {
+                                // otherwise, we've exhausted our options: `src` may be
+                                // uninitialized here, but `dst` must be initialized.
+                                Answer::No(Reason::SrcMayBeUninitAt { offset })
                             } else {
                                 // otherwise, we've exhausted our options.
                                 // the DFAs, from this point onwards, are bit-incompatible.
-                                Answer::No(Reason::DstIsBitIncompatible)
+                                Answer::No(Reason::DstIsBitIncompatibleAt { offset })
                             }
                         },
                     ),
@@ -300,6 +317,7 @@ fn answer_memo(
                                                     cache,
                                                     src_state_prime,
                                                     dst_state_prime,
+                                                    offset + self.context.pointer_size(),
                                                 ),
                                             )
                                         }
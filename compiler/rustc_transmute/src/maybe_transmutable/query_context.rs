@@ -5,6 +5,9 @@ pub(crate) trait QueryContext {
     type Def: layout::Def;
     type Ref: layout::Ref;
     type Scope: Copy;
+
+    /// The size of a reference, in bytes.
+    fn pointer_size(&self) -> usize;
 }
 
 #[cfg(test)]
@@ -29,6 +32,10 @@ impl QueryContext for UltraMinimal {
         type Def = Def;
         type Ref = !;
         type Scope = ();
+
+        fn pointer_size(&self) -> usize {
+            8
+        }
     }
 }
 
@@ -42,5 +49,9 @@ impl<'tcx> super::QueryContext for TyCtxt<'tcx> {
         type Ref = layout::rustc::Ref<'tcx>;
 
         type Scope = Ty<'tcx>;
+
+        fn pointer_size(&self) -> usize {
+            self.data_layout.pointer_size.bytes_usize()
+        }
     }
 }
@@ -151,7 +151,7 @@ fn should_permit_validity_expansion_and_reject_contraction() {
                     );
                 } else {
                     assert_eq!(
-                        Answer::No(Reason::DstIsBitIncompatible),
+                        Answer::No(Reason::DstIsBitIncompatibleAt { offset: 0 }),
                         MaybeTransmutableQuery::new(
                             src_layout.clone(),
                             dst_layout.clone(),
@@ -168,3 +168,32 @@ fn should_permit_validity_expansion_and_reject_contraction() {
         }
     }
 }
+
+mod explanations {
+    use crate::Answer;
+
+    use super::*;
+
+    type Tree = layout::Tree<Def, !>;
+
+    fn is_transmutable(src: Tree, dst: Tree) -> Answer<!> {
+        MaybeTransmutableQuery::new(src, dst, crate::Assume::default(), UltraMinimal).answer()
+    }
+
+    #[test]
+    fn should_point_at_the_first_invalid_byte() {
+        let src = Tree::number(2);
+        let dst = Tree::u8().then(Tree::bool());
+        assert_eq!(
+            is_transmutable(src, dst),
+            Answer::No(Reason::DstIsBitIncompatibleAt { offset: 1 })
+        );
+    }
+
+    #[test]
+    fn should_point_at_uninit_bytes() {
+        let src = Tree::u8().then(Tree::padding(1)).then(Tree::u8());
+        let dst = Tree::number(3);
+        assert_eq!(is_transmutable(src, dst), Answer::No(Reason::SrcMayBeUninitAt { offset: 1 }));
+    }
+}
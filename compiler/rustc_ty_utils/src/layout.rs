@@ -13,7 +13,10 @@
     self, AdtDef, CoroutineArgsExt, EarlyBinder, FieldDef, GenericArgsRef, Ty, TyCtxt,
     TypeVisitableExt,
 };
-use rustc_session::{DataTypeKind, FieldInfo, FieldKind, SizeKind, VariantInfo};
+use rustc_session::config::PrintKind;
+use rustc_session::{
+    DataTypeKind, FieldInfo, FieldKind, NicheInfo, SizeKind, TagEncodingInfo, TagInfo, VariantInfo,
+};
 use rustc_span::sym;
 use rustc_span::symbol::Symbol;
 use rustc_target::abi::*;
@@ -68,9 +71,11 @@ fn layout_of<'tcx>(
     let layout = layout_of_uncached(&cx, ty)?;
     let layout = TyAndLayout { ty, layout };
 
-    // If we are running with `-Zprint-type-sizes`, maybe record layouts
-    // for dumping later.
-    if cx.tcx.sess.opts.unstable_opts.print_type_sizes {
+    // If we are running with `-Zprint-type-sizes` or `--print=type-layouts`,
+    // maybe record layouts for dumping later.
+    if cx.tcx.sess.opts.unstable_opts.print_type_sizes
+        || cx.tcx.sess.opts.prints.iter().any(|p| p.kind == PrintKind::TypeLayouts)
+    {
         record_layout_for_printing(&cx, layout);
     }
 
@@ -1027,6 +1032,8 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx, TyCtxt<'tcx>>, layout: T
             layout.size,
             packed,
             opt_discr_size,
+            niche_info(cx, layout),
+            tag_info(cx, layout),
             variants,
         );
     };
@@ -1059,6 +1066,42 @@ fn record_layout_for_printing<'tcx>(cx: &LayoutCx<'tcx, TyCtxt<'tcx>>, layout: T
     };
 }
 
+fn niche_info<'tcx>(
+    cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
+    layout: TyAndLayout<'tcx>,
+) -> Option<NicheInfo> {
+    let niche = layout.largest_niche?;
+    Some(NicheInfo {
+        offset: niche.offset.bytes(),
+        size: niche.value.size(cx).bytes(),
+        valid_range_start: niche.valid_range.start,
+        valid_range_end: niche.valid_range.end,
+        available: niche.available(cx),
+    })
+}
+
+fn tag_info<'tcx>(cx: &LayoutCx<'tcx, TyCtxt<'tcx>>, layout: TyAndLayout<'tcx>) -> Option<TagInfo> {
+    let Variants::Multiple { tag, ref tag_encoding, tag_field, .. } = layout.variants else {
+        return None;
+    };
+    let encoding = match *tag_encoding {
+        TagEncoding::Direct => TagEncodingInfo::Direct,
+        TagEncoding::Niche { untagged_variant, ref niche_variants, niche_start } => {
+            TagEncodingInfo::Niche {
+                untagged_variant: untagged_variant.as_u32(),
+                niche_variants_start: niche_variants.start().as_u32(),
+                niche_variants_end: niche_variants.end().as_u32(),
+                niche_start,
+            }
+        }
+    };
+    Some(TagInfo {
+        offset: layout.fields.offset(tag_field).bytes(),
+        size: tag.size(cx).bytes(),
+        encoding,
+    })
+}
+
 fn variant_info_for_adt<'tcx>(
     cx: &LayoutCx<'tcx, TyCtxt<'tcx>>,
     layout: TyAndLayout<'tcx>,
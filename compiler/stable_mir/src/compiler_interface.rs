@@ -6,6 +6,7 @@
 use std::cell::Cell;
 
 use crate::abi::{FnAbi, Layout, LayoutShape};
+use crate::crate_def::Attribute;
 use crate::mir::alloc::{AllocId, GlobalAlloc};
 use crate::mir::mono::{Instance, InstanceDef, StaticDef};
 use crate::mir::{BinOp, Body, Place, UnOp};
@@ -13,8 +14,8 @@
 use crate::ty::{
     AdtDef, AdtKind, Allocation, ClosureDef, ClosureKind, FieldDef, FnDef, ForeignDef,
     ForeignItemKind, ForeignModule, ForeignModuleDef, GenericArgs, GenericPredicates, Generics,
-    ImplDef, ImplTrait, IntrinsicDef, LineInfo, MirConst, PolyFnSig, RigidTy, Span, TraitDecl,
-    TraitDef, Ty, TyConst, TyConstId, TyKind, UintTy, VariantDef,
+    ImplDef, ImplTrait, IntrinsicDef, LineInfo, MirConst, ParamEnv, PolyFnSig, RigidTy, Span,
+    TraitDecl, TraitDef, TraitRef, Ty, TyConst, TyConstId, TyKind, UintTy, VariantDef,
 };
 use crate::{
     mir, Crate, CrateItem, CrateItems, CrateNum, DefId, Error, Filename, ImplTraitDecls, ItemKind,
@@ -55,6 +56,12 @@ pub trait Context {
     /// Returns the name of given `DefId`
     fn def_name(&self, def_id: DefId, trimmed: bool) -> Symbol;
 
+    /// Returns all the attributes of the given `DefId`.
+    fn get_all_attrs(&self, def_id: DefId) -> Vec<Attribute>;
+
+    /// Returns the attributes of the given `DefId` whose path is `attr`.
+    fn get_attrs_by_path(&self, def_id: DefId, attr: &[Symbol]) -> Vec<Attribute>;
+
     /// Returns printable, human readable form of `Span`
     fn span_to_string(&self, span: Span) -> String;
 
@@ -232,6 +239,27 @@ fn resolve_closure(
 
     /// Get the resulting type of unary operation.
     fn unop_ty(&self, un_op: UnOp, arg: Ty) -> Ty;
+
+    /// Get the param-env of an item.
+    fn param_env(&self, def_id: DefId) -> ParamEnv;
+
+    /// Get the param-env of monomorphic code.
+    fn param_env_reveal_all(&self) -> ParamEnv;
+
+    /// Check whether a trait reference holds in the given param-env, ignoring regions.
+    fn predicate_must_hold_modulo_regions(&self, env: ParamEnv, trait_ref: &TraitRef) -> bool;
+
+    /// Normalize a type in the given param-env, and erase its regions.
+    fn normalize_erasing_regions(&self, env: ParamEnv, ty: Ty) -> Result<Ty, Error>;
+
+    /// Check whether a type implements `Copy` in the given param-env, ignoring regions.
+    fn ty_is_copy(&self, env: ParamEnv, ty: Ty) -> bool;
+
+    /// Check whether a type is `Sized` in the given param-env.
+    fn ty_is_sized(&self, env: ParamEnv, ty: Ty) -> bool;
+
+    /// Check whether dropping a value of a type may run some code.
+    fn ty_needs_drop(&self, env: ParamEnv, ty: Ty) -> bool;
 }
 
 // A thread local variable that stores a pointer to the tables mapping between TyCtxt
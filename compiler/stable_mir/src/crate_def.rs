@@ -6,6 +6,7 @@
 
 /// A unique identification number for each item accessible for the current compilation unit.
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DefId(pub(crate) usize);
 
 /// A trait for retrieving information about a particular definition.
@@ -50,6 +51,59 @@ fn span(&self) -> Span {
         let def_id = self.def_id();
         with(|cx| cx.span_of_an_item(def_id))
     }
+
+    /// Return all the attributes of this definition, including doc comments, in source order.
+    ///
+    /// For definitions from other crates, only the attributes recorded in the crate metadata
+    /// are available.
+    fn all_attrs(&self) -> Vec<Attribute> {
+        let def_id = self.def_id();
+        with(|cx| cx.get_all_attrs(def_id))
+    }
+
+    /// Return the attributes of this definition whose path is `attr`.
+    ///
+    /// For example, `["kani", "proof"]` matches `#[kani::proof]` and `["doc"]` matches both
+    /// `#[doc = "..."]` and doc comments.
+    fn attrs_by_path(&self, attr: &[Symbol]) -> Vec<Attribute> {
+        let def_id = self.def_id();
+        with(|cx| cx.get_attrs_by_path(def_id, attr))
+    }
+
+    /// Return the documentation of this definition, made of its doc comments and
+    /// `#[doc = "..."]` attributes joined by newlines, or `None` if there are none.
+    fn documentation(&self) -> Option<String> {
+        let docs: Vec<_> = self.all_attrs().into_iter().filter_map(|attr| attr.doc).collect();
+        if docs.is_empty() {
+            None
+        } else {
+            Some(docs.join("\n"))
+        }
+    }
+}
+
+/// An attribute of a definition, such as `#[inline]`, `#[kani::unwind(5)]` or a doc comment.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attribute {
+    /// The path of the attribute, e.g. `["kani", "unwind"]`. Doc comments have the path `["doc"]`.
+    pub path: Vec<Symbol>,
+    /// The arguments of the attribute, e.g. `(5)` for `#[kani::unwind(5)]` or `= "text"` for
+    /// `#[doc = "text"]`, or an empty string if there are none.
+    pub tokens: String,
+    /// The text of a doc comment or of a `#[doc = "..."]` attribute, without the comment
+    /// markers.
+    pub doc: Option<String>,
+    /// The whole attribute, e.g. `#[kani::unwind(5)]` or `/// text`.
+    pub value: String,
+    pub span: Span,
+}
+
+impl Attribute {
+    /// Whether this attribute is a doc comment or a `#[doc = "..."]` attribute.
+    pub fn is_doc(&self) -> bool {
+        self.doc.is_some()
+    }
 }
 
 /// A trait that can be used to retrieve a definition's type.
@@ -75,6 +129,7 @@ macro_rules! crate_def {
     ) => {
         $(#[$attr])*
         #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         $vis struct $name(pub DefId);
 
         impl CrateDef for $name {
@@ -91,6 +146,7 @@ macro_rules! crate_def_with_ty {
     ) => {
         $(#[$attr])*
         #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         $vis struct $name(pub DefId);
 
         impl CrateDef for $name {
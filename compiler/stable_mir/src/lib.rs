@@ -35,6 +35,8 @@
 #[macro_use]
 pub mod error;
 pub mod mir;
+#[cfg(feature = "serde")]
+pub mod snapshot;
 pub mod target;
 pub mod ty;
 pub mod visitor;
@@ -75,6 +77,7 @@ fn to_index(&self) -> usize {
 
 /// Holds information about a crate.
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Crate {
     pub id: CrateNum,
     pub name: Symbol,
@@ -99,6 +102,7 @@ pub fn trait_impls(&self) -> ImplTraitDecls {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ItemKind {
     Fn,
     Static,
@@ -107,6 +111,7 @@ pub enum ItemKind {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CtorKind {
     Const,
     Fn,
@@ -127,6 +132,11 @@ pub fn body(&self) -> mir::Body {
         with(|cx| cx.mir_body(self.0))
     }
 
+    /// Check if a body is available for this item.
+    pub fn has_body(&self) -> bool {
+        with(|cx| cx.has_body(self.0))
+    }
+
     pub fn span(&self) -> Span {
         with(|cx| cx.span_of_an_item(self.0))
     }
@@ -189,6 +199,7 @@ pub fn all_trait_impls() -> ImplTraitDecls {
 
 /// A type that provides internal information but that can still be used for debug purpose.
 #[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Opaque(String);
 
 impl std::fmt::Display for Opaque {
@@ -1,8 +1,9 @@
 pub mod alloc;
 mod body;
+pub mod dataflow;
 pub mod mono;
 pub mod pretty;
 pub mod visit;
 
 pub use body::*;
-pub use visit::MirVisitor;
+pub use visit::{MirVisitor, MutMirVisitor};
@@ -0,0 +1,387 @@
+//! # A dataflow framework for Stable MIR bodies
+//!
+//! This module mirrors the dataflow framework used inside the compiler, but works on the
+//! `stable_mir` representation of a [`Body`]. An analysis is described by implementing the
+//! [`Analysis`] trait: its domain, its direction and the effect of each statement and
+//! terminator on a state. [`Analysis::iterate_to_fixpoint`] then computes the state on entry to
+//! each basic block, and the returned [`Results`] can be queried at any location of the body.
+//!
+//! The [`impls`] module contains the analyses of locals that the compiler itself uses, so tools
+//! get the same semantics as the compiler:
+//! - [`MaybeInitializedLocals`]: the locals that may hold a (partially) initialized value.
+//! - [`MaybeLiveLocals`]: the locals whose current value may be read later.
+//! - [`MaybeBorrowedLocals`]: the locals that may have a live reference or pointer to them.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::mir::{BasicBlockIdx, Body, Place, Statement, Terminator, TerminatorKind};
+
+pub mod impls;
+
+pub use impls::{MaybeBorrowedLocals, MaybeInitializedLocals, MaybeLiveLocals};
+
+/// The block where the execution of every body starts.
+pub const START_BLOCK: BasicBlockIdx = 0;
+
+/// The direction in which the state of an analysis flows through the body.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// From the start block to the return, e.g. initializedness.
+    Forward,
+    /// From the return to the start block, e.g. liveness.
+    Backward,
+}
+
+/// A statement in a body, or its terminator when `statement_index` is the number of statements
+/// of the block.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BodyLocation {
+    pub block: BasicBlockIdx,
+    pub statement_index: usize,
+}
+
+impl BodyLocation {
+    /// The location of the terminator of `block`.
+    pub fn terminator(body: &Body, block: BasicBlockIdx) -> BodyLocation {
+        BodyLocation { block, statement_index: body.blocks[block].statements.len() }
+    }
+}
+
+/// A domain whose states can be merged where control flow joins.
+pub trait JoinSemiLattice: Eq {
+    /// Computes the least upper bound of `self` and `other` into `self`, and returns whether
+    /// `self` changed.
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+/// A dataflow analysis over a Stable MIR body.
+pub trait Analysis {
+    /// The type of the state computed at each location.
+    type Domain: Clone + JoinSemiLattice;
+
+    const DIRECTION: Direction;
+
+    /// The initial state of every block, which must be the bottom of the lattice.
+    fn bottom_value(&self, body: &Body) -> Self::Domain;
+
+    /// Mutates the state on entry to the start block of the body. This is only used by forward
+    /// analyses, backward analyses start from the bottom value on every exit.
+    fn initialize_start_block(&self, body: &Body, state: &mut Self::Domain);
+
+    /// Applies the effect of `statement` to `state`.
+    fn apply_statement_effect(
+        &mut self,
+        state: &mut Self::Domain,
+        statement: &Statement,
+        location: BodyLocation,
+    );
+
+    /// Applies the effect of `terminator` to `state`. This effect applies to all the successors
+    /// of the terminator.
+    fn apply_terminator_effect(
+        &mut self,
+        state: &mut Self::Domain,
+        terminator: &Terminator,
+        location: BodyLocation,
+    );
+
+    /// Applies the effect of a successful return from the call at `location`, which writes to
+    /// `destination`. This effect only applies to the return edge of the call and not to its
+    /// unwind edge.
+    fn apply_call_return_effect(
+        &mut self,
+        state: &mut Self::Domain,
+        destination: &Place,
+        location: BodyLocation,
+    ) {
+        let _ = (state, destination, location);
+    }
+
+    /// Runs this analysis on `body` until it reaches a fixpoint.
+    fn iterate_to_fixpoint(self, body: &Body) -> Results<Self>
+    where
+        Self: Sized,
+    {
+        iterate_to_fixpoint(self, body)
+    }
+}
+
+/// The fixpoint of an analysis, which gives the state of the analysis at any location of the
+/// body it was computed for.
+pub struct Results<A: Analysis> {
+    analysis: A,
+    entry_states: Vec<A::Domain>,
+}
+
+impl<A: Analysis> Results<A> {
+    pub fn analysis(&self) -> &A {
+        &self.analysis
+    }
+
+    pub fn into_analysis(self) -> A {
+        self.analysis
+    }
+
+    /// The state on entry to `block` in the direction of the analysis: before its first
+    /// statement for forward analyses, and after its terminator for backward ones.
+    pub fn entry_state(&self, block: BasicBlockIdx) -> &A::Domain {
+        &self.entry_states[block]
+    }
+
+    /// The state right before the statement or terminator at `location` executes.
+    ///
+    /// `body` must be the body the analysis ran on.
+    pub fn state_before(&mut self, body: &Body, location: BodyLocation) -> A::Domain {
+        match A::DIRECTION {
+            Direction::Forward => {
+                self.state_forward(body, location.block, location.statement_index)
+            }
+            Direction::Backward => {
+                self.state_backward(body, location.block, location.statement_index)
+            }
+        }
+    }
+
+    /// The state right after the statement or terminator at `location` executes.
+    ///
+    /// For a call, this does not include the effect on its destination, as it is only written
+    /// when the call returns.
+    ///
+    /// `body` must be the body the analysis ran on.
+    pub fn state_after(&mut self, body: &Body, location: BodyLocation) -> A::Domain {
+        match A::DIRECTION {
+            Direction::Forward => {
+                self.state_forward(body, location.block, location.statement_index + 1)
+            }
+            Direction::Backward => {
+                self.state_backward(body, location.block, location.statement_index + 1)
+            }
+        }
+    }
+
+    /// Applies the effects of the first `count` statements and terminator of `block`.
+    fn state_forward(&mut self, body: &Body, block: BasicBlockIdx, count: usize) -> A::Domain {
+        let mut state = self.entry_states[block].clone();
+        for statement_index in 0..count {
+            apply_effect(
+                &mut self.analysis,
+                &mut state,
+                body,
+                BodyLocation { block, statement_index },
+            );
+        }
+        state
+    }
+
+    /// Applies the effects of the terminator and statements of `block`, backwards down to the
+    /// one at index `first`.
+    fn state_backward(&mut self, body: &Body, block: BasicBlockIdx, first: usize) -> A::Domain {
+        let mut state = self.entry_states[block].clone();
+        for statement_index in (first..=body.blocks[block].statements.len()).rev() {
+            apply_effect(
+                &mut self.analysis,
+                &mut state,
+                body,
+                BodyLocation { block, statement_index },
+            );
+        }
+        state
+    }
+}
+
+fn apply_effect<A: Analysis>(
+    analysis: &mut A,
+    state: &mut A::Domain,
+    body: &Body,
+    location: BodyLocation,
+) {
+    let block = &body.blocks[location.block];
+    match block.statements.get(location.statement_index) {
+        Some(statement) => analysis.apply_statement_effect(state, statement, location),
+        None => analysis.apply_terminator_effect(state, &block.terminator, location),
+    }
+}
+
+/// Returns the destination of the call terminating `block` if it returns to `target`.
+fn call_return_to(body: &Body, block: BasicBlockIdx, target: BasicBlockIdx) -> Option<&Place> {
+    match &body.blocks[block].terminator.kind {
+        TerminatorKind::Call { destination, target: Some(return_target), .. }
+            if *return_target == target =>
+        {
+            Some(destination)
+        }
+        _ => None,
+    }
+}
+
+fn iterate_to_fixpoint<A: Analysis>(mut analysis: A, body: &Body) -> Results<A> {
+    let num_blocks = body.blocks.len();
+    let mut entry_states = vec![analysis.bottom_value(body); num_blocks];
+    let mut queue = WorkQueue::new(num_blocks);
+    match A::DIRECTION {
+        Direction::Forward => {
+            if num_blocks > 0 {
+                analysis.initialize_start_block(body, &mut entry_states[START_BLOCK]);
+            }
+            (0..num_blocks).for_each(|block| queue.insert(block));
+        }
+        Direction::Backward => (0..num_blocks).rev().for_each(|block| queue.insert(block)),
+    }
+
+    let mut predecessors = vec![vec![]; num_blocks];
+    if A::DIRECTION == Direction::Backward {
+        for (block, data) in body.blocks.iter().enumerate() {
+            for successor in data.terminator.successors() {
+                predecessors[successor].push(block);
+            }
+        }
+    }
+
+    while let Some(block) = queue.pop() {
+        let data = &body.blocks[block];
+        let terminator = BodyLocation::terminator(body, block);
+        let mut state = entry_states[block].clone();
+        match A::DIRECTION {
+            Direction::Forward => {
+                for statement_index in 0..=data.statements.len() {
+                    let location = BodyLocation { block, statement_index };
+                    apply_effect(&mut analysis, &mut state, body, location);
+                }
+                for successor in data.terminator.successors() {
+                    let changed = match call_return_to(body, block, successor) {
+                        Some(destination) => {
+                            let mut returned = state.clone();
+                            analysis.apply_call_return_effect(
+                                &mut returned,
+                                destination,
+                                terminator,
+                            );
+                            entry_states[successor].join(&returned)
+                        }
+                        None => entry_states[successor].join(&state),
+                    };
+                    if changed {
+                        queue.insert(successor);
+                    }
+                }
+            }
+            Direction::Backward => {
+                for statement_index in (0..=data.statements.len()).rev() {
+                    let location = BodyLocation { block, statement_index };
+                    apply_effect(&mut analysis, &mut state, body, location);
+                }
+                for &predecessor in &predecessors[block] {
+                    let changed = match call_return_to(body, predecessor, block) {
+                        Some(destination) => {
+                            let mut returned = state.clone();
+                            let location = BodyLocation::terminator(body, predecessor);
+                            analysis.apply_call_return_effect(&mut returned, destination, location);
+                            entry_states[predecessor].join(&returned)
+                        }
+                        None => entry_states[predecessor].join(&state),
+                    };
+                    if changed {
+                        queue.insert(predecessor);
+                    }
+                }
+            }
+        }
+    }
+
+    Results { analysis, entry_states }
+}
+
+/// A queue of blocks that contains each block at most once.
+struct WorkQueue {
+    queue: VecDeque<BasicBlockIdx>,
+    queued: Vec<bool>,
+}
+
+impl WorkQueue {
+    fn new(num_blocks: usize) -> WorkQueue {
+        WorkQueue { queue: VecDeque::with_capacity(num_blocks), queued: vec![false; num_blocks] }
+    }
+
+    fn insert(&mut self, block: BasicBlockIdx) {
+        if !self.queued[block] {
+            self.queued[block] = true;
+            self.queue.push_back(block);
+        }
+    }
+
+    fn pop(&mut self) -> Option<BasicBlockIdx> {
+        let block = self.queue.pop_front()?;
+        self.queued[block] = false;
+        Some(block)
+    }
+}
+
+/// A fixed-size set of indices, such as locals, ordered by inclusion.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    domain_size: usize,
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// Creates an empty set that can contain the indices from `0` to `domain_size - 1`.
+    pub fn new_empty(domain_size: usize) -> BitSet {
+        BitSet { domain_size, words: vec![0; domain_size.div_ceil(64)] }
+    }
+
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        assert!(index < self.domain_size);
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// Inserts `index`, and returns whether it was not already in the set.
+    pub fn insert(&mut self, index: usize) -> bool {
+        assert!(index < self.domain_size);
+        let word = &mut self.words[index / 64];
+        let old = *word;
+        *word |= 1 << (index % 64);
+        *word != old
+    }
+
+    /// Removes `index`, and returns whether it was in the set.
+    pub fn remove(&mut self, index: usize) -> bool {
+        assert!(index < self.domain_size);
+        let word = &mut self.words[index / 64];
+        let old = *word;
+        *word &= !(1 << (index % 64));
+        *word != old
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&word| word == 0)
+    }
+
+    /// Iterates over the indices in the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.domain_size).filter(|&index| self.contains(index))
+    }
+}
+
+impl JoinSemiLattice for BitSet {
+    fn join(&mut self, other: &Self) -> bool {
+        assert_eq!(self.domain_size, other.domain_size);
+        let mut changed = false;
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            let old = *word;
+            *word |= other;
+            changed |= *word != old;
+        }
+        changed
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,293 @@
+//! Dataflow analyses of the locals of a body, with the same semantics as the compiler's.
+
+use crate::mir::dataflow::{Analysis, BitSet, BodyLocation, Direction};
+use crate::mir::visit::{Location, PlaceContext};
+use crate::mir::{
+    Body, BorrowKind, Local, MirVisitor, NonDivergingIntrinsic, Operand, Place, ProjectionElem,
+    Rvalue, Statement, StatementKind, Terminator, TerminatorKind,
+};
+
+/// Whether `place` is behind a pointer, so writing to it does not write to its local.
+fn is_indirect(place: &Place) -> bool {
+    place.projection.iter().any(|elem| matches!(elem, ProjectionElem::Deref))
+}
+
+/// Removes the locals that are moved out of entirely by the visited operands.
+struct MoveVisitor<'a>(&'a mut BitSet);
+
+impl MirVisitor for MoveVisitor<'_> {
+    fn visit_operand(&mut self, operand: &Operand, _location: Location) {
+        if let Operand::Move(place) = operand {
+            if place.projection.is_empty() {
+                self.0.remove(place.local);
+            }
+        }
+    }
+}
+
+/// A local is maybe-initialized at a location if it may hold a value, or part of a value, on
+/// some path that reaches the location.
+///
+/// Arguments are initialized on entry. A local becomes initialized when it is assigned, even
+/// partially, and uninitialized when it is moved out of entirely, dropped, deinitialized or when
+/// its storage is marked live or dead.
+pub struct MaybeInitializedLocals;
+
+impl Analysis for MaybeInitializedLocals {
+    type Domain = BitSet;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom_value(&self, body: &Body) -> BitSet {
+        BitSet::new_empty(body.locals().len())
+    }
+
+    fn initialize_start_block(&self, body: &Body, state: &mut BitSet) {
+        for arg in 1..=body.arg_locals().len() {
+            state.insert(arg);
+        }
+    }
+
+    fn apply_statement_effect(
+        &mut self,
+        state: &mut BitSet,
+        statement: &Statement,
+        _location: BodyLocation,
+    ) {
+        match &statement.kind {
+            StatementKind::Assign(place, rvalue) => {
+                MoveVisitor(state).visit_rvalue(rvalue, Location(statement.span));
+                if !is_indirect(place) {
+                    state.insert(place.local);
+                }
+            }
+            StatementKind::Deinit(place) if place.projection.is_empty() => {
+                state.remove(place.local);
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                state.remove(*local);
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &mut self,
+        state: &mut BitSet,
+        terminator: &Terminator,
+        _location: BodyLocation,
+    ) {
+        MoveVisitor(state).visit_terminator(terminator, Location(terminator.span));
+        match &terminator.kind {
+            TerminatorKind::Drop { place, .. } if place.projection.is_empty() => {
+                state.remove(place.local);
+            }
+            TerminatorKind::InlineAsm { operands, .. } => {
+                for place in operands.iter().filter_map(|operand| operand.out_place.as_ref()) {
+                    if !is_indirect(place) {
+                        state.insert(place.local);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_call_return_effect(
+        &mut self,
+        state: &mut BitSet,
+        destination: &Place,
+        _location: BodyLocation,
+    ) {
+        if !is_indirect(destination) {
+            state.insert(destination.local);
+        }
+    }
+}
+
+/// A local is maybe-live at a location if its current value may be read later.
+///
+/// Like in the compiler, writing to a whole local is a definition that kills it, writing through
+/// a pointer stored in a local is a use of that local, and writing to a field of a local is
+/// neither. Every other mention of a local, including borrowing it, is a use.
+pub struct MaybeLiveLocals;
+
+impl MaybeLiveLocals {
+    /// Applies the effect of writing to `place`.
+    fn def(state: &mut BitSet, place: &Place) {
+        if place.projection.is_empty() {
+            state.remove(place.local);
+        } else if is_indirect(place) {
+            state.insert(place.local);
+        }
+        Self::index_uses(state, place);
+    }
+
+    /// Marks the locals used to index into `place` as used.
+    fn index_uses(state: &mut BitSet, place: &Place) {
+        for elem in &place.projection {
+            if let ProjectionElem::Index(local) = elem {
+                state.insert(*local);
+            }
+        }
+    }
+}
+
+/// Marks every local that is visited as used.
+struct UseVisitor<'a>(&'a mut BitSet);
+
+impl MirVisitor for UseVisitor<'_> {
+    fn visit_local(&mut self, local: &Local, _ptx: PlaceContext, _location: Location) {
+        self.0.insert(*local);
+    }
+}
+
+impl Analysis for MaybeLiveLocals {
+    type Domain = BitSet;
+
+    const DIRECTION: Direction = Direction::Backward;
+
+    fn bottom_value(&self, body: &Body) -> BitSet {
+        BitSet::new_empty(body.locals().len())
+    }
+
+    fn initialize_start_block(&self, _body: &Body, _state: &mut BitSet) {}
+
+    fn apply_statement_effect(
+        &mut self,
+        state: &mut BitSet,
+        statement: &Statement,
+        _location: BodyLocation,
+    ) {
+        let location = Location(statement.span);
+        match &statement.kind {
+            StatementKind::Assign(place, rvalue) => {
+                Self::def(state, place);
+                UseVisitor(state).visit_rvalue(rvalue, location);
+            }
+            StatementKind::Deinit(place) => Self::def(state, place),
+            StatementKind::SetDiscriminant { place, .. } => {
+                if is_indirect(place) {
+                    state.insert(place.local);
+                }
+                Self::index_uses(state, place);
+            }
+            // Like in rustc's liveness, `let _ = x;` is a use of `x`.
+            StatementKind::FakeRead(_, place)
+            | StatementKind::Retag(_, place)
+            | StatementKind::PlaceMention(place) => {
+                UseVisitor(state).visit_place(place, PlaceContext::NON_MUTATING, location);
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(operand)) => {
+                UseVisitor(state).visit_operand(operand, location);
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping(copy)) => {
+                let mut uses = UseVisitor(state);
+                uses.visit_operand(&copy.src, location);
+                uses.visit_operand(&copy.dst, location);
+                uses.visit_operand(&copy.count, location);
+            }
+            // Storage markers and type annotations do not use the places they name.
+            StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Coverage(_)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &mut self,
+        state: &mut BitSet,
+        terminator: &Terminator,
+        _location: BodyLocation,
+    ) {
+        let location = Location(terminator.span);
+        match &terminator.kind {
+            // The destination is written on the return edge only.
+            TerminatorKind::Call { func, args, .. } => {
+                let mut uses = UseVisitor(state);
+                uses.visit_operand(func, location);
+                for arg in args {
+                    uses.visit_operand(arg, location);
+                }
+            }
+            TerminatorKind::InlineAsm { operands, .. } => {
+                for operand in operands {
+                    if let Some(place) = &operand.out_place {
+                        Self::def(state, place);
+                    }
+                }
+                for operand in operands {
+                    if let Some(input) = &operand.in_value {
+                        UseVisitor(state).visit_operand(input, location);
+                    }
+                }
+            }
+            _ => UseVisitor(state).visit_terminator(terminator, location),
+        }
+    }
+
+    fn apply_call_return_effect(
+        &mut self,
+        state: &mut BitSet,
+        destination: &Place,
+        _location: BodyLocation,
+    ) {
+        Self::def(state, destination);
+    }
+}
+
+/// A local is maybe-borrowed at a location if a reference or a raw pointer to it, or to a part
+/// of it, may have been created on some path that reaches the location, without its storage
+/// being marked dead since.
+///
+/// Dropping a local borrows it mutably. Fake borrows, which only exist for the borrow checker,
+/// are ignored.
+pub struct MaybeBorrowedLocals;
+
+impl Analysis for MaybeBorrowedLocals {
+    type Domain = BitSet;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom_value(&self, body: &Body) -> BitSet {
+        BitSet::new_empty(body.locals().len())
+    }
+
+    fn initialize_start_block(&self, _body: &Body, _state: &mut BitSet) {}
+
+    fn apply_statement_effect(
+        &mut self,
+        state: &mut BitSet,
+        statement: &Statement,
+        _location: BodyLocation,
+    ) {
+        match &statement.kind {
+            StatementKind::Assign(_, Rvalue::Ref(_, BorrowKind::Fake(_), _)) => {}
+            StatementKind::Assign(_, Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place)) => {
+                if !is_indirect(place) {
+                    state.insert(place.local);
+                }
+            }
+            StatementKind::StorageDead(local) => {
+                state.remove(*local);
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_terminator_effect(
+        &mut self,
+        state: &mut BitSet,
+        terminator: &Terminator,
+        _location: BodyLocation,
+    ) {
+        if let TerminatorKind::Drop { place, .. } = &terminator.kind {
+            if !is_indirect(place) {
+                state.insert(place.local);
+            }
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Serializable snapshots of the stable MIR of a crate.
+//!
+//! A [`CrateSnapshot`] contains everything needed to analyze a crate without the compiler: the
+//! local items and their bodies, the instances reachable from them, and tables that resolve the
+//! handles that appear in those, such as types, layouts, definitions and spans.
+//!
+//! Snapshots can be written as JSON or CBOR. Both formats have the same structure, which is
+//! versioned by [`SNAPSHOT_FORMAT_VERSION`]. JSON snapshots can be read back with
+//! [`CrateSnapshot::read_json`].
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::ops::ControlFlow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::abi::{Layout, LayoutShape};
+use crate::mir::mono::Instance;
+use crate::mir::visit::Location;
+use crate::mir::{
+    Body, CastKind, MirVisitor, Operand, PointerCoercion, Rvalue, Terminator, TerminatorKind,
+};
+use crate::ty::{
+    IndexedVal, LineInfo, Region, RegionKind, RigidTy, Span, Ty, TyConst, TyConstKind, TyKind,
+};
+use crate::visitor::{Visitable, Visitor};
+use crate::{with, CrateDef, CrateItem, DefId, Filename, ItemKind, Symbol};
+
+mod cbor;
+
+/// The version of the snapshot format. It is increased on every change to the structure of the
+/// snapshot, including changes to the stable MIR types it contains.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// A snapshot of the stable MIR of the local crate.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CrateSnapshot {
+    pub format_version: u32,
+    pub crate_name: Symbol,
+    /// The item where execution starts, if any.
+    pub entry: Option<CrateItem>,
+    pub items: Vec<ItemSnapshot>,
+    pub instances: Vec<InstanceSnapshot>,
+    pub types: Vec<TySnapshot>,
+    pub layouts: Vec<LayoutSnapshot>,
+    pub defs: Vec<DefSnapshot>,
+    pub spans: Vec<SpanSnapshot>,
+}
+
+/// A local item, with its polymorphic body.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemSnapshot {
+    pub item: CrateItem,
+    pub name: Symbol,
+    pub kind: ItemKind,
+    pub ty: Ty,
+    pub span: Span,
+    pub body: Option<Body>,
+}
+
+/// An instance reachable from the local items, with its monomorphic body.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct InstanceSnapshot {
+    pub instance: Instance,
+    pub name: Symbol,
+    pub mangled_name: Symbol,
+    pub ty: Ty,
+    pub body: Option<Body>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TySnapshot {
+    pub id: Ty,
+    pub kind: TyKind,
+    /// The layout of the type, for types that do not depend on generic parameters.
+    pub layout: Option<Layout>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LayoutSnapshot {
+    pub id: Layout,
+    pub shape: LayoutShape,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DefSnapshot {
+    pub id: DefId,
+    pub name: Symbol,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SpanSnapshot {
+    pub id: Span,
+    pub filename: Filename,
+    pub lines: LineInfo,
+}
+
+impl CrateSnapshot {
+    /// Takes a snapshot of the local crate.
+    ///
+    /// The instances are the non-generic local functions, and the ones they call, drop or turn
+    /// into function pointers, transitively. This approximates the set of functions that are
+    /// code generated for the crate.
+    pub fn collect() -> CrateSnapshot {
+        let mut collector = Collector::default();
+
+        let items: Vec<_> = crate::all_local_items()
+            .into_iter()
+            .map(|item| {
+                let body = item.has_body().then(|| item.body());
+                if let Some(body) = &body {
+                    collector.visit_body(body);
+                }
+                let ty = item.ty();
+                collector.add_ty(ty);
+                let span = item.span();
+                collector.spans.insert(span);
+                ItemSnapshot { item, name: item.name(), kind: item.kind(), ty, span, body }
+            })
+            .collect();
+
+        let roots = items
+            .iter()
+            .filter(|item| item.kind == ItemKind::Fn)
+            .filter_map(|item| Instance::try_from(item.item).ok());
+        let instances = collector.collect_instances(roots);
+
+        collector.close_types();
+        let mut layouts = HashMap::new();
+        let mut types: Vec<_> = collector
+            .types
+            .iter()
+            .map(|ty| {
+                let layout = if is_monomorphic(*ty) { ty.layout().ok() } else { None };
+                if let Some(layout) = layout {
+                    layouts.entry(layout).or_insert_with(|| layout.shape());
+                }
+                TySnapshot { id: *ty, kind: ty.kind(), layout }
+            })
+            .collect();
+        types.sort_by_key(|ty| ty.id.to_index());
+        let mut layouts: Vec<_> =
+            layouts.into_iter().map(|(id, shape)| LayoutSnapshot { id, shape }).collect();
+        layouts.sort_by_key(|layout| layout.id.to_index());
+
+        let mut defs: Vec<_> = types
+            .iter()
+            .filter_map(|ty| rigid_def_id(&ty.kind))
+            .chain(items.iter().map(|item| item.item.def_id()))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .map(|id| DefSnapshot { id, name: with(|cx| cx.def_name(id, false)) })
+            .collect();
+        defs.sort_by_key(|def| def.id.to_index());
+        let mut spans: Vec<_> = collector
+            .spans
+            .into_iter()
+            .map(|id| SpanSnapshot { id, filename: id.get_filename(), lines: id.get_lines() })
+            .collect();
+        spans.sort_by_key(|span| span.id.to_index());
+
+        CrateSnapshot {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            crate_name: crate::local_crate().name,
+            entry: crate::entry_fn(),
+            items,
+            instances,
+            types,
+            layouts,
+            defs,
+            spans,
+        }
+    }
+
+    /// Writes the snapshot as pretty-printed JSON.
+    pub fn write_json<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(io::Error::from)
+    }
+
+    /// Reads a snapshot that was written by [`CrateSnapshot::write_json`].
+    ///
+    /// Fails if the snapshot was written with a different [`SNAPSHOT_FORMAT_VERSION`].
+    pub fn read_json<R: io::Read>(reader: R) -> io::Result<CrateSnapshot> {
+        let snapshot: CrateSnapshot = serde_json::from_reader(reader)?;
+        if snapshot.format_version != SNAPSHOT_FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "snapshot format version {} is not supported, expected {}",
+                    snapshot.format_version, SNAPSHOT_FORMAT_VERSION
+                ),
+            ));
+        }
+        Ok(snapshot)
+    }
+
+    /// Writes the snapshot as CBOR.
+    pub fn write_cbor<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        cbor::to_writer(writer, self)
+    }
+}
+
+/// Collects the handles that appear in the snapshot, and the reachable instances.
+#[derive(Default)]
+struct Collector {
+    types: HashSet<Ty>,
+    spans: HashSet<Span>,
+    /// Instances that are referenced from the bodies visited so far.
+    callees: Vec<Instance>,
+}
+
+impl Collector {
+    fn add_ty(&mut self, ty: Ty) {
+        self.types.insert(ty);
+    }
+
+    fn visit_body(&mut self, body: &Body) {
+        BodyVisitor { body, collector: self }.visit_body(body);
+    }
+
+    /// Adds the types that appear inside of the collected types.
+    fn close_types(&mut self) {
+        let mut nested = NestedTypes { types: HashSet::new() };
+        for ty in &self.types {
+            let _ = ty.super_visit(&mut nested);
+        }
+        self.types.extend(nested.types);
+    }
+
+    fn collect_instances(
+        &mut self,
+        roots: impl Iterator<Item = Instance>,
+    ) -> Vec<InstanceSnapshot> {
+        let mut seen = HashSet::new();
+        let mut worklist: Vec<_> = roots.collect();
+        let mut instances = vec![];
+        while let Some(instance) = worklist.pop() {
+            if !seen.insert(instance) {
+                continue;
+            }
+            let body = instance.body();
+            if let Some(body) = &body {
+                self.visit_body(body);
+                worklist.append(&mut self.callees);
+            }
+            let ty = instance.ty();
+            self.add_ty(ty);
+            instances.push(InstanceSnapshot {
+                instance,
+                name: instance.name(),
+                mangled_name: instance.mangled_name(),
+                ty,
+                body,
+            });
+        }
+        instances
+    }
+
+    /// Records the instance that a function operand refers to, if it is a function item.
+    fn add_callee(&mut self, func: &Operand, body_locals: &[crate::mir::LocalDecl]) {
+        let Ok(ty) = func.ty(body_locals) else { return };
+        if let TyKind::RigidTy(RigidTy::FnDef(def, args)) = ty.kind() {
+            if let Ok(instance) = Instance::resolve(def, &args) {
+                self.callees.push(instance);
+            }
+        }
+    }
+}
+
+/// Collects the handles and the callees of a body.
+struct BodyVisitor<'a> {
+    body: &'a Body,
+    collector: &'a mut Collector,
+}
+
+impl<'a> MirVisitor for BodyVisitor<'a> {
+    fn visit_terminator(&mut self, term: &Terminator, location: Location) {
+        match &term.kind {
+            TerminatorKind::Call { func, .. } => {
+                self.collector.add_callee(func, self.body.locals())
+            }
+            TerminatorKind::Drop { place, .. } => {
+                if let Ok(ty) = place.ty(self.body.locals()) {
+                    let instance = Instance::resolve_drop_in_place(ty);
+                    if !instance.is_empty_shim() {
+                        self.collector.callees.push(instance);
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.super_terminator(term, location);
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &Rvalue, location: Location) {
+        if let Rvalue::Cast(
+            CastKind::PointerCoercion(PointerCoercion::ReifyFnPointer),
+            operand,
+            _,
+        ) = rvalue
+        {
+            if let Ok(ty) = operand.ty(self.body.locals()) {
+                if let TyKind::RigidTy(RigidTy::FnDef(def, args)) = ty.kind() {
+                    if let Ok(instance) = Instance::resolve_for_fn_ptr(def, &args) {
+                        self.collector.callees.push(instance);
+                    }
+                }
+            }
+        }
+        self.super_rvalue(rvalue, location);
+    }
+
+    fn visit_ty(&mut self, ty: &Ty, _location: Location) {
+        self.collector.add_ty(*ty);
+    }
+
+    fn visit_span(&mut self, span: &Span) {
+        self.collector.spans.insert(*span);
+    }
+}
+
+struct NestedTypes {
+    types: HashSet<Ty>,
+}
+
+impl Visitor for NestedTypes {
+    type Break = ();
+
+    fn visit_ty(&mut self, ty: &Ty) -> ControlFlow<()> {
+        if self.types.insert(*ty) {
+            ty.super_visit(self)
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// Finds the generic parameters and bound variables in a type.
+struct GenericFinder;
+
+impl Visitor for GenericFinder {
+    type Break = ();
+
+    fn visit_ty(&mut self, ty: &Ty) -> ControlFlow<()> {
+        match ty.kind() {
+            TyKind::Param(_) | TyKind::Bound(..) => ControlFlow::Break(()),
+            _ => ty.super_visit(self),
+        }
+    }
+
+    fn visit_const(&mut self, c: &TyConst) -> ControlFlow<()> {
+        match c.kind() {
+            TyConstKind::Param(_) | TyConstKind::Bound(..) => ControlFlow::Break(()),
+            _ => c.super_visit(self),
+        }
+    }
+
+    fn visit_reg(&mut self, reg: &Region) -> ControlFlow<()> {
+        match reg.kind {
+            RegionKind::ReBound(..) => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// Whether the layout of `ty` can be computed, i.e., whether it does not depend on generic
+/// parameters or bound variables.
+fn is_monomorphic(ty: Ty) -> bool {
+    ty.visit(&mut GenericFinder).is_continue()
+}
+
+fn rigid_def_id(kind: &TyKind) -> Option<DefId> {
+    match kind {
+        TyKind::RigidTy(RigidTy::Adt(def, _)) => Some(def.def_id()),
+        TyKind::RigidTy(RigidTy::Foreign(def)) => Some(def.def_id()),
+        TyKind::RigidTy(RigidTy::FnDef(def, _)) => Some(def.def_id()),
+        TyKind::RigidTy(RigidTy::Closure(def, _)) => Some(def.def_id()),
+        TyKind::RigidTy(RigidTy::Coroutine(def, ..)) => Some(def.def_id()),
+        TyKind::RigidTy(RigidTy::CoroutineWitness(def, _)) => Some(def.def_id()),
+        _ => None,
+    }
+}
@@ -0,0 +1,412 @@
+//! A minimal [CBOR](https://www.rfc-editor.org/rfc/rfc8949) serializer.
+//!
+//! Values are laid out like `serde_json` does, so that both formats of a snapshot have the same
+//! structure: structs are maps keyed by field name, and enum variants that carry data are maps
+//! with a single entry keyed by the variant name.
+
+use std::fmt::Display;
+use std::io;
+
+use serde::ser::{self, Serialize};
+
+/// Serializes `value` as CBOR into `writer`.
+pub(super) fn to_writer<W: io::Write, T: Serialize + ?Sized>(
+    writer: W,
+    value: &T,
+) -> io::Result<()> {
+    value.serialize(&mut Serializer { writer }).map_err(|Error(err)| err)
+}
+
+#[derive(Debug)]
+struct Error(io::Error);
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error(io::Error::new(io::ErrorKind::Other, msg.to_string()))
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error(err)
+    }
+}
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+const FALSE: u8 = 20;
+const TRUE: u8 = 21;
+const NULL: u8 = 22;
+const FLOAT64: u8 = 27;
+/// The additional information of indefinite-length items, and of the "break" stop code.
+const INDEFINITE: u8 = 31;
+
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+
+struct Serializer<W> {
+    writer: W,
+}
+
+impl<W: io::Write> Serializer<W> {
+    fn write_head(&mut self, major: u8, value: u64) -> Result<(), Error> {
+        let major = major << 5;
+        match value {
+            0..=23 => self.writer.write_all(&[major | value as u8])?,
+            24..=0xff => self.writer.write_all(&[major | 24, value as u8])?,
+            0x100..=0xffff => {
+                self.writer.write_all(&[major | 25])?;
+                self.writer.write_all(&(value as u16).to_be_bytes())?;
+            }
+            0x1_0000..=0xffff_ffff => {
+                self.writer.write_all(&[major | 26])?;
+                self.writer.write_all(&(value as u32).to_be_bytes())?;
+            }
+            _ => {
+                self.writer.write_all(&[major | 27])?;
+                self.writer.write_all(&value.to_be_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_len(&mut self, major: u8, len: Option<usize>) -> Result<(), Error> {
+        match len {
+            Some(len) => self.write_head(major, len as u64),
+            None => Ok(self.writer.write_all(&[major << 5 | INDEFINITE])?),
+        }
+    }
+
+    fn write_break(&mut self, len: Option<usize>) -> Result<(), Error> {
+        if len.is_none() {
+            self.writer.write_all(&[SIMPLE << 5 | INDEFINITE])?;
+        }
+        Ok(())
+    }
+
+    fn write_text(&mut self, text: &str) -> Result<(), Error> {
+        self.write_head(TEXT, text.len() as u64)?;
+        Ok(self.writer.write_all(text.as_bytes())?)
+    }
+
+    /// Starts a map with a single entry keyed by `variant`, for enum variants with data.
+    fn start_variant(&mut self, variant: &str) -> Result<(), Error> {
+        self.write_head(MAP, 1)?;
+        self.write_text(variant)
+    }
+}
+
+impl<'a, W: io::Write> ser::Serializer for &'a mut Serializer<W> {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'a, W>;
+    type SerializeTuple = Compound<'a, W>;
+    type SerializeTupleStruct = Compound<'a, W>;
+    type SerializeTupleVariant = Compound<'a, W>;
+    type SerializeMap = Compound<'a, W>;
+    type SerializeStruct = Compound<'a, W>;
+    type SerializeStructVariant = Compound<'a, W>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        Ok(self.writer.write_all(&[SIMPLE << 5 | if v { TRUE } else { FALSE }])?)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        self.serialize_i128(v.into())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Error> {
+        // Negative integers are encoded as `-1 - n`.
+        let (major, magnitude) = if v < 0 { (NEGATIVE, !v as u128) } else { (UNSIGNED, v as u128) };
+        match u64::try_from(magnitude) {
+            Ok(magnitude) => self.write_head(major, magnitude),
+            Err(_) => {
+                let tag = if v < 0 { TAG_NEGATIVE_BIGNUM } else { TAG_POSITIVE_BIGNUM };
+                self.write_head(TAG, tag)?;
+                let bytes = magnitude.to_be_bytes();
+                let first = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+                self.serialize_bytes(&bytes[first..])
+            }
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.write_head(UNSIGNED, v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Error> {
+        match u64::try_from(v) {
+            Ok(v) => self.serialize_u64(v),
+            Err(_) => {
+                self.write_head(TAG, TAG_POSITIVE_BIGNUM)?;
+                let bytes = v.to_be_bytes();
+                let first = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+                self.serialize_bytes(&bytes[first..])
+            }
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.writer.write_all(&[SIMPLE << 5 | FLOAT64])?;
+        Ok(self.writer.write_all(&v.to_be_bytes())?)
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.write_text(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.write_text(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.write_head(BYTES, v.len() as u64)?;
+        Ok(self.writer.write_all(v)?)
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        Ok(self.writer.write_all(&[SIMPLE << 5 | NULL])?)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        self.write_text(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.start_variant(variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a, W>, Error> {
+        self.write_len(ARRAY, len)?;
+        Ok(Compound { ser: self, len })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a, W>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.start_variant(variant)?;
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a, W>, Error> {
+        self.write_len(MAP, len)?;
+        Ok(Compound { ser: self, len })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Compound<'a, W>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a, W>, Error> {
+        self.start_variant(variant)?;
+        self.serialize_map(Some(len))
+    }
+}
+
+/// Serializes the elements of an array or of a map.
+struct Compound<'a, W> {
+    ser: &'a mut Serializer<W>,
+    /// The length of the array or map, or `None` if it is encoded as an indefinite-length item.
+    len: Option<usize>,
+}
+
+impl<'a, W: io::Write> ser::SerializeSeq for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.ser.write_break(self.len)
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeTuple for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeTupleStruct for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeTupleVariant for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeMap for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        key.serialize(&mut *self.ser)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.ser.write_break(self.len)
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeStruct for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.ser.write_text(key)?;
+        value.serialize(&mut *self.ser)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeMap::end(self)
+    }
+}
+
+impl<'a, W: io::Write> ser::SerializeStructVariant for Compound<'a, W> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        ser::SerializeStruct::serialize_field(self, key, value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        ser::SerializeMap::end(self)
+    }
+}
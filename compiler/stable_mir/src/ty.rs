@@ -12,6 +12,7 @@
 use std::ops::Range;
 
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ty(usize);
 
 impl Debug for Ty {
@@ -101,12 +102,14 @@ pub fn kind(&self) -> TyKind {
 
 /// Represents a pattern in the type system
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pattern {
     Range { start: Option<TyConst>, end: Option<TyConst>, include_end: bool },
 }
 
 /// Represents a constant in the type system
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TyConst {
     pub(crate) kind: TyConstKind,
     pub id: TyConstId,
@@ -134,6 +137,7 @@ pub fn eval_target_usize(&self) -> Result<u64, Error> {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TyConstKind {
     Param(ParamConst),
     Bound(DebruijnIndex, BoundVar),
@@ -145,10 +149,12 @@ pub enum TyConstKind {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TyConstId(usize);
 
 /// Represents a constant in MIR
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MirConst {
     /// The constant kind.
     pub(crate) kind: ConstantKind,
@@ -206,16 +212,19 @@ pub fn try_from_uint(value: u128, uint_ty: UintTy) -> Result<MirConst, Error> {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MirConstId(usize);
 
 type Ident = Opaque;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Region {
     pub kind: RegionKind,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RegionKind {
     ReEarlyParam(EarlyParamRegion),
     ReBound(DebruijnIndex, BoundRegion),
@@ -227,6 +236,7 @@ pub enum RegionKind {
 pub(crate) type DebruijnIndex = u32;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EarlyParamRegion {
     pub index: u32,
     pub name: Symbol,
@@ -235,6 +245,7 @@ pub struct EarlyParamRegion {
 pub(crate) type BoundVar = u32;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoundRegion {
     pub var: BoundVar,
     pub kind: BoundRegionKind,
@@ -243,12 +254,14 @@ pub struct BoundRegion {
 pub(crate) type UniverseIndex = u32;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Placeholder<T> {
     pub universe: UniverseIndex,
     pub bound: T,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Span(usize);
 
 impl Debug for Span {
@@ -273,6 +286,7 @@ pub fn get_lines(&self) -> LineInfo {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Information you get from `Span` in a struct form.
 /// Line and col start from 1.
 pub struct LineInfo {
@@ -283,6 +297,7 @@ pub struct LineInfo {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TyKind {
     RigidTy(RigidTy),
     Alias(AliasKind, AliasTy),
@@ -522,6 +537,7 @@ pub struct TypeAndMut {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RigidTy {
     Bool,
     Char,
@@ -561,6 +577,7 @@ fn from(value: RigidTy) -> Self {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IntTy {
     Isize,
     I8,
@@ -584,6 +601,7 @@ pub fn num_bytes(self) -> usize {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UintTy {
     Usize,
     U8,
@@ -607,12 +625,14 @@ pub fn num_bytes(self) -> usize {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FloatTy {
     F32,
     F64,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Movability {
     Static,
     Movable,
@@ -651,6 +671,7 @@ pub fn kind(&self) -> ForeignItemKind {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ForeignItemKind {
     Fn(FnDef),
     Static(StaticDef),
@@ -736,6 +757,7 @@ fn from(def: IntrinsicDef) -> Self {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AdtKind {
     Enum,
     Union,
@@ -790,6 +812,7 @@ pub fn variant(&self, idx: VariantIdx) -> Option<VariantDef> {
 
 /// Definition of a variant, which can be either a struct / union field or an enum variant.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VariantDef {
     /// The variant index.
     ///
@@ -819,6 +842,7 @@ pub fn fields(&self) -> Vec<FieldDef> {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FieldDef {
     /// The field definition.
     ///
@@ -913,6 +937,7 @@ pub fn trait_impl(&self) -> ImplTrait {
 
 /// A list of generic arguments.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenericArgs(pub Vec<GenericArgKind>);
 
 impl std::ops::Index<ParamTy> for GenericArgs {
@@ -932,6 +957,7 @@ fn index(&self, index: ParamConst) -> &Self::Output {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GenericArgKind {
     Lifetime(Region),
     Type(Ty),
@@ -969,12 +995,14 @@ pub fn ty(&self) -> Option<&Ty> {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TermKind {
     Type(Ty),
     Const(TyConst),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AliasKind {
     Projection,
     Inherent,
@@ -983,12 +1011,14 @@ pub enum AliasKind {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AliasTy {
     pub def_id: AliasDef,
     pub args: GenericArgs,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AliasTerm {
     pub def_id: AliasDef,
     pub args: GenericArgs,
@@ -1007,6 +1037,7 @@ pub fn fn_ptr_abi(self) -> Result<FnAbi, Error> {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FnSig {
     pub inputs_and_output: Vec<Ty>,
     pub c_variadic: bool,
@@ -1025,6 +1056,7 @@ pub fn inputs(&self) -> &[Ty] {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Abi {
     Rust,
     C { unwind: bool },
@@ -1055,6 +1087,7 @@ pub enum Abi {
 
 /// A binder represents a possibly generic type and its bound vars.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Binder<T> {
     pub value: T,
     pub bound_vars: Vec<BoundVariableKind>,
@@ -1095,11 +1128,13 @@ pub fn map_bound<F, U>(self, f: F) -> Binder<U>
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EarlyBinder<T> {
     pub value: T,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BoundVariableKind {
     Ty(BoundTyKind),
     Region(BoundRegionKind),
@@ -1107,12 +1142,14 @@ pub enum BoundVariableKind {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BoundTyKind {
     Anon,
     Param(ParamDef, String),
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BoundRegionKind {
     BrAnon,
     BrNamed(BrNamedDef, String),
@@ -1120,12 +1157,14 @@ pub enum BoundRegionKind {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DynKind {
     Dyn,
     DynStar,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExistentialPredicate {
     Trait(ExistentialTraitRef),
     Projection(ExistentialProjection),
@@ -1136,6 +1175,7 @@ pub enum ExistentialPredicate {
 ///
 /// The `generic_args` will include any other known argument.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExistentialTraitRef {
     pub def_id: TraitDef,
     pub generic_args: GenericArgs,
@@ -1154,6 +1194,7 @@ pub fn with_self_ty(&self, self_ty: Ty) -> TraitRef {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExistentialProjection {
     pub def_id: TraitDef,
     pub generic_args: GenericArgs,
@@ -1161,12 +1202,14 @@ pub struct ExistentialProjection {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParamTy {
     pub index: u32,
     pub name: String,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoundTy {
     pub var: usize,
     pub kind: BoundTyKind,
@@ -1178,6 +1221,7 @@ pub struct BoundTy {
 pub type Size = usize;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Prov(pub AllocId);
 
 pub type Align = u64;
@@ -1186,6 +1230,7 @@ pub struct BoundTy {
 
 /// Stores the provenance information of pointers stored in memory.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProvenanceMap {
     /// Provenance in this map applies from the given offset for an entire pointer-size worth of
     /// bytes. Two entries in this map are always at least a pointer size apart.
@@ -1193,6 +1238,7 @@ pub struct ProvenanceMap {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Allocation {
     pub bytes: Bytes,
     pub provenance: ProvenanceMap,
@@ -1269,6 +1315,7 @@ pub fn is_null(&self) -> Result<bool, Error> {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConstantKind {
     Ty(TyConst),
     Allocated(Allocation),
@@ -1280,12 +1327,14 @@ pub enum ConstantKind {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ParamConst {
     pub index: u32,
     pub name: String,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UnevaluatedConst {
     pub def: ConstDef,
     pub args: GenericArgs,
@@ -1293,6 +1342,7 @@ pub struct UnevaluatedConst {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TraitSpecializationKind {
     None,
     Marker,
@@ -1300,6 +1350,7 @@ pub enum TraitSpecializationKind {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraitDecl {
     pub def_id: TraitDef,
     pub safety: Safety,
@@ -1333,6 +1384,7 @@ pub fn explicit_predicates_of(&self) -> GenericPredicates {
 
 /// A complete reference to a trait, i.e., one where `Self` is known.
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraitRef {
     pub def_id: TraitDef,
     /// The generic arguments for this definition.
@@ -1366,7 +1418,57 @@ pub fn self_ty(&self) -> Ty {
     }
 }
 
+/// The environment in which trait obligations are solved, i.e., the where-clauses that are
+/// assumed to hold.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ParamEnv(usize);
+
+impl ParamEnv {
+    /// The environment of the given item, where its where-clauses hold.
+    pub fn of(def_id: DefId) -> ParamEnv {
+        with(|cx| cx.param_env(def_id))
+    }
+
+    /// The environment of monomorphic code, where opaque types are revealed.
+    pub fn reveal_all() -> ParamEnv {
+        with(|cx| cx.param_env_reveal_all())
+    }
+
+    /// Whether `trait_ref` is known to hold in this environment, ignoring regions.
+    pub fn implements(self, trait_ref: &TraitRef) -> bool {
+        with(|cx| cx.predicate_must_hold_modulo_regions(self, trait_ref))
+    }
+
+    /// Normalize the projections in `ty` and erase its regions.
+    pub fn normalize_erasing_regions(self, ty: Ty) -> Result<Ty, Error> {
+        with(|cx| cx.normalize_erasing_regions(self, ty))
+    }
+
+    /// Whether the two types are equal once normalized, ignoring regions.
+    pub fn types_equal_modulo_regions(self, lhs: Ty, rhs: Ty) -> Result<bool, Error> {
+        Ok(self.normalize_erasing_regions(lhs)? == self.normalize_erasing_regions(rhs)?)
+    }
+
+    /// Whether `ty` is known to implement `Copy` in this environment, ignoring regions.
+    pub fn is_copy(self, ty: Ty) -> bool {
+        with(|cx| cx.ty_is_copy(self, ty))
+    }
+
+    /// Whether `ty` is known to be `Sized` in this environment.
+    pub fn is_sized(self, ty: Ty) -> bool {
+        with(|cx| cx.ty_is_sized(self, ty))
+    }
+
+    /// Whether dropping a value of type `ty` may run some code. This is conservative for types
+    /// that are not known in this environment.
+    pub fn needs_drop(self, ty: Ty) -> bool {
+        with(|cx| cx.ty_needs_drop(self, ty))
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Generics {
     pub parent: Option<GenericDef>,
     pub parent_count: usize,
@@ -1378,6 +1480,7 @@ pub struct Generics {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GenericParamDefKind {
     Lifetime,
     Type { has_default: bool, synthetic: bool },
@@ -1385,6 +1488,7 @@ pub enum GenericParamDefKind {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GenericParamDef {
     pub name: super::Symbol,
     pub def_id: GenericDef,
@@ -1399,6 +1503,7 @@ pub struct GenericPredicates {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PredicateKind {
     Clause(ClauseKind),
     ObjectSafe(TraitDef),
@@ -1410,6 +1515,7 @@ pub enum PredicateKind {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ClauseKind {
     Trait(TraitPredicate),
     RegionOutlives(RegionOutlivesPredicate),
@@ -1421,6 +1527,7 @@ pub enum ClauseKind {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ClosureKind {
     Fn,
     FnMut,
@@ -1428,42 +1535,49 @@ pub enum ClosureKind {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SubtypePredicate {
     pub a: Ty,
     pub b: Ty,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CoercePredicate {
     pub a: Ty,
     pub b: Ty,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AliasRelationDirection {
     Equate,
     Subtype,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TraitPredicate {
     pub trait_ref: TraitRef,
     pub polarity: PredicatePolarity,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutlivesPredicate<A, B>(pub A, pub B);
 
 pub type RegionOutlivesPredicate = OutlivesPredicate<Region, Region>;
 pub type TypeOutlivesPredicate = OutlivesPredicate<Ty, Region>;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProjectionPredicate {
     pub projection_term: AliasTerm,
     pub term: TermKind,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ImplPolarity {
     Positive,
     Negative,
@@ -1471,6 +1585,7 @@ pub enum ImplPolarity {
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PredicatePolarity {
     Positive,
     Negative,
@@ -1499,6 +1614,7 @@ fn to_index(&self) -> usize {
 index_impl!(MirConstId);
 index_impl!(Ty);
 index_impl!(Span);
+index_impl!(ParamEnv);
 
 /// The source-order index of a variant in a type.
 ///
@@ -1514,6 +1630,7 @@ fn to_index(&self) -> usize {
 /// `c` is in the variant with the `VariantIdx` of `1`, and
 /// `g` is in the variant with the `VariantIdx` of `0`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VariantIdx(usize);
 
 index_impl!(VariantIdx);
@@ -8,7 +8,7 @@
 
 #![deny(unsafe_code)]
 
-use crate::{Delimiter, Level, Spacing};
+use crate::{Applicability, Delimiter, Level, Spacing};
 use std::fmt;
 use std::hash::Hash;
 use std::marker;
@@ -57,7 +57,10 @@ macro_rules! with_api {
                 fn injected_env_var(var: &str) -> Option<String>;
                 fn track_env_var(var: &str, value: Option<&str>);
                 fn track_path(path: &str);
+                fn read_tracked_file(path: &str) -> Result<Vec<u8>, String>;
+                fn read_tracked_dir(path: &str) -> Result<Vec<String>, String>;
                 fn literal_from_str(s: &str) -> Result<Literal<$S::Span, $S::Symbol>, ()>;
+                fn literal_value(literal: Literal<$S::Span, $S::Symbol>) -> Result<LitValue, ()>;
                 fn emit_diagnostic(diagnostic: Diagnostic<$S::Span>);
             },
             TokenStream {
@@ -310,9 +313,12 @@ fn unmark(self) -> Self::Unmarked {
     &'_ str,
     String,
     u8,
+    u128,
     usize,
+    Applicability,
     Delimiter,
     LitKind,
+    LitValue,
     Level,
     Spacing,
 }
@@ -333,6 +339,14 @@ enum Level {
         Help,
     }
 );
+rpc_encode_decode!(
+    enum Applicability {
+        MachineApplicable,
+        MaybeIncorrect,
+        HasPlaceholders,
+        Unspecified,
+    }
+);
 rpc_encode_decode!(
     enum Spacing {
         Alone,
@@ -375,6 +389,36 @@ enum LitKind {
     }
 );
 
+/// The value of a literal, decoded by the server like the compiler decodes the literals of the
+/// source code.
+#[derive(Clone, PartialEq, Debug)]
+pub enum LitValue {
+    Str(String),
+    ByteStr(Vec<u8>),
+    /// The bytes of a C string, without the nul terminator.
+    CStr(Vec<u8>),
+    Byte(u8),
+    Char(char),
+    Int(u128),
+    /// The absolute value of a negative integer, like `-1` in `Literal::i32_unsuffixed(-1)`.
+    NegativeInt(u128),
+    /// The digits of a float, without the underscores and the suffix.
+    Float(String),
+}
+
+rpc_encode_decode!(
+    enum LitValue {
+        Str(s),
+        ByteStr(bytes),
+        CStr(bytes),
+        Byte(b),
+        Char(c),
+        Int(n),
+        NegativeInt(n),
+        Float(s),
+    }
+);
+
 macro_rules! mark_compound {
     (struct $name:ident <$($T:ident),+> { $($field:ident),* $(,)? }) => {
         impl<$($T: Mark),+> Mark for $name <$($T),+> {
@@ -518,16 +562,41 @@ enum TokenTree<TokenStream, Span, Symbol> {
     }
 );
 
+/// A message attached to a span, used both for the labels of a diagnostic and for the parts of a
+/// suggestion, where the message is the replacement code.
+#[derive(Clone, Debug)]
+pub struct SpanLabel<Span> {
+    pub span: Span,
+    pub message: String,
+}
+
+compound_traits!(struct SpanLabel<Span> { span, message });
+
+#[derive(Clone, Debug)]
+pub struct Suggestion<Span> {
+    pub message: String,
+    pub parts: Vec<SpanLabel<Span>>,
+    pub applicability: Applicability,
+}
+
+compound_traits!(
+    struct Suggestion<Span> { message, parts, applicability }
+);
+
 #[derive(Clone, Debug)]
 pub struct Diagnostic<Span> {
     pub level: Level,
     pub message: String,
     pub spans: Vec<Span>,
+    pub labels: Vec<SpanLabel<Span>>,
     pub children: Vec<Diagnostic<Span>>,
+    pub suggestions: Vec<Suggestion<Span>>,
+    /// Whether a warning should be emitted as a `proc_macro_warnings` lint.
+    pub lint: bool,
 }
 
 compound_traits!(
-    struct Diagnostic<Span> { level, message, spans, children }
+    struct Diagnostic<Span> { level, message, spans, labels, children, suggestions, lint }
 );
 
 /// Globals provided alongside the initial inputs for a macro expansion.
@@ -127,6 +127,7 @@ fn decode(r: &mut Reader<'_>, _: &mut S) -> Self {
 }
 
 rpc_encode_decode!(le u32);
+rpc_encode_decode!(le u128);
 rpc_encode_decode!(le usize);
 
 impl<S> Encode<S> for bool {
@@ -363,6 +363,151 @@ pub trait MessagePipe<T>: Sized {
     fn recv(&mut self) -> Option<T>;
 }
 
+/// Runs the client in a child process, so that the server can restrict what it
+/// has access to. The server side of the bridge stays in the current process,
+/// so handles never cross the process boundary: only the contents of buffers
+/// do, over a `ProcessPipe`.
+pub struct CrossProcess<P>(PhantomData<P>);
+
+impl<P> CrossProcess<P> {
+    pub const fn new() -> Self {
+        CrossProcess(PhantomData)
+    }
+}
+
+/// Runs the client with `CrossProcess<P>` if `cross_process` is set, and with
+/// `fallback` otherwise.
+pub struct MaybeCrossProcess<S, P> {
+    cross_process: bool,
+    fallback: S,
+    marker: PhantomData<P>,
+}
+
+impl<S, P> MaybeCrossProcess<S, P> {
+    pub const fn new(cross_process: bool, fallback: S) -> Self {
+        MaybeCrossProcess { cross_process, fallback, marker: PhantomData }
+    }
+}
+
+impl<S, P> ExecutionStrategy for MaybeCrossProcess<S, P>
+where
+    S: ExecutionStrategy,
+    P: ProcessPipe,
+{
+    fn run_bridge_and_client(
+        &self,
+        dispatcher: &mut impl DispatcherTrait,
+        input: Buffer,
+        run_client: extern "C" fn(BridgeConfig<'_>) -> Buffer,
+        force_show_panics: bool,
+    ) -> Buffer {
+        if self.cross_process {
+            <CrossProcess<P>>::new().run_bridge_and_client(
+                dispatcher,
+                input,
+                run_client,
+                force_show_panics,
+            )
+        } else {
+            self.fallback.run_bridge_and_client(dispatcher, input, run_client, force_show_panics)
+        }
+    }
+}
+
+/// Tags of the messages sent by the client process.
+const CLIENT_REQUEST: u8 = 0;
+const CLIENT_OUTPUT: u8 = 1;
+
+impl<P> ExecutionStrategy for CrossProcess<P>
+where
+    P: ProcessPipe,
+{
+    fn run_bridge_and_client(
+        &self,
+        dispatcher: &mut impl DispatcherTrait,
+        input: Buffer,
+        run_client: extern "C" fn(BridgeConfig<'_>) -> Buffer,
+        force_show_panics: bool,
+    ) -> Buffer {
+        let spawned = P::spawn(move |mut client| {
+            let mut dispatch = |b: Buffer| -> Buffer {
+                client.send(CLIENT_REQUEST, &b);
+                match client.recv() {
+                    Some(reply) => Buffer::from(reply),
+                    None => panic!("server died while client waiting for reply"),
+                }
+            };
+
+            let output = run_client(BridgeConfig {
+                input,
+                dispatch: (&mut dispatch).into(),
+                force_show_panics,
+                _marker: marker::PhantomData,
+            });
+            client.send(CLIENT_OUTPUT, &output);
+        });
+        let mut server = match spawned {
+            Ok(server) => server,
+            Err(message) => return encode_client_failure(message),
+        };
+
+        while let Some(mut message) = server.recv() {
+            match message.first() {
+                Some(&CLIENT_REQUEST) => {
+                    message.remove(0);
+                    let reply = dispatcher.dispatch(Buffer::from(message));
+                    server.send_reply(&reply);
+                }
+                Some(&CLIENT_OUTPUT) => {
+                    message.remove(0);
+                    return Buffer::from(message);
+                }
+                _ => {
+                    return encode_client_failure(
+                        "malformed message from the proc macro process".into(),
+                    )
+                }
+            }
+        }
+
+        encode_client_failure(server.exit_status())
+    }
+}
+
+/// Encodes the output of a client that failed to run to completion, as if it
+/// had panicked with `message`.
+fn encode_client_failure(message: String) -> Buffer {
+    let mut buf = Buffer::new();
+    Err::<(), _>(PanicMessage::String(message)).encode(&mut buf, &mut ());
+    buf
+}
+
+/// A pipe between the server and a client running in another process, used by
+/// `CrossProcess`.
+pub trait ProcessPipe: Sized {
+    /// Start a process that calls `client` with its endpoint of the pipe, and
+    /// exits when it returns, and return the endpoint of the current process.
+    ///
+    /// Returns an error message if the process could not be started.
+    fn spawn(client: impl FnOnce(Self)) -> Result<Self, String>;
+
+    /// Send a tagged message from the client to the server.
+    fn send(&mut self, tag: u8, bytes: &[u8]);
+
+    /// Send the reply to a request from the server to the client.
+    fn send_reply(&mut self, bytes: &[u8]);
+
+    /// Receive a message from the other endpoint of this pipe. Messages from
+    /// the client start with their tag.
+    ///
+    /// Returns `None` if the other end of the pipe has been closed.
+    fn recv(&mut self) -> Option<Vec<u8>>;
+
+    /// Wait for the client process to exit, and describe how it exited, once
+    /// the pipe has been closed before it sent its output.
+    fn exit_status(self) -> String;
+}
+
 fn run_server<
     S: Server,
     I: Encode<HandleStore<MarkedTypes<S>>>,
@@ -15,6 +15,24 @@ pub enum Level {
     Help,
 }
 
+/// How confident a macro is that a suggestion is what the user meant, which determines whether
+/// tools like `cargo fix` apply it.
+#[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended, and can be applied automatically.
+    MachineApplicable,
+    /// The suggestion may be what the user intended, but it is uncertain. It should compile if it
+    /// is applied.
+    MaybeIncorrect,
+    /// The suggestion contains placeholders like `(...)` or `{ /* fields */ }`, and won't compile
+    /// until the user replaces them.
+    HasPlaceholders,
+    /// The applicability of the suggestion is unknown.
+    Unspecified,
+}
+
 /// Trait implemented by types that can be converted into a set of `Span`s.
 #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
 pub trait MultiSpan {
@@ -51,7 +69,18 @@ pub struct Diagnostic {
     level: Level,
     message: String,
     spans: Vec<Span>,
+    labels: Vec<(Span, String)>,
     children: Vec<Diagnostic>,
+    suggestions: Vec<Suggestion>,
+    lint: bool,
+}
+
+/// A suggested change to the code, made of replacements of the code of some spans.
+#[derive(Clone, Debug)]
+struct Suggestion {
+    message: String,
+    parts: Vec<(Span, String)>,
+    applicability: Applicability,
 }
 
 macro_rules! diagnostic_child_methods {
@@ -97,7 +126,7 @@ impl Diagnostic {
     /// Creates a new diagnostic with the given `level` and `message`.
     #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
     pub fn new<T: Into<String>>(level: Level, message: T) -> Diagnostic {
-        Diagnostic { level, message: message.into(), spans: vec![], children: vec![] }
+        Diagnostic::spanned(vec![], level, message)
     }
 
     /// Creates a new diagnostic with the given `level` and `message` pointing to
@@ -108,7 +137,15 @@ pub fn spanned<S, T>(spans: S, level: Level, message: T) -> Diagnostic
         S: MultiSpan,
         T: Into<String>,
     {
-        Diagnostic { level, message: message.into(), spans: spans.into_spans(), children: vec![] }
+        Diagnostic {
+            level,
+            message: message.into(),
+            spans: spans.into_spans(),
+            labels: vec![],
+            children: vec![],
+            suggestions: vec![],
+            lint: false,
+        }
     }
 
     diagnostic_child_methods!(span_error, error, Level::Error);
@@ -116,6 +153,64 @@ pub fn spanned<S, T>(spans: S, level: Level, message: T) -> Diagnostic
     diagnostic_child_methods!(span_note, note, Level::Note);
     diagnostic_child_methods!(span_help, help, Level::Help);
 
+    /// Adds a label to `self`, which is shown next to `span` when the diagnostic is rendered.
+    ///
+    /// Unlike the spans of the diagnostic, which all point at the problem, the labels can point at
+    /// related code and explain how it is involved.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn span_label<T: Into<String>>(mut self, span: Span, message: T) -> Diagnostic {
+        self.labels.push((span, message.into()));
+        self
+    }
+
+    /// Adds a suggestion to `self` to replace the code of `span` with `replacement`.
+    ///
+    /// Suggestions that are `MachineApplicable` are applied by tools like `cargo fix`.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn span_suggestion<T, U>(
+        self,
+        span: Span,
+        message: T,
+        replacement: U,
+        applicability: Applicability,
+    ) -> Diagnostic
+    where
+        T: Into<String>,
+        U: Into<String>,
+    {
+        self.multipart_suggestion(message, vec![(span, replacement.into())], applicability)
+    }
+
+    /// Adds a suggestion to `self` to replace the code of several spans at once, like adding both
+    /// parentheses around an expression. The spans must not overlap.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn multipart_suggestion<T: Into<String>>(
+        mut self,
+        message: T,
+        parts: Vec<(Span, String)>,
+        applicability: Applicability,
+    ) -> Diagnostic {
+        self.suggestions.push(Suggestion { message: message.into(), parts, applicability });
+        self
+    }
+
+    /// Makes `self`, which must be a warning, a `proc_macro_warnings` lint, which users can
+    /// silence or deny with the usual lint attributes, like `#[allow(proc_macro_warnings)]` on an
+    /// item around the invocation of the macro.
+    ///
+    /// This has no effect on diagnostics that are not warnings, nor on the children diagnostics.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn as_lint(mut self) -> Diagnostic {
+        self.lint = true;
+        self
+    }
+
+    /// Returns whether `self` is emitted as a lint, see [`Diagnostic::as_lint`].
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn is_lint(&self) -> bool {
+        self.lint
+    }
+
     /// Returns the diagnostic `level` for `self`.
     #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
     pub fn level(&self) -> Level {
@@ -152,6 +247,12 @@ pub fn set_spans<S: MultiSpan>(&mut self, spans: S) {
         self.spans = spans.into_spans();
     }
 
+    /// Returns the labels of `self`, with their spans.
+    #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
+    pub fn labels(&self) -> &[(Span, String)] {
+        &self.labels
+    }
+
     /// Returns an iterator over the children diagnostics of `self`.
     #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
     pub fn children(&self) -> Children<'_> {
@@ -166,7 +267,29 @@ fn to_internal(diag: Diagnostic) -> crate::bridge::Diagnostic<crate::bridge::cli
                 level: diag.level,
                 message: diag.message,
                 spans: diag.spans.into_iter().map(|s| s.0).collect(),
+                labels: diag
+                    .labels
+                    .into_iter()
+                    .map(|(span, message)| crate::bridge::SpanLabel { span: span.0, message })
+                    .collect(),
                 children: diag.children.into_iter().map(to_internal).collect(),
+                suggestions: diag
+                    .suggestions
+                    .into_iter()
+                    .map(|suggestion| crate::bridge::Suggestion {
+                        message: suggestion.message,
+                        parts: suggestion
+                            .parts
+                            .into_iter()
+                            .map(|(span, message)| crate::bridge::SpanLabel {
+                                span: span.0,
+                                message,
+                            })
+                            .collect(),
+                        applicability: suggestion.applicability,
+                    })
+                    .collect(),
+                lint: diag.lint,
             }
         }
 
@@ -46,10 +46,10 @@
 mod escape;
 
 #[unstable(feature = "proc_macro_diagnostic", issue = "54140")]
-pub use diagnostic::{Diagnostic, Level, MultiSpan};
+pub use diagnostic::{Applicability, Diagnostic, Level, MultiSpan};
 
 use crate::escape::{escape_bytes, EscapeOptions};
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::ops::{Range, RangeBounds};
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -1173,6 +1173,64 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 #[stable(feature = "proc_macro_lib2", since = "1.29.0")]
 pub struct Literal(bridge::Literal<bridge::client::Span, bridge::client::Symbol>);
 
+/// The kind of a [`Literal`], as written in the source code.
+#[unstable(feature = "proc_macro_value", issue = "none")]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LiteralKind {
+    /// A byte character (`b'a'`).
+    Byte,
+    /// A character (`'a'`).
+    Char,
+    /// An integer (`1`, `0xff_u8`).
+    Integer,
+    /// A floating point number (`2.3`, `1e10f32`).
+    Float,
+    /// A string (`"hello"`).
+    Str,
+    /// A raw string (`r#"hello"#`), with the number of `#`s.
+    StrRaw(u8),
+    /// A byte string (`b"hello"`).
+    ByteStr,
+    /// A raw byte string (`br#"hello"#`), with the number of `#`s.
+    ByteStrRaw(u8),
+    /// A C string (`c"hello"`).
+    CStr,
+    /// A raw C string (`cr#"hello"#`), with the number of `#`s.
+    CStrRaw(u8),
+    /// A literal the compiler already reported an error for, like an unterminated string.
+    Err,
+}
+
+/// Error returned from the accessors of the value of a [`Literal`], like [`Literal::str_value`].
+#[unstable(feature = "proc_macro_value", issue = "none")]
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LiteralValueError {
+    /// The literal is not of a kind the accessor decodes, like a string for `int_value`.
+    WrongKind,
+    /// The literal is malformed, like a string with an unknown escape or an integer with an
+    /// invalid suffix. The compiler reports such literals as errors when they end up in the
+    /// expansion of the macro.
+    Invalid,
+    /// The value of the integer does not fit in the requested type.
+    OutOfRange,
+}
+
+#[unstable(feature = "proc_macro_value", issue = "none")]
+impl fmt::Display for LiteralValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LiteralValueError::WrongKind => "literal is not of the expected kind",
+            LiteralValueError::Invalid => "literal is malformed",
+            LiteralValueError::OutOfRange => "integer literal is out of range for the type",
+        })
+    }
+}
+
+#[unstable(feature = "proc_macro_value", issue = "none")]
+impl error::Error for LiteralValueError {}
+
 macro_rules! suffixed_int_literals {
     ($($name:ident => $kind:ident,)*) => ($(
         /// Creates a new suffixed integer literal with the specified value.
@@ -1443,6 +1501,148 @@ pub fn subspan<R: RangeBounds<usize>>(&self, range: R) -> Option<Span> {
         self.0.span.subspan(range.start_bound().cloned(), range.end_bound().cloned()).map(Span)
     }
 
+    /// Returns the kind of this literal.
+    ///
+    /// An integer with a float suffix, like `1f32`, is a float literal.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn kind(&self) -> LiteralKind {
+        if self.is_float() {
+            return LiteralKind::Float;
+        }
+        match self.0.kind {
+            bridge::LitKind::Byte => LiteralKind::Byte,
+            bridge::LitKind::Char => LiteralKind::Char,
+            bridge::LitKind::Integer => LiteralKind::Integer,
+            bridge::LitKind::Float => LiteralKind::Float,
+            bridge::LitKind::Str => LiteralKind::Str,
+            bridge::LitKind::StrRaw(n) => LiteralKind::StrRaw(n),
+            bridge::LitKind::ByteStr => LiteralKind::ByteStr,
+            bridge::LitKind::ByteStrRaw(n) => LiteralKind::ByteStrRaw(n),
+            bridge::LitKind::CStr => LiteralKind::CStr,
+            bridge::LitKind::CStrRaw(n) => LiteralKind::CStrRaw(n),
+            bridge::LitKind::ErrWithGuar => LiteralKind::Err,
+        }
+    }
+
+    /// Returns the suffix of this literal, like `u8` in `1u8`, if it has one.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn suffix(&self) -> Option<String> {
+        self.0.suffix.map(|suffix| suffix.with(|suffix| suffix.to_owned()))
+    }
+
+    /// Returns the value of a string or raw string literal, with its escapes decoded.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn str_value(&self) -> Result<String, LiteralValueError> {
+        match self
+            .value(|kind| matches!(kind, bridge::LitKind::Str | bridge::LitKind::StrRaw(_)))?
+        {
+            bridge::LitValue::Str(string) => Ok(string),
+            _ => Err(LiteralValueError::Invalid),
+        }
+    }
+
+    /// Returns the value of a byte string or raw byte string literal, with its escapes decoded.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn byte_str_value(&self) -> Result<Vec<u8>, LiteralValueError> {
+        match self.value(|kind| {
+            matches!(kind, bridge::LitKind::ByteStr | bridge::LitKind::ByteStrRaw(_))
+        })? {
+            bridge::LitValue::ByteStr(bytes) => Ok(bytes),
+            _ => Err(LiteralValueError::Invalid),
+        }
+    }
+
+    /// Returns the value of a C string or raw C string literal, with its escapes decoded.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn c_str_value(&self) -> Result<CString, LiteralValueError> {
+        match self
+            .value(|kind| matches!(kind, bridge::LitKind::CStr | bridge::LitKind::CStrRaw(_)))?
+        {
+            bridge::LitValue::CStr(bytes) => {
+                CString::new(bytes).map_err(|_| LiteralValueError::Invalid)
+            }
+            _ => Err(LiteralValueError::Invalid),
+        }
+    }
+
+    /// Returns the value of a character literal, with its escape decoded.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn char_value(&self) -> Result<char, LiteralValueError> {
+        match self.value(|kind| kind == bridge::LitKind::Char)? {
+            bridge::LitValue::Char(ch) => Ok(ch),
+            _ => Err(LiteralValueError::Invalid),
+        }
+    }
+
+    /// Returns the value of a byte character literal, with its escape decoded.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn byte_value(&self) -> Result<u8, LiteralValueError> {
+        match self.value(|kind| kind == bridge::LitKind::Byte)? {
+            bridge::LitValue::Byte(byte) => Ok(byte),
+            _ => Err(LiteralValueError::Invalid),
+        }
+    }
+
+    /// Returns the value of an integer literal as a `T`, whatever the base of the literal.
+    ///
+    /// The suffix of the literal is checked to be a valid integer suffix, but it does not need to
+    /// be `T`: `Literal::u8_suffixed(1).int_value::<u64>()` is `Ok(1)`. Use [`Literal::suffix`]
+    /// to check it.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn int_value<T: TryFrom<u128> + TryFrom<i128>>(&self) -> Result<T, LiteralValueError> {
+        match self.value(|kind| kind == bridge::LitKind::Integer && !self.is_float())? {
+            bridge::LitValue::Int(n) => {
+                <T as TryFrom<u128>>::try_from(n).map_err(|_| LiteralValueError::OutOfRange)
+            }
+            // Literals created from negative numbers, like `Literal::i32_unsuffixed(-1)`.
+            bridge::LitValue::NegativeInt(n) => 0i128
+                .checked_sub_unsigned(n)
+                .and_then(|n| <T as TryFrom<i128>>::try_from(n).ok())
+                .ok_or(LiteralValueError::OutOfRange),
+            _ => Err(LiteralValueError::Invalid),
+        }
+    }
+
+    /// Returns the value of a floating point literal, including an integer with a float suffix
+    /// like `1f32`.
+    ///
+    /// The value is rounded to the nearest `f64`, whatever the suffix. The compiler rounds the
+    /// literal to the type of its suffix instead, so for an `f32` literal, converting the result
+    /// to `f32` can differ from the value the compiler uses by one unit in the last place.
+    #[unstable(feature = "proc_macro_value", issue = "none")]
+    pub fn float_value(&self) -> Result<f64, LiteralValueError> {
+        match self.value(|_| self.is_float())? {
+            bridge::LitValue::Float(digits) => {
+                digits.parse().map_err(|_| LiteralValueError::Invalid)
+            }
+            _ => Err(LiteralValueError::Invalid),
+        }
+    }
+
+    /// Whether this is a float literal. The lexer sees an integer with a float suffix, like
+    /// `1f32`, as an integer, but the compiler gives it a float type.
+    fn is_float(&self) -> bool {
+        match self.0.kind {
+            bridge::LitKind::Float => true,
+            bridge::LitKind::Integer => self.0.suffix.is_some_and(|suffix| {
+                suffix.with(|suffix| matches!(suffix, "f16" | "f32" | "f64" | "f128"))
+            }),
+            _ => false,
+        }
+    }
+
+    /// Asks the server to decode the value of this literal, after checking its kind.
+    fn value(
+        &self,
+        expected_kind: impl FnOnce(bridge::LitKind) -> bool,
+    ) -> Result<bridge::LitValue, LiteralValueError> {
+        if !expected_kind(self.0.kind) {
+            return Err(LiteralValueError::WrongKind);
+        }
+        bridge::client::FreeFunctions::literal_value(self.0.clone())
+            .map_err(|()| LiteralValueError::Invalid)
+    }
+
     fn with_symbol_and_suffix<R>(&self, f: impl FnOnce(&str, &str) -> R) -> R {
         self.0.symbol.with(|symbol| match self.0.suffix {
             Some(suffix) => suffix.with(|suffix| f(symbol, suffix)),
@@ -1557,7 +1757,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 #[unstable(feature = "proc_macro_tracked_env", issue = "99515")]
 pub mod tracked_env {
     use std::env::{self, VarError};
-    use std::ffi::OsStr;
+    use std::ffi::{OsStr, OsString};
 
     /// Retrieve an environment variable and add it to build dependency info.
     /// The build system executing the compiler will know that the variable was accessed during
@@ -1572,11 +1772,30 @@ pub fn var<K: AsRef<OsStr> + AsRef<str>>(key: K) -> Result<String, VarError> {
         crate::bridge::client::FreeFunctions::track_env_var(key, value.as_deref().ok());
         value
     }
+
+    /// Retrieve an environment variable and add it to build dependency info, like [`var`], but
+    /// without requiring the value to be UTF-8.
+    ///
+    /// Besides the dependency tracking this function should be equivalent to `env::var_os` from
+    /// the standard library, except that the argument must be UTF-8.
+    #[unstable(feature = "proc_macro_tracked_env", issue = "99515")]
+    pub fn var_os<K: AsRef<OsStr> + AsRef<str>>(key: K) -> Option<OsString> {
+        let key: &str = key.as_ref();
+        let value = crate::bridge::client::FreeFunctions::injected_env_var(key)
+            .map(OsString::from)
+            .or_else(|| env::var_os(key));
+        // The dependency info only supports UTF-8 values.
+        let tracked_value = value.as_ref().map(|value| value.to_string_lossy());
+        crate::bridge::client::FreeFunctions::track_env_var(key, tracked_value.as_deref());
+        value
+    }
 }
 
 /// Tracked access to additional files.
 #[unstable(feature = "track_path", issue = "99515")]
 pub mod tracked_path {
+    use std::io;
+    use std::path::{Path, PathBuf};
 
     /// Track a file explicitly.
     ///
@@ -1586,4 +1805,40 @@ pub fn path<P: AsRef<str>>(path: P) {
         let path: &str = path.as_ref();
         crate::bridge::client::FreeFunctions::track_path(path);
     }
+
+    /// Reads the contents of a file through the compiler, and adds it to build dependency info.
+    /// The build system executing the compiler will know that the file was read during
+    /// compilation, and will be able to rerun the build when the file changes. The file is also
+    /// part of the inputs of incremental compilation, like the files included with
+    /// `include_bytes!`. A file that can't be read is added to the dependency info too, so that
+    /// the build reruns when it is created.
+    ///
+    /// Relative paths are relative to the current directory of the compiler, like for
+    /// `std::fs::read`, so consider making them absolute with `CARGO_MANIFEST_DIR`.
+    #[unstable(feature = "track_path", issue = "99515")]
+    pub fn read<P: AsRef<str>>(path: P) -> io::Result<Vec<u8>> {
+        crate::bridge::client::FreeFunctions::read_tracked_file(path.as_ref())
+            .map_err(io::Error::other)
+    }
+
+    /// Reads the contents of a file through the compiler as a string, and adds it to build
+    /// dependency info, like [`read`].
+    #[unstable(feature = "track_path", issue = "99515")]
+    pub fn read_to_string<P: AsRef<str>>(path: P) -> io::Result<String> {
+        String::from_utf8(read(path)?)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "file is not valid UTF-8"))
+    }
+
+    /// Lists the entries of a directory, and adds the directory to build dependency info, so that
+    /// the build system reruns the build when entries are added to or removed from it.
+    ///
+    /// The entries are sorted by name, and are returned as `path` joined with their name. The
+    /// contents of the entries are not tracked: read them with [`read`].
+    #[unstable(feature = "track_path", issue = "99515")]
+    pub fn read_dir<P: AsRef<str>>(path: P) -> io::Result<Vec<PathBuf>> {
+        let path: &str = path.as_ref();
+        let names = crate::bridge::client::FreeFunctions::read_tracked_dir(path)
+            .map_err(io::Error::other)?;
+        Ok(names.into_iter().map(|name| Path::new(path).join(name)).collect())
+    }
 }
@@ -0,0 +1,66 @@
+#!/usr/bin/env python
+
+"""
+This script merges the reports written by `rustc -Z dump-cfg-report` for several builds of the
+same crate, e.g. with different features or targets, and lists the code that is left out of all
+of them: the `#[cfg]` attributes whose predicate holds in none of the builds, and the
+`#[cfg_attr]` attributes that are never applied.
+
+With `--verbose`, it also lists, for each `#[cfg]` that holds in some builds but not all, the
+reports of the builds that compile the code under it.
+
+sample usage: src/etc/cfg-coverage.py default/foo.cfg-report.json all-features/foo.cfg-report.json
+"""
+
+import argparse
+import json
+import sys
+
+
+def load_reports(paths):
+    # For each attribute, by span, its kind, its predicate, and the reports where it holds.
+    attrs = {}
+    for path in paths:
+        with open(path) as f:
+            report = json.load(f)
+        for cfg in report["cfgs"]:
+            key = (cfg["span"], cfg["attr"])
+            attr = attrs.setdefault(key, {"predicate": cfg["predicate"], "active_in": []})
+            if cfg["active"]:
+                attr["active_in"].append(path)
+    return attrs
+
+
+def main():
+    parser = argparse.ArgumentParser(description=__doc__,
+                                     formatter_class=argparse.RawDescriptionHelpFormatter)
+    parser.add_argument("reports", nargs="+", help="the `.cfg-report.json` files to merge")
+    parser.add_argument("-v", "--verbose", action="store_true",
+                        help="also list the code compiled in some builds only")
+    args = parser.parse_args()
+
+    attrs = load_reports(args.reports)
+    never_compiled = [(span, attr) for (span, kind), attr in attrs.items()
+                      if kind == "cfg" and not attr["active_in"]]
+    never_applied = [(span, attr) for (span, kind), attr in attrs.items()
+                     if kind == "cfg_attr" and not attr["active_in"]]
+
+    print("never compiled in any of the {} builds:".format(len(args.reports)))
+    for span, attr in never_compiled:
+        print("  {}: #[cfg({})]".format(span, attr["predicate"]))
+    print("never applied in any of the {} builds:".format(len(args.reports)))
+    for span, attr in never_applied:
+        print("  {}: #[cfg_attr({}, ..)]".format(span, attr["predicate"]))
+
+    if args.verbose:
+        print("compiled in some builds only:")
+        for (span, kind), attr in attrs.items():
+            if kind == "cfg" and 0 < len(attr["active_in"]) < len(args.reports):
+                print("  {}: #[cfg({})] in {}".format(
+                    span, attr["predicate"], ", ".join(attr["active_in"])))
+
+    return 1 if never_compiled else 0
+
+
+if __name__ == "__main__":
+    sys.exit(main())
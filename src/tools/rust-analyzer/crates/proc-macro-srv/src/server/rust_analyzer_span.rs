@@ -66,6 +66,24 @@ fn track_path(&mut self, path: &str) {
         self.tracked_paths.insert(path.into());
     }
 
+    fn read_tracked_file(&mut self, path: &str) -> Result<Vec<u8>, String> {
+        self.tracked_paths.insert(path.into());
+        std::fs::read(path).map_err(|err| format!("couldn't read `{path}`: {err}"))
+    }
+
+    fn read_tracked_dir(&mut self, path: &str) -> Result<Vec<String>, String> {
+        self.tracked_paths.insert(path.into());
+        let mut names = std::fs::read_dir(path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                    .collect::<std::io::Result<Vec<_>>>()
+            })
+            .map_err(|err| format!("couldn't read `{path}`: {err}"))?;
+        names.sort_unstable();
+        Ok(names)
+    }
+
     fn literal_from_str(
         &mut self,
         s: &str,
@@ -137,6 +155,14 @@ fn literal_from_str(
         })
     }
 
+    fn literal_value(
+        &mut self,
+        _: bridge::Literal<Self::Span, Self::Symbol>,
+    ) -> Result<bridge::LitValue, ()> {
+        // FIXME: decode the literal like rustc does.
+        Err(())
+    }
+
     fn emit_diagnostic(&mut self, _: bridge::Diagnostic<Self::Span>) {
         // FIXME handle diagnostic
     }
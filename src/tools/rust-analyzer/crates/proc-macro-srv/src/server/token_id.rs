@@ -58,6 +58,20 @@ fn injected_env_var(&mut self, _: &str) -> Option<std::string::String> {
     }
     fn track_env_var(&mut self, _var: &str, _value: Option<&str>) {}
     fn track_path(&mut self, _path: &str) {}
+    fn read_tracked_file(&mut self, path: &str) -> Result<Vec<u8>, String> {
+        std::fs::read(path).map_err(|err| format!("couldn't read `{path}`: {err}"))
+    }
+    fn read_tracked_dir(&mut self, path: &str) -> Result<Vec<String>, String> {
+        let mut names = std::fs::read_dir(path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                    .collect::<std::io::Result<Vec<_>>>()
+            })
+            .map_err(|err| format!("couldn't read `{path}`: {err}"))?;
+        names.sort_unstable();
+        Ok(names)
+    }
     fn literal_from_str(
         &mut self,
         s: &str,
@@ -130,6 +144,14 @@ fn literal_from_str(
         })
     }
 
+    fn literal_value(
+        &mut self,
+        _: bridge::Literal<Self::Span, Self::Symbol>,
+    ) -> Result<bridge::LitValue, ()> {
+        // FIXME: decode the literal like rustc does.
+        Err(())
+    }
+
     fn emit_diagnostic(&mut self, _: bridge::Diagnostic<Self::Span>) {}
 }
 
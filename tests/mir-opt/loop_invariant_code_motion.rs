@@ -0,0 +1,60 @@
+//@ test-mir-pass: LoopInvariantCodeMotion
+// EMIT_MIR_FOR_EACH_PANIC_STRATEGY
+
+struct NotCopy(u32);
+
+#[inline(never)]
+fn opaque(x: u32) -> bool {
+    x == 0
+}
+
+// EMIT_MIR loop_invariant_code_motion.hoist_bounds_check.LoopInvariantCodeMotion.diff
+fn hoist_bounds_check(s: &[u32], k: usize) {
+    // CHECK-LABEL: fn hoist_bounds_check(
+    // CHECK: bb0: {
+    // CHECK: [[index:_.*]] = _2;
+    // CHECK: [[len:_.*]] = Len((*_1));
+    // CHECK: [[lt:_.*]] = Lt([[index]], [[len]]);
+    // CHECK: assert([[lt]], "index out of bounds: the length is {} but the index is {}", [[len]], [[index]]) -> [success: [[preheader:bb.*]], unwind
+    // CHECK: bb1: {
+    // CHECK-NOT: Len(
+    // CHECK-NOT: assert(
+    // CHECK: = opaque(
+    // CHECK: [[preheader]]: {
+    // CHECK-NEXT: goto -> bb1;
+    loop {
+        let x = s[k];
+        if opaque(x) {
+            break;
+        }
+    }
+}
+
+// EMIT_MIR loop_invariant_code_motion.conditional.LoopInvariantCodeMotion.diff
+fn conditional(x: u32, d: u32) {
+    // CHECK-LABEL: fn conditional(
+    // CHECK: bb0: {
+    // CHECK-NOT: move
+    // CHECK-NOT: Div(
+    // CHECK: goto -> bb1;
+    // CHECK: bb4: {
+    // CHECK: = move {{_.*}};
+    // CHECK: assert(!{{_.*}}, "attempt to divide `{}` by zero"
+    // CHECK: bb5: {
+    // CHECK-NEXT: = Div(
+    loop {
+        let a = NotCopy(x);
+        if opaque(d) {
+            break;
+        }
+        // Neither the move nor the division may happen before we know that the loop does not
+        // exit on its first iteration.
+        let _b = a;
+        let _q = x / d;
+    }
+}
+
+fn main() {
+    hoist_bounds_check(&[1, 2, 3], 1);
+    conditional(4, 2);
+}
@@ -0,0 +1,27 @@
+//! Checks that `-Zabi-baseline` reports the binary-incompatible changes between two builds
+//! of a `cdylib`, using the output of `--print=exported-abi` for the first one.
+
+//@ needs-dynamic-linking
+
+use run_make_support::rustc;
+
+fn main() {
+    rustc().input("v1.rs").arg("-Zunstable-options").print("exported-abi=v1.json").run();
+
+    // The baseline compared against itself has no changes.
+    rustc()
+        .input("v1.rs")
+        .arg("-Zabi-baseline=v1.json")
+        .run()
+        .assert_stderr_not_contains("ABI-breaking change");
+
+    rustc()
+        .input("v2.rs")
+        .arg("-Zabi-baseline=v1.json")
+        .run_fail()
+        .assert_stderr_contains("exported symbol `point_count` was removed")
+        .assert_stderr_contains("size of type `Point` changed from 8 to 16")
+        .assert_stderr_contains("size of field `x` of variant `Point` of type `Point` changed")
+        .assert_stderr_contains("offset of field `y` of variant `Point` of type `Point` changed")
+        .assert_stderr_contains("size of return value of function `point_sum` changed from 4 to 8");
+}
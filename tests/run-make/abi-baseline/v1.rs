@@ -0,0 +1,17 @@
+#![crate_type = "cdylib"]
+
+#[repr(C)]
+pub struct Point {
+    pub x: u32,
+    pub y: u32,
+}
+
+#[no_mangle]
+pub extern "C" fn point_sum(p: Point) -> u32 {
+    p.x + p.y
+}
+
+#[no_mangle]
+pub extern "C" fn point_count() -> u32 {
+    1
+}
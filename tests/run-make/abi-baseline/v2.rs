@@ -0,0 +1,12 @@
+#![crate_type = "cdylib"]
+
+#[repr(C)]
+pub struct Point {
+    pub x: u64,
+    pub y: u32,
+}
+
+#[no_mangle]
+pub extern "C" fn point_sum(p: Point) -> u64 {
+    p.x + p.y as u64
+}
@@ -0,0 +1,19 @@
+#[cfg(feature = "json")]
+pub fn to_json() -> String {
+    1
+}
+
+#[cfg(feature = "yaml")]
+pub fn to_yaml() -> String {
+    String::new()
+}
+
+#[cfg(not(any(feature = "json", feature = "yaml")))]
+pub fn to_text() -> String {
+    String::new()
+}
+
+#[cfg(feature = "with space")]
+pub fn spaced() -> String {
+    2
+}
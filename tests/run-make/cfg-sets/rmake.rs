@@ -0,0 +1,29 @@
+//! Checks that `-Zcfg-sets` type-checks the crate once per configuration listed in the file, and
+//! tags each diagnostic with the configuration it occurs in, while only parsing the crate once.
+
+use run_make_support::rustc;
+
+fn main() {
+    let output = rustc()
+        .input("foo.rs")
+        .crate_type("lib")
+        .arg("-Zcfg-sets=cfg-sets.txt")
+        .arg("-Ztime-passes")
+        .arg("-Ztime-passes-format=json")
+        .run_fail();
+    let stderr = output.stderr_utf8();
+
+    // `to_json` and `spaced` have a type error, and they're only checked with their feature. The
+    // `json` configuration is listed twice, but only checked once. The errors of a configuration
+    // don't stop the next ones from being checked.
+    assert_eq!(stderr.matches("error[E0308]: mismatched types").count(), 2, "{stderr}");
+    assert!(stderr.contains("note: in the configuration `feature=\"json\"`"), "{stderr}");
+    assert!(stderr.contains("note: in the configuration `feature=\"with space\"`"), "{stderr}");
+    assert!(!stderr.contains("feature=\"yaml\""), "{stderr}");
+    assert!(!stderr.contains("without additional cfgs"), "{stderr}");
+
+    // The crate is parsed once, and expanded in each of the four distinct configurations.
+    let passes = |name: &str| stderr.matches(&format!(r#"time: {{"pass":"{name}","#)).count();
+    assert_eq!(passes("parse_crate"), 1, "{stderr}");
+    assert_eq!(passes("macro_expand_crate"), 4, "{stderr}");
+}
@@ -0,0 +1,31 @@
+#![cfg_attr(feature = "json", allow(dead_code))]
+
+#[cfg(feature = "json")]
+pub fn to_json() {}
+
+#[cfg(feature = "yaml")]
+pub fn to_yaml() {}
+
+#[cfg(not(feature = "json"))]
+pub fn to_text() {}
+
+#[cfg_attr(feature = "json", derive(Debug))]
+pub struct Config {
+    #[cfg(all(feature = "json", feature = "yaml"))]
+    pub pretty: bool,
+}
+
+macro_rules! helper {
+    () => {
+        #[cfg(feature = "json")]
+        pub fn helper() {}
+    };
+}
+
+pub mod a {
+    helper!();
+}
+
+pub mod b {
+    helper!();
+}
@@ -0,0 +1,43 @@
+//! Checks that `-Zdump-cfg-report` records each `cfg` and `cfg_attr` evaluated with its predicate,
+//! and that `src/etc/cfg-coverage.py` finds the code that none of several builds compiles.
+
+use run_make_support::{fs_wrapper, python_command, rustc, source_root};
+
+fn main() {
+    rustc().input("foo.rs").crate_type("lib").arg("-Zdump-cfg-report=default").run();
+    rustc()
+        .input("foo.rs")
+        .crate_type("lib")
+        .cfg(r#"feature="json""#)
+        .arg("-Zdump-cfg-report=json")
+        .run();
+
+    let report = fs_wrapper::read_to_string("default/foo.cfg-report.json");
+    assert!(report.contains("\"crate_name\": \"foo\""), "{report}");
+    assert!(report.contains("\"predicate\": \"feature = \\\"json\\\"\""), "{report}");
+    assert!(report.contains("\"predicate\": \"not(feature = \\\"json\\\")\""), "{report}");
+    assert!(report.contains("\"attr\": \"cfg_attr\""), "{report}");
+    assert!(report.contains("\"span\": \"foo.rs:3:1: 3:25\""), "{report}");
+    assert!(report.contains("\"active\": true"), "{report}");
+    // The attributes of the crate are recorded too.
+    assert!(report.contains("\"span\": \"foo.rs:1:1: 1:49\""), "{report}");
+    // The `cfg` in `helper!` is reported once, even though the macro is expanded twice.
+    assert_eq!(report.matches("\"span\": \"foo.rs:20:9: 20:33\"").count(), 1, "{report}");
+
+    let report = fs_wrapper::read_to_string("json/foo.cfg-report.json");
+    assert!(report.contains("\"feature=\\\"json\\\"\""), "{report}");
+
+    // The `cfg` of `to_yaml` and of the `pretty` field hold in neither build, all the others
+    // hold in one of them.
+    let coverage = python_command()
+        .arg(source_root().join("src/etc/cfg-coverage.py"))
+        .arg("default/foo.cfg-report.json")
+        .arg("json/foo.cfg-report.json")
+        .run_fail();
+    let stdout = coverage.stdout_utf8();
+    assert!(stdout.contains("foo.rs:6:1: 6:25: #[cfg(feature = \"yaml\")]"), "{stdout}");
+    assert!(stdout.contains("#[cfg(all(feature = \"json\", feature = \"yaml\"))]"), "{stdout}");
+    assert!(!stdout.contains("to_text"), "{stdout}");
+    assert!(!stdout.contains("not(feature"), "{stdout}");
+    assert!(!stdout.contains("cfg_attr"), "{stdout}");
+}
@@ -0,0 +1,18 @@
+macro_rules! pair {
+    ($a:ident) => {
+        compile_error!("unreachable");
+    };
+    ($a:ident, $b:expr) => {
+        sum!($b, 1)
+    };
+}
+
+macro_rules! sum {
+    ($($x:expr),*) => {
+        0 $(+ $x)*
+    };
+}
+
+pub fn f() -> i32 {
+    pair!(x, 2)
+}
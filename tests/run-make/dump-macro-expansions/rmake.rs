@@ -0,0 +1,26 @@
+//! Checks that `-Zdump-macro-expansions` writes each step of the expansion of the declarative
+//! macros of the crate to a JSON file, including the arms that failed to match.
+
+use run_make_support::{fs_wrapper, rustc};
+
+fn main() {
+    rustc().input("foo.rs").crate_type("lib").arg("-Zdump-macro-expansions=expansions").run();
+
+    let trace = fs_wrapper::read_to_string("expansions/foo.macro-expansions.json");
+    assert!(trace.contains("\"crate_name\": \"foo\""), "{trace}");
+    assert!(trace.contains("\"macro_name\": \"pair\""), "{trace}");
+    assert!(trace.contains("\"input\": \"x, 2\""), "{trace}");
+    assert!(trace.contains("\"result\": \"failed\""), "{trace}");
+    assert!(trace.contains("\"message\": \"no rules expected the token `,`\""), "{trace}");
+    assert!(trace.contains("\"matched_arm\": 1"), "{trace}");
+    assert!(trace.contains("\"b\": \"2\""), "{trace}");
+    assert!(trace.contains("\"output\": \"sum!"), "{trace}");
+
+    // The expansion of `sum!` comes from the one of `pair!`, and binds a repetition.
+    assert!(trace.contains("\"macro_name\": \"sum\""), "{trace}");
+    assert!(
+        trace.matches("\"parent\": null").count() < trace.matches("\"parent\": ").count(),
+        "{trace}"
+    );
+    assert!(trace.contains("\"x\": [\n"), "{trace}");
+}
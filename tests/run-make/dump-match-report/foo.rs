@@ -0,0 +1,23 @@
+pub enum State {
+    Idle,
+    Running(u8),
+    Done,
+}
+
+pub fn step(state: State, ready: bool) -> State {
+    match state {
+        State::Idle if ready => State::Running(0),
+        State::Idle => State::Idle,
+        State::Running(255) => State::Done,
+        State::Running(n) => State::Running(n + 1),
+        State::Running(_) => State::Done,
+        State::Done => State::Done,
+    }
+}
+
+pub fn is_done(state: &State) -> bool {
+    match state {
+        State::Done => true,
+        State::Idle | State::Running(_) => false,
+    }
+}
@@ -0,0 +1,25 @@
+//! Checks that `-Zdump-match-report` writes the exhaustiveness and arm reachability of the matches
+//! of each body to a JSON file.
+
+use run_make_support::{fs_wrapper, rustc};
+
+fn main() {
+    rustc()
+        .input("foo.rs")
+        .crate_type("lib")
+        .arg("-Zdump-match-report=reports")
+        .run()
+        .assert_stderr_contains("unreachable pattern");
+
+    let step = fs_wrapper::read_to_string("reports/foo.step.matches.json");
+    assert!(step.contains("\"item\": \"step\""), "{step}");
+    assert!(step.contains("\"scrutinee_type\": \"State\""), "{step}");
+    assert!(step.contains("\"exhaustive\": true"), "{step}");
+    assert!(step.contains("\"pattern\": \"State::Running(_)\""), "{step}");
+    assert!(step.contains("\"reachability\": \"only_reachable_via_guards\""), "{step}");
+    assert!(step.contains("\"reachability\": \"redundant\""), "{step}");
+
+    let is_done = fs_wrapper::read_to_string("reports/foo.is_done.matches.json");
+    assert!(is_done.contains("\"missing_patterns\": []"), "{is_done}");
+    assert!(!is_done.contains("\"reachability\": \"redundant\""), "{is_done}");
+}
@@ -0,0 +1,3 @@
+pub fn longest<'a, 'b>(x: &'a str, y: &'b str) -> &'a str {
+    if x.len() > y.len() { x } else { y }
+}
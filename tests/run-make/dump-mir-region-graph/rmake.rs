@@ -0,0 +1,37 @@
+//! Checks that `-Zdump-mir-region-graph` dumps the region constraint graph of the functions
+//! selected by `-Zdump-mir`, and highlights the constraints that cause their region errors.
+
+use std::path::PathBuf;
+
+use run_make_support::{fs_wrapper, read_dir, rustc};
+
+fn dump_region_graph(format: &str) -> String {
+    rustc()
+        .input("foo.rs")
+        .crate_type("lib")
+        .arg("-Zdump-mir=nll & longest")
+        .arg(format!("-Zdump-mir-dir={format}"))
+        .arg(format!("-Zdump-mir-region-graph={format}"))
+        .run_fail()
+        .assert_stderr_contains("lifetime may not live long enough");
+
+    let mut dumps: Vec<PathBuf> = vec![];
+    read_dir(format, |path| {
+        if path.to_str().unwrap().ends_with(&format!(".regioncx.explain.{format}")) {
+            dumps.push(path.to_owned());
+        }
+    });
+    assert_eq!(dumps.len(), 1, "{dumps:?}");
+    fs_wrapper::read_to_string(&dumps[0])
+}
+
+fn main() {
+    let html = dump_region_graph("html");
+    assert!(html.contains("must outlive"), "{html}");
+    assert!(html.contains("class=\"constraint blamed\""), "{html}");
+    assert!(html.contains("in the type of `y`"), "{html}");
+
+    let dot = dump_region_graph("dot");
+    assert!(dot.starts_with("digraph RegionConstraints"), "{dot}");
+    assert!(dot.contains("style=\"bold\""), "{dot}");
+}
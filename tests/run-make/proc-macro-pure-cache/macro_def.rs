@@ -0,0 +1,18 @@
+#![feature(proc_macro_pure, proc_macro_tracked_env)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+#[proc_macro_derive(Greet)]
+#[proc_macro_pure]
+pub fn derive_greet(input: TokenStream) -> TokenStream {
+    eprintln!("running derive_greet");
+    let name = input
+        .into_iter()
+        .skip_while(|tree| !matches!(tree, TokenTree::Ident(ident) if ident.to_string() == "struct"))
+        .nth(1)
+        .unwrap();
+    let greeting = tracked_env::var("GREETING").unwrap();
+    format!("impl {name} {{ const GREETING: &str = \"{greeting}\"; }}").parse().unwrap()
+}
@@ -0,0 +1,9 @@
+#[macro_use]
+extern crate macro_def;
+
+#[derive(Greet)]
+struct World;
+
+fn main() {
+    println!("{}", World::GREETING);
+}
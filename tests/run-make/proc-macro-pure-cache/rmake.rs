@@ -0,0 +1,44 @@
+//! Checks that the expansions of `#[proc_macro_pure]` macros are cached in the incremental
+//! directory, and reused while the input and the tracked inputs of the macro don't change. A
+//! cache file that wasn't fully written is ignored.
+
+//@ ignore-cross-compile
+
+use std::path::{Path, PathBuf};
+
+use run_make_support::{fs_wrapper, read_dir, rustc};
+
+fn compile(greeting: &str) -> String {
+    rustc().input("macro_use.rs").incremental("incr").env("GREETING", greeting).run().stderr_utf8()
+}
+
+fn find_cache_files(dir: &Path, found: &mut Vec<PathBuf>) {
+    read_dir(dir, |path| {
+        if path.is_dir() {
+            find_cache_files(path, found);
+        } else if path.file_name().unwrap() == "proc-macro-expansions.bin" {
+            found.push(path.to_owned());
+        }
+    });
+}
+
+fn main() {
+    rustc().input("macro_def.rs").run();
+
+    assert!(compile("hello").contains("running derive_greet"));
+    assert!(!compile("hello").contains("running derive_greet"));
+    // The macro reads `GREETING` with `tracked_env`, so changing it invalidates the expansion.
+    assert!(compile("goodbye").contains("running derive_greet"));
+    assert!(!compile("goodbye").contains("running derive_greet"));
+
+    // Truncate the cache, like a write that failed halfway would, in every session directory.
+    let mut cache_files = Vec::new();
+    find_cache_files(Path::new("incr"), &mut cache_files);
+    assert!(!cache_files.is_empty());
+    for cache_file in cache_files {
+        let contents = fs_wrapper::read(&cache_file);
+        fs_wrapper::write(&cache_file, &contents[..contents.len() / 2]);
+    }
+    assert!(compile("goodbye").contains("running derive_greet"));
+    assert!(!compile("goodbye").contains("running derive_greet"));
+}
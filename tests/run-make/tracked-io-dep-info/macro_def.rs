@@ -0,0 +1,15 @@
+#![feature(proc_macro_tracked_env, track_path)]
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+use proc_macro::*;
+
+#[proc_macro]
+pub fn read_tracked_inputs(_: TokenStream) -> TokenStream {
+    assert_eq!(tracked_path::read_to_string("data/a.txt").unwrap(), "hello\n");
+    let entries = tracked_path::read_dir("data").unwrap();
+    assert_eq!(entries, ["data/a.txt", "data/b.txt"].map(std::path::PathBuf::from));
+    assert!(tracked_path::read("data/missing.txt").is_err());
+    assert_eq!(tracked_env::var_os("TRACKED_ENV").unwrap(), "1");
+    TokenStream::new()
+}
@@ -0,0 +1,6 @@
+#[macro_use]
+extern crate macro_def;
+
+read_tracked_inputs!();
+
+fn main() {}
@@ -0,0 +1,21 @@
+//! Checks that the files, directories and environment variables that a proc macro reads through
+//! `proc_macro::tracked_path` and `proc_macro::tracked_env` end up in the dep-info, including the
+//! files that it fails to read.
+
+//@ ignore-cross-compile
+
+use run_make_support::{fs_wrapper, rustc};
+
+fn main() {
+    rustc().input("macro_def.rs").run();
+    rustc().input("macro_use.rs").emit("dep-info").env("TRACKED_ENV", "1").run();
+
+    let deps = fs_wrapper::read_to_string("macro_use.d");
+    assert!(deps.contains("data/a.txt"), "{deps}");
+    assert!(!deps.contains("data/b.txt"), "{deps}");
+    // The macro's output may depend on the file not existing, so the build system must rerun it
+    // when the file is created.
+    assert!(deps.lines().any(|line| line == "data/missing.txt:"), "{deps}");
+    assert!(deps.lines().any(|line| line == "data:"), "{deps}");
+    assert!(deps.contains("# env-dep:TRACKED_ENV=1"), "{deps}");
+}
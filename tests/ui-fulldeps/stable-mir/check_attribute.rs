@@ -0,0 +1,114 @@
+//@ run-pass
+//! Test that users are able to retrieve the attributes and doc comments of items.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate stable_mir;
+
+use rustc_smir::rustc_internal;
+use stable_mir::crate_def::Attribute;
+use stable_mir::*;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_attributes() -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let item = |name: &str| items.iter().find(|item| item.name() == name).unwrap();
+
+    let documented = item("documented");
+    let attrs = documented.all_attrs();
+    assert_eq!(attrs.len(), 3, "{attrs:?}");
+    assert_eq!(attrs[0].path, ["doc"]);
+    assert_eq!(attrs[0].doc.as_deref(), Some(" First line."));
+    assert_eq!(attrs[0].tokens, r#"= " First line.""#);
+    assert_eq!(attrs[0].value, "/// First line.");
+    assert_eq!(attrs[1].doc.as_deref(), Some("Second line."));
+    assert_eq!(attrs[1].value, r#"#[doc = "Second line."]"#);
+    assert_eq!(attrs[2].path, ["inline"]);
+    assert_eq!(attrs[2].tokens, "");
+    assert!(!attrs[2].is_doc());
+    assert_eq!(documented.documentation().as_deref(), Some(" First line.\nSecond line."));
+
+    let annotated = item("annotated");
+    let tool_attrs = annotated.attrs_by_path(&["rustfmt".to_string(), "skip".to_string()]);
+    assert_eq!(tool_attrs.len(), 1);
+    assert_eq!(tool_attrs[0].value, "#[rustfmt::skip]");
+    let cold = annotated.attrs_by_path(&["cold".to_string()]);
+    check_single(&cold, "#[cold]");
+    let allow = annotated.attrs_by_path(&["allow".to_string()]);
+    assert_eq!(allow.len(), 1);
+    assert_eq!(allow[0].tokens, "(unused_variables)");
+    assert_eq!(annotated.documentation(), None);
+
+    // Attributes are also available for items without a body.
+    let Some(ty::RigidTy::Adt(adt, _)) = documented.body().ret_local().ty.kind().rigid().cloned()
+    else {
+        unreachable!()
+    };
+    check_single(&adt.attrs_by_path(&["repr".to_string()]), "#[repr(C)]");
+    assert_eq!(adt.documentation().as_deref(), Some(" A dummy type."));
+
+    ControlFlow::Continue(())
+}
+
+fn check_single(attrs: &[Attribute], expected: &str) {
+    assert_eq!(attrs.len(), 1, "{attrs:?}");
+    assert_eq!(attrs[0].value, expected);
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "attribute_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_attributes).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        /// A dummy type.
+        #[repr(C)]
+        pub struct Dummy(u32);
+
+        /// First line.
+        #[doc = "Second line."]
+        #[inline]
+        pub fn documented() -> Dummy {{
+            Dummy(0)
+        }}
+
+        #[rustfmt::skip]
+        #[cold]
+        #[allow(unused_variables)]
+        pub fn annotated(x: u32) {{}}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//@ run-pass
+//! Test that a body transformed through StableMIR is the one used for code generation.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_session;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_interface::interface::Config;
+use rustc_middle::util::Providers;
+use rustc_session::Session;
+use rustc_smir::rustc_internal::transform::set_body_transform;
+use stable_mir::mir::visit::Location;
+use stable_mir::mir::{Body, MutMirVisitor};
+use stable_mir::ty::{MirConst, RigidTy, TyKind, UintTy};
+use stable_mir::{CrateDef, CrateItem};
+use std::io::Write;
+use std::process::Command;
+
+const CRATE_NAME: &str = "input";
+
+struct TransformCallbacks;
+
+impl rustc_driver::Callbacks for TransformCallbacks {
+    fn config(&mut self, config: &mut Config) {
+        assert!(config.override_queries.is_none());
+        config.override_queries = Some(override_queries);
+    }
+}
+
+fn override_queries(_session: &Session, providers: &mut Providers) {
+    set_body_transform(providers, transform);
+}
+
+/// Replace every `u32` constant in `answer` by `42`, and leave the other bodies untouched.
+fn transform(item: CrateItem, body: &Body) -> Option<Body> {
+    if item.name() != "answer" {
+        return None;
+    }
+    let mut body = body.clone();
+    ReplaceU32Consts.visit_body(&mut body);
+    Some(body)
+}
+
+struct ReplaceU32Consts;
+
+impl MutMirVisitor for ReplaceU32Consts {
+    fn visit_mir_const(&mut self, constant: &mut MirConst, _location: Location) {
+        if constant.ty().kind() == TyKind::RigidTy(RigidTy::Uint(UintTy::U32)) {
+            *constant = MirConst::try_from_uint(42, UintTy::U32).unwrap();
+        }
+    }
+}
+
+fn main() {
+    let path = "body_transform_input.rs";
+    let output = "body_transform_output";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "-o".to_string(),
+        output.to_string(),
+        path.to_string(),
+    ];
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::RunCompiler::new(&args, &mut TransformCallbacks).run()
+    })
+    .unwrap()
+    .unwrap();
+
+    let status = Command::new(format!("./{output}")).status().unwrap();
+    assert_eq!(status.code(), Some(42));
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        #[inline(never)]
+        fn answer() -> u32 {{
+            1
+        }}
+
+        fn main() {{
+            std::process::exit(answer() as i32);
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,129 @@
+//@ run-pass
+//! Test that a body first requested from within a StableMIR call, while a body transform is
+//! registered, is returned untransformed instead of causing an ICE.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate rustc_middle;
+extern crate rustc_session;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_driver::Compilation;
+use rustc_interface::interface::{Compiler, Config};
+use rustc_interface::Queries;
+use rustc_middle::util::Providers;
+use rustc_session::Session;
+use rustc_smir::rustc_internal;
+use rustc_smir::rustc_internal::transform::set_body_transform;
+use stable_mir::mir::visit::Location;
+use stable_mir::mir::{Body, MirVisitor, MutMirVisitor};
+use stable_mir::ty::{ConstantKind, MirConst, RigidTy, TyKind, UintTy};
+use stable_mir::{CrateDef, CrateItem};
+use std::io::Write;
+
+const CRATE_NAME: &str = "input";
+
+struct TransformCallbacks;
+
+impl rustc_driver::Callbacks for TransformCallbacks {
+    fn config(&mut self, config: &mut Config) {
+        assert!(config.override_queries.is_none());
+        config.override_queries = Some(override_queries);
+    }
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &Compiler,
+        queries: &'tcx Queries<'tcx>,
+    ) -> Compilation {
+        queries.global_ctxt().unwrap().enter(|tcx| {
+            rustc_internal::run(tcx, || {
+                let items = stable_mir::all_local_items();
+                let answer = items.iter().find(|item| item.name() == "answer").unwrap();
+                // The optimized MIR of `answer` is computed here, while the tables are borrowed.
+                let mut consts = U32Consts(Vec::new());
+                consts.visit_body(&answer.body());
+                assert_eq!(consts.0, [1]);
+            })
+            .unwrap();
+        });
+        Compilation::Stop
+    }
+}
+
+fn override_queries(_session: &Session, providers: &mut Providers) {
+    set_body_transform(providers, transform);
+}
+
+/// Replace every `u32` constant in `answer` by `42`, and leave the other bodies untouched.
+fn transform(item: CrateItem, body: &Body) -> Option<Body> {
+    if item.name() != "answer" {
+        return None;
+    }
+    let mut body = body.clone();
+    ReplaceU32Consts.visit_body(&mut body);
+    Some(body)
+}
+
+struct ReplaceU32Consts;
+
+impl MutMirVisitor for ReplaceU32Consts {
+    fn visit_mir_const(&mut self, constant: &mut MirConst, _location: Location) {
+        if constant.ty().kind() == TyKind::RigidTy(RigidTy::Uint(UintTy::U32)) {
+            *constant = MirConst::try_from_uint(42, UintTy::U32).unwrap();
+        }
+    }
+}
+
+/// Collects the values of the `u32` constants of a body.
+struct U32Consts(Vec<u128>);
+
+impl MirVisitor for U32Consts {
+    fn visit_mir_const(&mut self, constant: &MirConst, _location: Location) {
+        if constant.ty().kind() != TyKind::RigidTy(RigidTy::Uint(UintTy::U32)) {
+            return;
+        }
+        if let ConstantKind::Allocated(allocation) = constant.kind() {
+            self.0.push(allocation.read_uint().unwrap());
+        }
+    }
+}
+
+fn main() {
+    let path = "body_transform_nested_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_driver::RunCompiler::new(&args, &mut TransformCallbacks).run()
+    })
+    .unwrap()
+    .unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub fn answer() -> u32 {{
+            1
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,132 @@
+//@ run-pass
+//! Test that users are able to run dataflow analyses on stable MIR bodies.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate stable_mir;
+
+use rustc_smir::rustc_internal;
+use stable_mir::mir::dataflow::{
+    Analysis, BitSet, BodyLocation, MaybeBorrowedLocals, MaybeInitializedLocals, MaybeLiveLocals,
+    Results, START_BLOCK,
+};
+use stable_mir::mir::{Body, TerminatorKind};
+use stable_mir::*;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_dataflow() -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let body = |name: &str| items.iter().find(|item| item.name() == name).unwrap().body();
+
+    // Only the first argument is read.
+    let first = body("first");
+    let mut live = MaybeLiveLocals.iterate_to_fixpoint(&first);
+    let entry = live.state_before(&first, BodyLocation { block: START_BLOCK, statement_index: 0 });
+    assert!(entry.contains(1), "{entry:?}");
+    assert!(!entry.contains(2), "{entry:?}");
+
+    // `let _ = y;` is kept as a `PlaceMention`, which reads `y`.
+    let mentioned = body("mentioned");
+    let mut live = MaybeLiveLocals.iterate_to_fixpoint(&mentioned);
+    let entry =
+        live.state_before(&mentioned, BodyLocation { block: START_BLOCK, statement_index: 0 });
+    assert!(entry.contains(2), "{entry:?}");
+
+    // The argument is moved into the return value.
+    let moved = body("moved");
+    let mut init = MaybeInitializedLocals.iterate_to_fixpoint(&moved);
+    let start = init.entry_state(START_BLOCK);
+    assert!(!start.contains(0), "{start:?}");
+    assert!(start.contains(1), "{start:?}");
+    for state in states_at_return(&mut init, &moved) {
+        assert!(state.contains(0), "{state:?}");
+        assert!(!state.contains(1), "{state:?}");
+    }
+
+    // The argument is borrowed, but not the return value.
+    let borrowed = body("borrowed");
+    let mut borrows = MaybeBorrowedLocals.iterate_to_fixpoint(&borrowed);
+    assert!(borrows.entry_state(START_BLOCK).is_empty());
+    for state in states_at_return(&mut borrows, &borrowed) {
+        assert!(state.contains(1), "{state:?}");
+        assert!(!state.contains(0), "{state:?}");
+    }
+
+    ControlFlow::Continue(())
+}
+
+/// Returns the state of the analysis before each `Return` terminator of `body`.
+fn states_at_return<A: Analysis<Domain = BitSet>>(
+    results: &mut Results<A>,
+    body: &Body,
+) -> Vec<BitSet> {
+    let states: Vec<_> = (0..body.blocks.len())
+        .filter(|&block| matches!(body.blocks[block].terminator.kind, TerminatorKind::Return))
+        .map(|block| results.state_before(body, BodyLocation::terminator(body, block)))
+        .collect();
+    assert!(!states.is_empty());
+    states
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "dataflow_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "-Cpanic=abort".to_string(),
+        "-Zmir-keep-place-mention".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_dataflow).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub fn first(x: u32, _y: u32) -> u32 {{
+            x
+        }}
+
+        pub fn mentioned(x: u32, y: u32) -> u32 {{
+            let _ = y;
+            x
+        }}
+
+        pub fn moved(s: String) -> String {{
+            s
+        }}
+
+        pub fn borrowed(x: u32) -> u32 {{
+            let r = &x;
+            std::hint::black_box(r);
+            *r
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//@ run-pass
+//! Test that users are able to take a snapshot of a crate, serialize it and read it back.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate stable_mir;
+
+use rustc_smir::rustc_internal;
+use stable_mir::snapshot::{CrateSnapshot, SNAPSHOT_FORMAT_VERSION};
+use stable_mir::ty::{RigidTy, TyKind};
+use stable_mir::ItemKind;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function takes a snapshot of the test crate and checks its contents.
+fn test_snapshot() -> ControlFlow<()> {
+    let snapshot = CrateSnapshot::collect();
+    assert_eq!(snapshot.format_version, SNAPSHOT_FORMAT_VERSION);
+    assert_eq!(snapshot.crate_name, CRATE_NAME);
+    assert!(snapshot.entry.is_none());
+
+    // Local items keep their polymorphic bodies.
+    let generic = snapshot.items.iter().find(|item| item.name == "generic").unwrap();
+    assert_eq!(generic.kind, ItemKind::Fn);
+    assert!(generic.body.is_some());
+
+    // Only the monomorphic roots and their callees are instances.
+    let instance_names: Vec<_> = snapshot.instances.iter().map(|i| i.name.as_str()).collect();
+    assert!(instance_names.contains(&"entry"), "{instance_names:?}");
+    assert!(instance_names.contains(&"generic::<u8>"), "{instance_names:?}");
+    assert!(!instance_names.contains(&"generic"), "{instance_names:?}");
+    assert!(snapshot.instances.iter().all(|instance| instance.body.is_some()));
+
+    // Every type handle is resolved, and monomorphic types have a layout.
+    let wrapper = snapshot
+        .types
+        .iter()
+        .find(|ty| match &ty.kind {
+            TyKind::RigidTy(RigidTy::Adt(def, _)) => {
+                snapshot.defs.iter().any(|d| d.id == def.0 && d.name == "Wrapper")
+            }
+            _ => false,
+        })
+        .unwrap();
+    let layout = wrapper.layout.unwrap();
+    assert!(snapshot.layouts.iter().any(|l| l.id == layout));
+    for item in &snapshot.items {
+        assert!(snapshot.types.iter().any(|ty| ty.id == item.ty));
+        assert!(snapshot.spans.iter().any(|span| span.id == item.span));
+    }
+
+    // Both formats start with the format version.
+    let mut json = vec![];
+    snapshot.write_json(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.starts_with(&format!("{{\n  \"format_version\": {SNAPSHOT_FORMAT_VERSION},")));
+
+    let mut cbor = vec![];
+    snapshot.write_cbor(&mut cbor).unwrap();
+    // A map with 9 entries, whose first key is the text "format_version".
+    assert_eq!(cbor[0], 0xa9);
+    assert_eq!(cbor[1], 0x60 | "format_version".len() as u8);
+    assert_eq!(&cbor[2..16], b"format_version");
+    assert_eq!(cbor[16], SNAPSHOT_FORMAT_VERSION as u8);
+
+    // Reading the JSON snapshot back gives the same snapshot, in both formats.
+    let read = CrateSnapshot::read_json(json.as_bytes()).unwrap();
+    assert_eq!(read.crate_name, snapshot.crate_name);
+    assert_eq!(read.items.len(), snapshot.items.len());
+    let mut read_json = vec![];
+    read.write_json(&mut read_json).unwrap();
+    assert_eq!(String::from_utf8(read_json).unwrap(), json);
+    let mut read_cbor = vec![];
+    read.write_cbor(&mut read_cbor).unwrap();
+    assert_eq!(read_cbor, cbor);
+
+    // Snapshots of another format version are rejected.
+    let other_version = json.replacen(
+        &format!("\"format_version\": {SNAPSHOT_FORMAT_VERSION}"),
+        &format!("\"format_version\": {}", SNAPSHOT_FORMAT_VERSION + 1),
+        1,
+    );
+    let err = CrateSnapshot::read_json(other_version.as_bytes()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "snapshot_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_snapshot).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub struct Wrapper(pub u64, pub bool);
+
+        pub fn generic<T: Copy>(t: T) -> (T, T) {{
+            (t, t)
+        }}
+
+        pub fn entry(wrapper: Wrapper) -> u64 {{
+            let (a, _) = generic(wrapper.1 as u8);
+            wrapper.0 + a as u64
+        }}
+        "#
+    )?;
+    Ok(())
+}
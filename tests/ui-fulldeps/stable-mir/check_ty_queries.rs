@@ -0,0 +1,143 @@
+//@ run-pass
+//! Test that users are able to query trait solving and type relations in a param-env.
+
+//@ ignore-stage1
+//@ ignore-cross-compile
+//@ ignore-remote
+//@ ignore-windows-gnu mingw has troubles with linking https://github.com/rust-lang/rust/pull/116837
+//@ edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+#![feature(control_flow_enum)]
+
+#[macro_use]
+extern crate rustc_smir;
+extern crate rustc_driver;
+extern crate rustc_interface;
+extern crate stable_mir;
+
+use rustc_smir::rustc_internal;
+use stable_mir::ty::{GenericArgs, ParamEnv, RigidTy, TraitRef, Ty, TyKind, UintTy};
+use stable_mir::{CrateDef, CrateItem};
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to query properties of the types in the test crate.
+fn test_ty_queries() -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let env = ParamEnv::reveal_all();
+
+    // Trait solving.
+    let shape = stable_mir::local_crate()
+        .trait_decls()
+        .into_iter()
+        .find(|trait_def| trait_def.name() == "Shape")
+        .unwrap();
+    let [square, owned] = inputs(get_item(&items, "concrete"))[..] else { unreachable!() };
+    assert!(env.implements(&TraitRef::new(shape, square, &GenericArgs(vec![]))));
+    assert!(!env.implements(&TraitRef::new(shape, owned, &GenericArgs(vec![]))));
+
+    assert!(!env.is_copy(square));
+    assert!(env.is_sized(square));
+    assert!(!env.needs_drop(square));
+    assert!(env.needs_drop(owned));
+
+    // Normalization.
+    let area = fn_output(get_item(&items, "area_of"));
+    assert_matches!(area.kind(), TyKind::Alias(..));
+    let normalized = env.normalize_erasing_regions(area).unwrap();
+    assert_eq!(normalized.kind(), TyKind::RigidTy(RigidTy::Uint(UintTy::U32)));
+    assert!(env.types_equal_modulo_regions(area, Ty::unsigned_ty(UintTy::U32)).unwrap());
+
+    // Equality modulo regions.
+    let [holder] = inputs(get_item(&items, "holder"))[..] else { unreachable!() };
+    let TyKind::RigidTy(RigidTy::Adt(def, _)) = holder.kind() else { unreachable!() };
+    let fields = def.variants()[0].fields();
+    let (short, long) = (fields[0].ty(), fields[1].ty());
+    assert_ne!(short, long);
+    assert!(env.types_equal_modulo_regions(short, long).unwrap());
+    assert!(!env.types_equal_modulo_regions(short, square).unwrap());
+
+    // The where-clauses of an item only hold in its own param-env.
+    let generic = get_item(&items, "generic");
+    let [param] = inputs(generic)[..] else { unreachable!() };
+    assert!(ParamEnv::of(generic.def_id()).is_copy(param));
+    assert!(!env.is_copy(param));
+
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a stable_mir::CrateItems, name: &str) -> &'a CrateItem {
+    items.iter().find(|item| item.name() == name).unwrap()
+}
+
+fn inputs(item: &CrateItem) -> Vec<Ty> {
+    item.ty().kind().fn_sig().unwrap().skip_binder().inputs().to_vec()
+}
+
+fn fn_output(item: &CrateItem) -> Ty {
+    item.ty().kind().fn_sig().unwrap().skip_binder().output()
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "ty_queries_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    run!(args, test_ty_queries).unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+        pub trait Shape {{
+            type Area;
+            fn area(&self) -> Self::Area;
+        }}
+
+        pub struct Square(u32);
+
+        impl Shape for Square {{
+            type Area = u32;
+            fn area(&self) -> u32 {{
+                self.0 * self.0
+            }}
+        }}
+
+        pub struct Owned(String);
+
+        pub struct Holder<'a> {{
+            pub short: &'a u32,
+            pub long: &'static u32,
+        }}
+
+        pub fn concrete(_square: Square, _owned: Owned) {{}}
+
+        pub fn area_of(square: Square) -> <Square as Shape>::Area {{
+            square.area()
+        }}
+
+        pub fn holder(_holder: Holder<'_>) {{}}
+
+        pub fn generic<T: Copy>(t: T) -> (T, T) {{
+            (t, t)
+        }}
+        "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,25 @@
+//@ build-pass
+//@ compile-flags: -Zprint-const-eval-profile
+//@ normalize-stdout-test "\d+\.\d{3}ms" -> "$$TIMEms"
+
+// Each call and each loop iteration counts as a step, and is charged to the function that
+// makes it.
+
+#![crate_type = "lib"]
+
+const fn square(x: u32) -> u32 {
+    x * x
+}
+
+const fn sum_squares(n: u32) -> u32 {
+    let mut total = 0;
+    let mut i = 0;
+    while i < n {
+        total += square(i);
+        i += 1;
+    }
+    total
+}
+
+pub const TABLE: u32 = sum_squares(10);
+pub const SMALL: u32 = sum_squares(2);
@@ -0,0 +1,10 @@
+#[format_string(fmt, args)] //~ ERROR the `#[format_string]` attribute is an experimental feature
+macro_rules! log {
+    ($fmt:literal $(, $args:expr)* $(,)?) => {
+        println!($fmt $(, $args)*)
+    };
+}
+
+fn main() {
+    log!("{}", 1);
+}
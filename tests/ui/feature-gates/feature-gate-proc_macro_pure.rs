@@ -0,0 +1,14 @@
+//@ force-host
+//@ no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+
+#[proc_macro]
+#[proc_macro_pure] //~ ERROR the `#[proc_macro_pure]` attribute is an experimental feature
+pub fn identity(input: TokenStream) -> TokenStream {
+    input
+}
@@ -0,0 +1,22 @@
+// Checks that the names captured by the format string given to a macro declared with
+// `#[format_string]` are resolved at the call of the macro, whether the call expands to
+// statements or to an expression, even if the expansion doesn't use the format string.
+
+#![feature(macro_format_string)]
+
+#[format_string(fmt, args)]
+macro_rules! log {
+    ($target:expr, $fmt:literal $(, $args:expr)* $(,)?) => {{
+        let _ = ($target, stringify!($fmt));
+        $(let _ = $args;)*
+    }};
+}
+
+fn main() {
+    let x = 1;
+    log!("app", "{x}");
+    log!("app", "{missing}");
+    //~^ ERROR cannot find value `missing` in this scope
+    let () = log!("app", "{x} and {other} and {}", x);
+    //~^ ERROR cannot find value `other` in this scope
+}
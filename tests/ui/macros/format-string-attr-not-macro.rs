@@ -0,0 +1,10 @@
+#![feature(macro_format_string)]
+
+#[format_string(fmt)] //~ ERROR `#[format_string]` may only be used on declarative macros
+fn log(fmt: &str) {
+    println!("{fmt}");
+}
+
+fn main() {
+    log("{}");
+}
@@ -0,0 +1,35 @@
+// Checks that the calls of a macro with `#[format_string]` are checked like calls of
+// `format_args!`.
+
+#![feature(macro_format_string)]
+
+#[format_string(fmt, args)]
+macro_rules! log {
+    ($target:expr, $fmt:literal $(, $args:expr)* $(,)?) => {{
+        let _ = ($target, stringify!($fmt));
+        $(let _ = $args;)*
+    }};
+}
+
+#[format_string(query)] //~ ERROR no rule of this macro binds a metavariable `$query`
+macro_rules! sql {
+    ($fmt:literal) => {};
+}
+
+#[format_string(fmt = "a")] //~ ERROR `#[format_string]` expects the name of the metavariable
+macro_rules! trace {
+    ($fmt:literal) => {};
+}
+
+fn main() {
+    let x = 1;
+    log!(x, "{x} and {}", 2);
+    log!("app", "{} and {}", 1);
+    //~^ ERROR 2 positional arguments in format string, but there is 1 argument
+    log!("app", "{}", 1, 2);
+    //~^ ERROR argument never used
+    log!("app", "{:?");
+    //~^ ERROR invalid format string
+    sql!("{}");
+    trace!("{}");
+}
@@ -0,0 +1,23 @@
+//@ compile-flags: -Zmacro-match-details
+// Checks that `-Zmacro-match-details` explains why each rule failed to match, and which
+// metavariables are ambiguous.
+
+macro_rules! pair {
+    ($a:ident) => {};
+    ($a:ident, $b:ident) => {};
+    ($a:literal; $b:literal) => {};
+}
+
+macro_rules! ambiguous {
+    ($($a:ident)* $b:ident) => {};
+}
+
+macro_rules! list {
+    ($($a:ident),* ;) => {};
+}
+
+fn main() {
+    pair!(x, 1); //~ ERROR no rules expected the token `1`
+    ambiguous!(x y); //~ ERROR local ambiguity when calling macro `ambiguous`
+    list!(x y); //~ ERROR no rules expected the token `y`
+}
@@ -0,0 +1,86 @@
+//@ run-pass
+//@ needs-unwind
+//@ compile-flags: -Zmir-opt-level=2
+//@ compile-flags: -Zvalidate-mir -Zinline-mir
+
+// Check that hoisting loop-invariant code does not change the behavior of loops whose body is
+// not executed, and that hoisted bounds checks still panic when they should.
+
+use std::panic;
+
+#[inline(never)]
+fn repeat_index(s: &[u32], k: usize, n: usize) -> u32 {
+    let mut total = 0;
+    let mut i = 0;
+    loop {
+        // The bounds check is executed on each iteration before anything else, so it can be
+        // checked once before the loop.
+        let x = s[k];
+        if i == n {
+            break;
+        }
+        total += x;
+        i += 1;
+    }
+    total
+}
+
+#[inline(never)]
+fn conditional_index(s: &[u32], k: usize, n: usize) -> u32 {
+    let mut total = 0;
+    for _ in 0..n {
+        total += s[k];
+    }
+    total
+}
+
+#[inline(never)]
+fn guarded_division(x: u32, d: u32, n: u32) -> u32 {
+    let mut total = 0;
+    for _ in 0..n {
+        if d != 0 {
+            total += x / d;
+        }
+    }
+    total
+}
+
+#[inline(never)]
+fn guarded_variant(o: Option<u32>, n: u32) -> u32 {
+    let mut total = 0;
+    for _ in 0..n {
+        if let Some(v) = o {
+            total += v;
+        }
+    }
+    total
+}
+
+#[inline(never)]
+fn nested(rows: &[[u32; 3]], col: usize) -> u32 {
+    let mut total = 0;
+    for row in rows {
+        for _ in 0..rows.len() {
+            total += row[col] * (rows.len() as u32 + 1);
+        }
+    }
+    total
+}
+
+fn main() {
+    assert_eq!(repeat_index(&[1, 2, 3], 1, 4), 8);
+    assert!(panic::catch_unwind(|| repeat_index(&[1, 2, 3], 3, 4)).is_err());
+
+    assert_eq!(conditional_index(&[1, 2, 3], 2, 2), 6);
+    assert_eq!(conditional_index(&[], 3, 0), 0);
+    assert!(panic::catch_unwind(|| conditional_index(&[], 3, 1)).is_err());
+
+    assert_eq!(guarded_division(12, 4, 3), 9);
+    assert_eq!(guarded_division(12, 0, 3), 0);
+
+    assert_eq!(guarded_variant(Some(2), 3), 6);
+    assert_eq!(guarded_variant(None, 3), 0);
+
+    assert_eq!(nested(&[[1, 2, 3], [4, 5, 6]], 1), 42);
+    assert_eq!(nested(&[], 5), 0);
+}
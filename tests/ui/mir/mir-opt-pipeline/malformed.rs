@@ -0,0 +1,5 @@
+// Checks that a custom MIR pipeline that can't be parsed is reported, with the reason.
+//
+//@ compile-flags: -Zmir-opt-pipeline={{src-base}}/mir/mir-opt-pipeline/malformed.txt
+
+fn main() {}
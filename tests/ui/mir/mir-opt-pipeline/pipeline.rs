@@ -0,0 +1,21 @@
+// Checks that a custom MIR optimization pipeline is run, and the MIR validated after each pass.
+//
+//@ run-pass
+//@ compile-flags: -Zmir-opt-pipeline={{src-base}}/mir/mir-opt-pipeline/pipeline.txt
+
+#[inline]
+fn square(x: u32) -> u32 {
+    x * x
+}
+
+fn sum_of_squares(values: &[u32]) -> u32 {
+    let mut sum = 0;
+    for &value in values {
+        sum += square(value);
+    }
+    sum
+}
+
+fn main() {
+    assert_eq!(sum_of_squares(&[1, 2, 3]), 14);
+}
@@ -0,0 +1,6 @@
+// Checks that options not supported by a pass of a custom MIR pipeline are reported.
+//
+//@ build-fail
+//@ compile-flags: -Zmir-opt-pipeline={{src-base}}/mir/mir-opt-pipeline/unknown-option.txt
+
+fn main() {}
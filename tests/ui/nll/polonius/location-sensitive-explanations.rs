@@ -0,0 +1,32 @@
+// Check that the errors emitted with the location-sensitive analysis of `-Zpolonius=next` have the
+// same explanations as NLL: these loans are live at the point of the error on all paths.
+
+//@ revisions: nll polonius
+//@ [polonius] compile-flags: -Zpolonius=next
+
+fn used_later() {
+    let mut x = 0;
+    let r = &x;
+    x += 1;
+    //~^ ERROR cannot assign to `x` because it is borrowed
+    drop(r);
+}
+
+fn used_later_on_one_path(c: bool) {
+    let mut x = 0;
+    let r = &x;
+    if c {
+        x += 1;
+        //~^ ERROR cannot assign to `x` because it is borrowed
+        drop(r);
+    }
+}
+
+fn returned<'a>(v: &'a mut Vec<u32>) -> &'a u32 {
+    let first = &v[0];
+    v.push(0);
+    //~^ ERROR cannot borrow `*v` as mutable because it is also borrowed as immutable
+    first
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+// This is NLL problem case #3: a loan flowing into the return value on a single path of a
+// conditional return. NLL rejects it, while the location-sensitive analysis of `-Zpolonius=next`
+// accepts it: the loan only flows into the free region `'r` on the path where it is returned.
+
+//@ revisions: nll polonius
+//@ [polonius] check-pass
+//@ [polonius] compile-flags: -Zpolonius=next
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+fn get_default<'r, K: Hash + Eq + Copy, V: Default>(
+    map: &'r mut HashMap<K, V>,
+    key: K,
+) -> &'r mut V {
+    match map.get_mut(&key) {
+        Some(value) => value,
+        None => {
+            map.insert(key, V::default());
+            //[nll]~^ ERROR cannot borrow `*map` as mutable more than once at a time
+            map.get_mut(&key).unwrap()
+        }
+    }
+}
+
+fn first_or_insert<'a>(v: &'a mut Vec<u32>) -> &'a u32 {
+    if let Some(first) = v.first() {
+        return first;
+    }
+    v.push(0);
+    //[nll]~^ ERROR cannot borrow `*v` as mutable because it is also borrowed as immutable
+    &v[0]
+}
+
+fn main() {}
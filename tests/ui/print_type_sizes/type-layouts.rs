@@ -0,0 +1,29 @@
+//! This file checks the JSON rendering of layouts by `--print=type-layouts`.
+//!
+//! It avoids pointers and `u64`/`i64` so that the output does not depend
+//! on the target's pointer width or integer alignment.
+//!
+//@ compile-flags: --print=type-layouts -Z unstable-options --crate-type lib
+//@ ignore-debug: debug assertions will print more types
+//@ build-pass
+//@ ignore-pass
+//  ^-- needed because `--pass check` does not emit the output needed.
+#![allow(dead_code)]
+
+#[derive(Default)]
+#[repr(C)]
+pub struct Ffi {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+
+pub enum Tagged {
+    A(u32),
+    B(u8),
+}
+
+pub fn test() {
+    let _ffi = Ffi::default();
+    let _tagged = Tagged::A(0);
+}
@@ -1,10 +1,11 @@
 // ignore-tidy-linelength
 
-use proc_macro::Literal;
+use proc_macro::{Literal, LiteralKind, LiteralValueError};
 
 pub fn test() {
     test_display_literal();
     test_parse_literal();
+    test_literal_value();
 }
 
 fn test_display_literal() {
@@ -81,3 +82,41 @@ fn test_parse_literal() {
     assert!("- 10".parse::<Literal>().is_err());
     assert!("-'x'".parse::<Literal>().is_err());
 }
+
+fn test_literal_value() {
+    fn lit(s: &str) -> Literal {
+        s.parse().unwrap()
+    }
+
+    assert_eq!(lit("r#\"a\"#").kind(), LiteralKind::StrRaw(1));
+    assert_eq!(lit("1u8").kind(), LiteralKind::Integer);
+    assert_eq!(lit("1f32").kind(), LiteralKind::Float);
+    assert_eq!(lit("1u8").suffix().as_deref(), Some("u8"));
+    assert_eq!(lit("1").suffix(), None);
+
+    assert_eq!(lit(r#""a\tb\u{2764}""#).str_value(), Ok("a\tb\u{2764}".to_string()));
+    assert_eq!(lit(r##"r#"a\tb"#"##).str_value(), Ok(r"a\tb".to_string()));
+    assert_eq!(Literal::string("\"\0").str_value(), Ok("\"\0".to_string()));
+    assert_eq!(lit(r#""\q""#).str_value(), Err(LiteralValueError::Invalid));
+    assert_eq!(lit("1").str_value(), Err(LiteralValueError::WrongKind));
+
+    assert_eq!(lit(r#"b"\xff\n""#).byte_str_value(), Ok(vec![0xff, b'\n']));
+    assert_eq!(lit(r#"br"\xff""#).byte_str_value(), Ok(br"\xff".to_vec()));
+    assert_eq!(lit(r#"c"a\u{2764}""#).c_str_value().unwrap().as_c_str(), c"a\u{2764}");
+    assert_eq!(lit(r"'\x7f'").char_value(), Ok('\x7f'));
+    assert_eq!(lit(r"b'\xff'").byte_value(), Ok(0xff));
+
+    assert_eq!(lit("0x_ff_u8").int_value::<u8>(), Ok(255));
+    assert_eq!(lit("0b1010").int_value::<i64>(), Ok(10));
+    assert_eq!(lit("256").int_value::<u8>(), Err(LiteralValueError::OutOfRange));
+    assert_eq!(lit("1ulong").int_value::<u8>(), Err(LiteralValueError::Invalid));
+    assert_eq!(Literal::i8_unsuffixed(-128).int_value::<i8>(), Ok(-128));
+    assert_eq!(Literal::i32_suffixed(-1).int_value::<u32>(), Err(LiteralValueError::OutOfRange));
+    assert_eq!(Literal::u128_unsuffixed(u128::MAX).int_value::<u128>(), Ok(u128::MAX));
+
+    assert_eq!(lit("1_000.5e-1f32").float_value(), Ok(100.05));
+    assert_eq!(Literal::f64_unsuffixed(-2.5).float_value(), Ok(-2.5));
+    assert_eq!(lit("1").float_value(), Err(LiteralValueError::WrongKind));
+    assert_eq!(lit("3f64").float_value(), Ok(3.0));
+    assert_eq!(lit("3f64").int_value::<u8>(), Err(LiteralValueError::WrongKind));
+}
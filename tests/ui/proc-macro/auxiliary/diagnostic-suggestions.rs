@@ -0,0 +1,41 @@
+//@ force-host
+//@ no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_diagnostic)]
+
+extern crate proc_macro;
+
+use proc_macro::{Applicability, Diagnostic, Level, TokenStream, TokenTree};
+
+#[proc_macro]
+pub fn snake_case(input: TokenStream) -> TokenStream {
+    for tree in input {
+        let TokenTree::Ident(ident) = tree else { continue };
+        let name = ident.to_string();
+        let lowercase = name.to_lowercase();
+        if name != lowercase {
+            Diagnostic::spanned(ident.span(), Level::Error, "identifier is not in snake case")
+                .span_label(ident.span(), "contains uppercase letters")
+                .span_suggestion(
+                    ident.span(),
+                    "use lowercase letters",
+                    lowercase,
+                    Applicability::MachineApplicable,
+                )
+                .emit();
+        }
+    }
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn deprecated_syntax(input: TokenStream) -> TokenStream {
+    let span = input.into_iter().next().unwrap().span();
+    Diagnostic::spanned(span, Level::Warning, "this syntax is deprecated")
+        .span_label(span, "deprecated")
+        .help("remove it")
+        .as_lint()
+        .emit();
+    TokenStream::new()
+}
@@ -0,0 +1,52 @@
+//@ force-host
+//@ no-prefer-dynamic
+
+#![crate_type = "proc-macro"]
+#![feature(proc_macro_span, rustc_private, track_path)]
+
+extern crate libc;
+extern crate proc_macro;
+
+use proc_macro::{tracked_path, Span, TokenStream};
+
+#[proc_macro]
+pub fn probe_sandbox(_: TokenStream) -> TokenStream {
+    let path = Span::call_site().source_file().path();
+    let path = path.to_str().unwrap();
+    let can_open_files = std::fs::File::open(path).is_ok();
+    let can_read_tracked_files =
+        tracked_path::read(path).is_ok_and(|source| source.starts_with(b"// Checks that"));
+    let can_use_network = std::net::UdpSocket::bind("127.0.0.1:0").is_ok();
+    let can_run_programs = std::process::Command::new("true").status().is_ok();
+    let can_use_io_uring = {
+        // `struct io_uring_params` is 120 bytes long.
+        let mut params = [0u8; 120];
+        // SAFETY: `params` is large enough for the kernel to write the parameters of the ring.
+        let fd = unsafe { libc::syscall(libc::SYS_io_uring_setup, 1, params.as_mut_ptr()) };
+        fd >= 0
+    };
+    format!(
+        "const CAN_OPEN_FILES: bool = {can_open_files};
+         const CAN_READ_TRACKED_FILES: bool = {can_read_tracked_files};
+         const CAN_USE_NETWORK: bool = {can_use_network};
+         const CAN_RUN_PROGRAMS: bool = {can_run_programs};
+         const CAN_USE_IO_URING: bool = {can_use_io_uring};"
+    )
+    .parse()
+    .unwrap()
+}
+
+#[proc_macro]
+pub fn open_file(_: TokenStream) -> TokenStream {
+    std::fs::File::open("/proc/self/status").expect("could not open a file");
+    TokenStream::new()
+}
+
+#[proc_macro]
+pub fn make_x32_syscall(_: TokenStream) -> TokenStream {
+    // The x32 ABI of x86_64 has the same system calls under other numbers.
+    const X32_SYSCALL_BIT: libc::c_long = 0x4000_0000;
+    // SAFETY: `getpid` has no arguments and no side effects.
+    unsafe { libc::syscall(X32_SYSCALL_BIT | libc::SYS_getpid) };
+    TokenStream::new()
+}
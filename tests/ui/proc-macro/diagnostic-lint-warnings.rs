@@ -0,0 +1,18 @@
+//@ aux-build:diagnostic-suggestions.rs
+
+#[macro_use]
+extern crate diagnostic_suggestions;
+
+deprecated_syntax!(a); //~ WARN this syntax is deprecated
+
+#[allow(proc_macro_warnings)]
+mod allowed {
+    deprecated_syntax!(a);
+}
+
+#[deny(proc_macro_warnings)]
+mod denied {
+    deprecated_syntax!(a); //~ ERROR this syntax is deprecated
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+//@ aux-build:diagnostic-suggestions.rs
+//@ run-rustfix
+
+extern crate diagnostic_suggestions;
+
+use diagnostic_suggestions::snake_case;
+
+snake_case!(foo_bar);
+snake_case!(FooBar); //~ ERROR identifier is not in snake case
+
+fn main() {}
@@ -0,0 +1,14 @@
+// Checks that a proc macro making a system call of the x32 ABI, which kills the
+// `-Zproc-macro-sandbox` process, is reported like a panic of the macro, with the reason.
+
+//@ aux-build:sandbox.rs
+//@ only-linux
+//@ only-x86_64
+//@ compile-flags: -Zproc-macro-sandbox
+
+extern crate sandbox;
+
+sandbox::make_x32_syscall!();
+//~^ ERROR proc macro panicked
+
+fn main() {}
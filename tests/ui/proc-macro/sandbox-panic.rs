@@ -0,0 +1,17 @@
+// Checks that a proc macro that panics in the `-Zproc-macro-sandbox` process, here because a
+// system call it needs is denied, is reported like a panic of the macro.
+
+//@ aux-build:sandbox.rs
+//@ needs-unwind proc macro panics to report errors
+//@ only-linux
+//@ revisions: x86_64 aarch64
+//@[x86_64] only-x86_64
+//@[aarch64] only-aarch64
+//@ compile-flags: -Zproc-macro-sandbox
+
+extern crate sandbox;
+
+sandbox::open_file!();
+//~^ ERROR proc macro panicked
+
+fn main() {}
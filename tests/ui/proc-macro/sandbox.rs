@@ -0,0 +1,24 @@
+// Checks that `-Zproc-macro-sandbox` runs proc macros in a process that can't open files, use the
+// network, run programs or make system calls that aren't needed to talk to the compiler, like the
+// ones of io_uring, but can still talk to the compiler, and read files through it with
+// `tracked_path`.
+
+//@ aux-build:sandbox.rs
+//@ check-pass
+//@ only-linux
+//@ revisions: x86_64 aarch64
+//@[x86_64] only-x86_64
+//@[aarch64] only-aarch64
+//@ compile-flags: -Zproc-macro-sandbox
+
+extern crate sandbox;
+
+sandbox::probe_sandbox!();
+
+const _: () = assert!(!CAN_OPEN_FILES);
+const _: () = assert!(CAN_READ_TRACKED_FILES);
+const _: () = assert!(!CAN_USE_NETWORK);
+const _: () = assert!(!CAN_RUN_PROGRAMS);
+const _: () = assert!(!CAN_USE_IO_URING);
+
+fn main() {}
@@ -0,0 +1,25 @@
+//@ check-pass
+//@ compile-flags: -Zreport-transmute-safety
+// Checks that `-Zreport-transmute-safety` reports which transmutes the safe transmute analysis can
+// prove safe, and explains why it can't prove the others.
+
+use std::mem::transmute;
+
+#[repr(C)]
+struct Padded(u8, u16);
+
+fn main() {
+    unsafe {
+        let _: i8 = transmute(1u8);
+        //~^ NOTE proves that this transmute from `u8` to `i8` is safe
+        let _: bool = transmute(2u8);
+        //~^ NOTE can't prove that this transmute from `u8` to `bool` is safe
+        //~| NOTE at least one value of `u8` isn't a bit-valid value of `bool` (at byte offset 0)
+        let _: u32 = transmute(Padded(0, 0));
+        //~^ NOTE can't prove that this transmute from `Padded` to `u32` is safe
+        //~| NOTE the byte at offset 1 of `Padded` may be uninitialized
+        let _: &i8 = transmute(&1u8);
+        //~^ NOTE proves that this transmute from `&u8` to `&i8` is safe
+        //~| NOTE the analysis doesn't check lifetimes
+    }
+}